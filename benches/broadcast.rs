@@ -1,6 +1,10 @@
+use std::{
+    sync::{Arc, Barrier},
+    time::{Duration, Instant},
+};
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use postage::broadcast;
-use postage::{sink::Sink, stream::Stream};
+use postage::broadcast::{self, OverflowPolicy};
 #[derive(Clone, Debug)]
 struct Message;
 
@@ -37,5 +41,41 @@ pub fn recv_empty(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, send_recv, send_full, recv_empty);
+const CONTENDED_WRITERS: usize = 8;
+
+pub fn send_contended(c: &mut Criterion) {
+    // writers never wait for the receiver, so the benchmark measures contention on the write path
+    let (tx, _rx) = broadcast::channel_with_policy::<Message>(64, OverflowPolicy::DropOldest);
+
+    c.bench_function("broadcast::send_contended", |b| {
+        b.iter_custom(|iters| {
+            let barrier = Arc::new(Barrier::new(CONTENDED_WRITERS + 1));
+
+            let threads: Vec<_> = (0..CONTENDED_WRITERS)
+                .map(|_| {
+                    let mut tx = tx.clone();
+                    let barrier = barrier.clone();
+
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        for _ in 0..iters {
+                            tx.try_send(black_box(Message {})).unwrap();
+                        }
+                    })
+                })
+                .collect();
+
+            barrier.wait();
+            let start = Instant::now();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+
+            let elapsed = start.elapsed();
+            Duration::from_nanos((elapsed.as_nanos() / CONTENDED_WRITERS as u128) as u64)
+        });
+    });
+}
+
+criterion_group!(benches, send_recv, send_full, recv_empty, send_contended);
 criterion_main!(benches);
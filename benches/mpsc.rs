@@ -1,6 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use postage::mpsc;
-use postage::{sink::Sink, stream::Stream};
 
 #[derive(Clone, Debug)]
 struct Message;
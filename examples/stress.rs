@@ -0,0 +1,457 @@
+//! A stress test for postage channels, which reports throughput and latency percentiles.
+//!
+//! Each channel is driven by a configurable number of producer and consumer tasks on the tokio
+//! multi-threaded runtime.  Producers send for the configured duration, and then hang up.  Consumers
+//! measure the latency of each message, and the run checks that every message was delivered.
+//! A delivery mismatch exits with a non-zero status, so the binary can be used as a reproduction
+//! harness for performance and correctness bug reports.
+//!
+//! ```text
+//! cargo run --release --example stress -- [options]
+//!
+//!   --channel <name>    mpsc, broadcast, dispatch, priority, watch, oneshot, barrier, or all (default: all)
+//!   --producers <n>     producer tasks (default: 4)
+//!   --consumers <n>     consumer tasks (default: 4)
+//!   --capacity <n>      channel capacity (default: 64)
+//!   --size <bytes>      message payload size (default: 64)
+//!   --duration <secs>   time spent sending on each channel (default: 1)
+//! ```
+//!
+//! Channels which only support a single producer or consumer ignore the corresponding knob.
+use std::{
+    process,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use postage::{
+    barrier, broadcast, dispatch, mpsc, oneshot, priority, sink::Sink, stream::Stream, watch,
+};
+use tokio::task::JoinHandle;
+
+const CHANNELS: &[&str] = &[
+    "mpsc",
+    "broadcast",
+    "dispatch",
+    "priority",
+    "watch",
+    "oneshot",
+    "barrier",
+];
+
+#[derive(Clone, Debug)]
+struct Config {
+    channel: String,
+    producers: usize,
+    consumers: usize,
+    capacity: usize,
+    size: usize,
+    duration: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            channel: "all".to_string(),
+            producers: 4,
+            consumers: 4,
+            capacity: 64,
+            size: 64,
+            duration: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Config {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", flag))?;
+
+            match flag.as_str() {
+                "--channel" => config.channel = value,
+                "--producers" => config.producers = parse_count(&flag, &value)?,
+                "--consumers" => config.consumers = parse_count(&flag, &value)?,
+                "--capacity" => config.capacity = parse_count(&flag, &value)?,
+                "--size" => config.size = parse_number(&flag, &value)?,
+                "--duration" => {
+                    let secs: f64 = parse_number(&flag, &value)?;
+                    config.duration = Duration::from_secs_f64(secs);
+                }
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+
+        if config.channel != "all" && !CHANNELS.contains(&config.channel.as_str()) {
+            return Err(format!("unknown channel {}", config.channel));
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+fn parse_count(flag: &str, value: &str) -> Result<usize, String> {
+    match parse_number(flag, value)? {
+        0 => Err(format!("{} must be at least 1", flag)),
+        n => Ok(n),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Message {
+    sent: Instant,
+    payload: Vec<u8>,
+}
+
+impl Message {
+    fn new(size: usize) -> Self {
+        Self {
+            sent: Instant::now(),
+            payload: vec![0xA5; size],
+        }
+    }
+}
+
+/// The outcome of a run on a single channel.
+#[derive(Default)]
+struct Report {
+    sent: usize,
+    expected: usize,
+    latencies: Vec<Duration>,
+    elapsed: Duration,
+}
+
+impl Report {
+    fn print(&mut self, channel: &str) {
+        self.latencies.sort_unstable();
+
+        let received = self.latencies.len();
+        let throughput = received as f64 / self.elapsed.as_secs_f64();
+        let status = if received == self.expected {
+            "ok"
+        } else {
+            "MISMATCH"
+        };
+
+        println!(
+            "{:<10} sent {:>9}  recv {:>9}  {:>12.0} msg/s  p50 {:>9?}  p90 {:>9?}  p99 {:>9?}  max {:>9?}  {}",
+            channel,
+            self.sent,
+            received,
+            throughput,
+            self.percentile(0.50),
+            self.percentile(0.90),
+            self.percentile(0.99),
+            self.latencies.last().copied().unwrap_or_default(),
+            status
+        );
+    }
+
+    fn percentile(&self, p: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::default();
+        }
+
+        let index = ((self.latencies.len() - 1) as f64 * p).round() as usize;
+        self.latencies[index]
+    }
+
+    fn is_ok(&self) -> bool {
+        self.latencies.len() == self.expected
+    }
+}
+
+fn spawn_producer<S>(mut tx: S, config: &Config) -> JoinHandle<usize>
+where
+    S: Sink<Item = Message> + Unpin + Send + 'static,
+{
+    let deadline = Instant::now() + config.duration;
+    let size = config.size;
+
+    tokio::spawn(async move {
+        let mut sent = 0;
+        while Instant::now() < deadline {
+            if tx.send(Message::new(size)).await.is_err() {
+                break;
+            }
+
+            sent += 1;
+        }
+
+        sent
+    })
+}
+
+fn spawn_consumer<S>(mut rx: S) -> JoinHandle<Vec<Duration>>
+where
+    S: Stream<Item = Message> + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut latencies = Vec::new();
+        while let Some(message) = rx.recv().await {
+            latencies.push(message.sent.elapsed());
+        }
+
+        latencies
+    })
+}
+
+async fn collect(
+    producers: Vec<JoinHandle<usize>>,
+    consumers: Vec<JoinHandle<Vec<Duration>>>,
+) -> Report {
+    let mut report = Report::default();
+
+    for producer in producers {
+        report.sent += producer.await.expect("producer panicked");
+    }
+
+    for consumer in consumers {
+        report
+            .latencies
+            .extend(consumer.await.expect("consumer panicked"));
+    }
+
+    report
+}
+
+async fn run_mpsc(config: &Config) -> Report {
+    let (tx, rx) = mpsc::channel(config.capacity);
+
+    let producers = (0..config.producers)
+        .map(|_| spawn_producer(tx.clone(), config))
+        .collect();
+    drop(tx);
+
+    let mut report = collect(producers, vec![spawn_consumer(rx)]).await;
+    report.expected = report.sent;
+    report
+}
+
+async fn run_broadcast(config: &Config) -> Report {
+    let (tx, rx) = broadcast::channel(config.capacity);
+
+    let consumers = (0..config.consumers)
+        .map(|_| spawn_consumer(rx.clone()))
+        .collect();
+    drop(rx);
+
+    let producers = (0..config.producers)
+        .map(|_| spawn_producer(tx.clone(), config))
+        .collect();
+    drop(tx);
+
+    let mut report = collect(producers, consumers).await;
+    report.expected = report.sent * config.consumers;
+    report
+}
+
+async fn run_dispatch(config: &Config) -> Report {
+    let (tx, rx) = dispatch::channel(config.capacity);
+
+    let consumers = (0..config.consumers)
+        .map(|_| spawn_consumer(rx.clone()))
+        .collect();
+    drop(rx);
+
+    let producers = (0..config.producers)
+        .map(|_| spawn_producer(tx.clone(), config))
+        .collect();
+    drop(tx);
+
+    let mut report = collect(producers, consumers).await;
+    report.expected = report.sent;
+    report
+}
+
+async fn run_priority(config: &Config) -> Report {
+    let (tx, rx) = priority::channel([config.capacity; 3]);
+
+    // spread the producers across the lanes, so the receiver interleaves them
+    let producers = (0..config.producers)
+        .map(|i| spawn_producer(tx.lane(i % tx.lanes()), config))
+        .collect();
+    drop(tx);
+
+    let mut report = collect(producers, vec![spawn_consumer(rx)]).await;
+    report.expected = report.sent;
+    report
+}
+
+async fn run_watch(config: &Config) -> Report {
+    // watch channels keep only the latest value, so consumers observe a subset of the messages.
+    // the run checks that each consumer sees the final value.
+    let (mut tx, rx) = watch::channel_with_option::<Message>();
+
+    let consumers: Vec<_> = (0..config.consumers)
+        .map(|_| {
+            let mut rx = rx.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                let mut last = None;
+                while let Some(value) = rx.recv().await {
+                    if let Some(message) = value {
+                        latencies.push(message.sent.elapsed());
+                        last = Some(message.payload[0]);
+                    }
+                }
+
+                (latencies, last)
+            })
+        })
+        .collect();
+    drop(rx);
+
+    let deadline = Instant::now() + config.duration;
+    let started = Instant::now();
+    let mut report = Report::default();
+    let mut index = 0usize;
+    while Instant::now() < deadline {
+        let mut message = Message::new(config.size.max(1));
+        message.payload[0] = index as u8;
+        index += 1;
+
+        if tx.send(Some(message)).await.is_err() {
+            break;
+        }
+
+        report.sent += 1;
+
+        // give the consumers a chance to observe intermediate values
+        tokio::task::yield_now().await;
+    }
+    drop(tx);
+
+    let last_sent = index.checked_sub(1).map(|i| i as u8);
+    let mut missed_final = false;
+    for consumer in consumers {
+        let (latencies, last) = consumer.await.expect("consumer panicked");
+        missed_final |= last != last_sent;
+        report.latencies.extend(latencies);
+    }
+
+    report.elapsed = started.elapsed();
+    report.expected = if missed_final {
+        usize::MAX
+    } else {
+        report.latencies.len()
+    };
+    report
+}
+
+async fn run_oneshot(config: &Config) -> Report {
+    let consumers = (0..config.consumers)
+        .map(|_| {
+            let deadline = Instant::now() + config.duration;
+            let size = config.size;
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                while Instant::now() < deadline {
+                    let (mut tx, mut rx) = oneshot::channel();
+                    tokio::spawn(async move { tx.send(Message::new(size)).await.ok() });
+
+                    if let Some(message) = rx.recv().await {
+                        latencies.push(message.sent.elapsed());
+                    }
+                }
+
+                latencies
+            })
+        })
+        .collect();
+
+    let mut report = collect(Vec::new(), consumers).await;
+    report.sent = report.latencies.len();
+    report.expected = report.sent;
+    report
+}
+
+async fn run_barrier(config: &Config) -> Report {
+    let consumers = (0..config.consumers)
+        .map(|_| {
+            let deadline = Instant::now() + config.duration;
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                while Instant::now() < deadline {
+                    let (mut tx, mut rx) = barrier::channel();
+                    let sent = Instant::now();
+                    tokio::spawn(async move { tx.send(()).await.ok() });
+
+                    if rx.recv().await.is_some() {
+                        latencies.push(sent.elapsed());
+                    }
+                }
+
+                latencies
+            })
+        })
+        .collect();
+
+    let mut report = collect(Vec::new(), consumers).await;
+    report.sent = report.latencies.len();
+    report.expected = report.sent;
+    report
+}
+
+async fn run(channel: &str, config: &Config) -> Report {
+    let started = Instant::now();
+
+    let mut report = match channel {
+        "mpsc" => run_mpsc(config).await,
+        "broadcast" => run_broadcast(config).await,
+        "dispatch" => run_dispatch(config).await,
+        "priority" => run_priority(config).await,
+        "watch" => run_watch(config).await,
+        "oneshot" => run_oneshot(config).await,
+        "barrier" => run_barrier(config).await,
+        _ => unreachable!("channel names are validated by Config::parse"),
+    };
+
+    if report.elapsed == Duration::default() {
+        report.elapsed = started.elapsed();
+    }
+
+    report
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::parse(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("stress: {}", e);
+            eprintln!("usage: stress [--channel <name>] [--producers <n>] [--consumers <n>] [--capacity <n>] [--size <bytes>] [--duration <secs>]");
+            process::exit(2);
+        }
+    };
+
+    println!(
+        "producers {}  consumers {}  capacity {}  size {} bytes  duration {:?}",
+        config.producers, config.consumers, config.capacity, config.size, config.duration
+    );
+
+    let channels: Vec<&str> = match config.channel.as_str() {
+        "all" => CHANNELS.to_vec(),
+        channel => vec![channel],
+    };
+
+    let mut failed = false;
+    for channel in channels {
+        let mut report = run(channel, &config).await;
+        report.print(channel);
+        failed |= !report.is_ok();
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,137 @@
+//! Support for blocking sends and receives, such as `Sink::blocking_send` and `Stream::blocking_recv`.
+//!
+//! With the `thread-priority` feature, `Sink::blocking_send_with` and `Stream::blocking_recv_with` accept a `WakePriority`.
+//! `WakePriority::High` raises the parked thread's scheduling priority on Windows and macOS, so latency-critical threads
+//! which bridge into async pipelines are not delayed behind bulk work after they are unparked.
+
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+#[cfg(feature = "thread-priority")]
+mod priority;
+
+/// The scheduling priority of a thread which is parked by a blocking send or receive.
+///
+/// Requires the `thread-priority` feature.
+#[cfg(feature = "thread-priority")]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum WakePriority {
+    /// The thread's priority is not changed.
+    #[default]
+    Normal,
+    /// The thread's priority is raised before it parks, and restored before the call returns.
+    /// This has no effect on platforms other than Windows and macOS.
+    High,
+}
+
+/// A waker which unparks the thread that created it.
+struct ThreadWaker {
+    thread: Thread,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.thread.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.thread.unpark();
+    }
+}
+
+/// Drives the future to completion on the current thread, parking the thread while the future is pending.
+///
+/// Channels register the waker when they return pending, and the thread is unparked when the waker is called.
+/// If the waker is called before the thread parks, the park returns immediately.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+
+    let waker = Waker::from(Arc::new(ThreadWaker {
+        thread: thread::current(),
+    }));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// Drives the future to completion on the current thread.  With `WakePriority::High`, the thread's priority is raised
+/// the first time it parks, and restored when the future completes.
+#[cfg(feature = "thread-priority")]
+pub(crate) fn block_on_with<F: Future>(future: F, priority: WakePriority) -> F::Output {
+    let mut future = std::pin::pin!(future);
+
+    let waker = Waker::from(Arc::new(ThreadWaker {
+        thread: thread::current(),
+    }));
+    let mut cx = Context::from_waker(&waker);
+
+    // ready futures return without a system call
+    let mut boost = None;
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => {
+                if priority == WakePriority::High && boost.is_none() {
+                    boost = Some(priority::Boost::raise());
+                }
+
+                thread::park()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+
+    use super::block_on;
+
+    #[test]
+    fn ready() {
+        assert_eq!(1usize, block_on(async { 1usize }));
+    }
+
+    #[test]
+    fn wakes_from_other_thread() {
+        let (mut tx, mut rx) = mpsc::channel(1);
+
+        let join = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            block_on(tx.send(1usize)).expect("send failed");
+        });
+
+        assert_eq!(Some(1usize), block_on(rx.recv()));
+        assert_eq!(None, block_on(rx.recv()));
+        join.join().expect("sender thread panicked");
+    }
+
+    #[cfg(feature = "thread-priority")]
+    #[test]
+    fn high_priority() {
+        use super::{block_on_with, WakePriority};
+
+        let (mut tx, mut rx) = mpsc::channel(1);
+
+        let join = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            block_on_with(tx.send(1usize), WakePriority::High).expect("send failed");
+        });
+
+        assert_eq!(Some(1usize), block_on_with(rx.recv(), WakePriority::High));
+        assert_eq!(None, block_on_with(rx.recv(), WakePriority::High));
+        join.join().expect("sender thread panicked");
+    }
+}
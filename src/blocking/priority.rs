@@ -0,0 +1,120 @@
+/// Raises the current thread's scheduling priority, and restores it when dropped.
+///
+/// On macOS the thread is moved to the user-interactive QoS class, and on Windows it is given `THREAD_PRIORITY_HIGHEST`.
+/// Other platforms are unchanged.  Errors are ignored, as the boost only affects latency.
+pub(crate) struct Boost {
+    previous: Option<platform::Previous>,
+}
+
+impl Boost {
+    pub fn raise() -> Self {
+        Self {
+            previous: platform::raise(),
+        }
+    }
+}
+
+impl Drop for Boost {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            platform::restore(previous);
+        }
+    }
+}
+
+#[cfg(target_vendor = "apple")]
+mod platform {
+    use libc::qos_class_t;
+
+    pub(crate) struct Previous {
+        class: qos_class_t,
+        relative_priority: libc::c_int,
+    }
+
+    pub(crate) fn raise() -> Option<Previous> {
+        let mut class = qos_class_t::QOS_CLASS_UNSPECIFIED;
+        let mut relative_priority = 0;
+
+        // SAFETY: the out pointers are valid for the duration of the call
+        let read = unsafe {
+            libc::pthread_get_qos_class_np(libc::pthread_self(), &mut class, &mut relative_priority)
+        };
+        if read != 0 {
+            return None;
+        }
+
+        // SAFETY: only the calling thread's QoS class is changed
+        let set = unsafe {
+            libc::pthread_set_qos_class_self_np(qos_class_t::QOS_CLASS_USER_INTERACTIVE, 0)
+        };
+        if set != 0 {
+            return None;
+        }
+
+        Some(Previous {
+            class,
+            relative_priority,
+        })
+    }
+
+    pub(crate) fn restore(previous: Previous) {
+        // threads without an explicit class run at the default class
+        let class = match previous.class {
+            qos_class_t::QOS_CLASS_UNSPECIFIED => qos_class_t::QOS_CLASS_DEFAULT,
+            class => class,
+        };
+
+        // SAFETY: only the calling thread's QoS class is changed
+        unsafe {
+            libc::pthread_set_qos_class_self_np(class, previous.relative_priority);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, GetThreadPriority, SetThreadPriority, THREAD_PRIORITY_HIGHEST,
+    };
+
+    const THREAD_PRIORITY_ERROR_RETURN: i32 = 0x7FFF_FFFF;
+
+    pub(crate) struct Previous(i32);
+
+    pub(crate) fn raise() -> Option<Previous> {
+        // SAFETY: the pseudo-handle refers to the calling thread, and does not need to be closed
+        unsafe {
+            let thread = GetCurrentThread();
+            let previous = GetThreadPriority(thread);
+            if previous == THREAD_PRIORITY_ERROR_RETURN || previous >= THREAD_PRIORITY_HIGHEST {
+                return None;
+            }
+
+            if SetThreadPriority(thread, THREAD_PRIORITY_HIGHEST) == 0 {
+                return None;
+            }
+
+            Some(Previous(previous))
+        }
+    }
+
+    pub(crate) fn restore(previous: Previous) {
+        // SAFETY: the pseudo-handle refers to the calling thread, and does not need to be closed
+        unsafe {
+            SetThreadPriority(GetCurrentThread(), previous.0);
+        }
+    }
+}
+
+#[cfg(not(any(target_vendor = "apple", windows)))]
+mod platform {
+    pub(crate) enum Previous {}
+
+    pub(crate) fn raise() -> Option<Previous> {
+        None
+    }
+
+    pub(crate) fn restore(previous: Previous) {
+        match previous {}
+    }
+}
@@ -0,0 +1,326 @@
+//! Bridges between synchronous channels and postage channels.
+//!
+//! Each bridge spawns a worker thread which drives the synchronous side, and forwards messages
+//! until either side is closed.  The worker thread exits when the source is closed, or the
+//! destination rejects a message.
+//!
+//! ```rust
+//! use postage::bridge;
+//! use postage::stream::Stream;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (std_tx, std_rx) = std::sync::mpsc::channel();
+//!     let (tx, mut rx) = postage::mpsc::channel(16);
+//!
+//!     bridge::from_std(std_rx, tx);
+//!
+//!     std_tx.send(1usize).unwrap();
+//!     drop(std_tx);
+//!
+//!     assert_eq!(Some(1usize), rx.recv().await);
+//!     assert_eq!(None, rx.recv().await);
+//! }
+//! ```
+//!
+//! When the postage side doesn't already exist, [from_std_receiver] and [to_std_sender] (and the
+//! crossbeam equivalents) create it, returning a postage receiver or sender which can be handed to
+//! async code while the legacy code keeps its synchronous endpoint.
+//!
+//! Requires the `blocking` feature (enabled by default).  The crossbeam bridges require the `crossbeam` feature.
+use std::thread::JoinHandle;
+
+use crate::{mpsc, sink::Sink, stream::Stream};
+
+/// The capacity of the postage channels created by the endpoint constructors.
+///
+/// The synchronous channel already buffers messages, so the postage side only needs to hold the
+/// message currently in flight.
+const ENDPOINT_CAPACITY: usize = 1;
+
+/// Converts a `std::sync::mpsc::Receiver` into a postage stream.
+///
+/// Messages are forwarded by a worker thread, which exits when all the std senders are dropped,
+/// or the returned receiver is dropped (and a message is forwarded).
+pub fn from_std_receiver<T>(source: std::sync::mpsc::Receiver<T>) -> mpsc::Receiver<T>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(ENDPOINT_CAPACITY);
+    from_std(source, tx);
+    rx
+}
+
+/// Converts a `std::sync::mpsc::Sender` into a postage sink.
+///
+/// Messages are forwarded by a worker thread, which exits when the returned sender (and all of
+/// its clones) are dropped, or the std receiver is dropped (and a message is forwarded).
+pub fn to_std_sender<T>(sink: std::sync::mpsc::Sender<T>) -> mpsc::Sender<T>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(ENDPOINT_CAPACITY);
+    to_std(rx, sink);
+    tx
+}
+
+/// Forwards messages from a `std::sync::mpsc::Receiver` into a postage sink, on a worker thread.
+///
+/// The thread exits when all the std senders are dropped, or the sink rejects a message.
+pub fn from_std<T, S>(source: std::sync::mpsc::Receiver<T>, mut sink: S) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    S: Sink<Item = T> + Unpin + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Ok(value) = source.recv() {
+            if sink.blocking_send(value).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Forwards messages from a postage stream into a `std::sync::mpsc::Sender`, on a worker thread.
+///
+/// The thread exits when the stream is closed, or the std receiver is dropped.
+pub fn to_std<T, S>(mut stream: S, sink: std::sync::mpsc::Sender<T>) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    S: Stream<Item = T> + Unpin + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Some(value) = stream.blocking_recv() {
+            if sink.send(value).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Converts a `crossbeam_channel::Receiver` into a postage stream.
+///
+/// Messages are forwarded by a worker thread, which exits when all the crossbeam senders are
+/// dropped, or the returned receiver is dropped (and a message is forwarded).
+///
+/// Requires the `crossbeam` feature.
+#[cfg(feature = "crossbeam")]
+pub fn from_crossbeam_receiver<T>(source: crossbeam_channel::Receiver<T>) -> mpsc::Receiver<T>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(ENDPOINT_CAPACITY);
+    from_crossbeam(source, tx);
+    rx
+}
+
+/// Converts a `crossbeam_channel::Sender` into a postage sink.
+///
+/// Messages are forwarded by a worker thread, which exits when the returned sender (and all of
+/// its clones) are dropped, or all the crossbeam receivers are dropped (and a message is forwarded).
+///
+/// Requires the `crossbeam` feature.
+#[cfg(feature = "crossbeam")]
+pub fn to_crossbeam_sender<T>(sink: crossbeam_channel::Sender<T>) -> mpsc::Sender<T>
+where
+    T: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(ENDPOINT_CAPACITY);
+    to_crossbeam(rx, sink);
+    tx
+}
+
+/// Forwards messages from a `crossbeam_channel::Receiver` into a postage sink, on a worker thread.
+///
+/// The thread exits when all the crossbeam senders are dropped, or the sink rejects a message.
+///
+/// Requires the `crossbeam` feature.
+#[cfg(feature = "crossbeam")]
+pub fn from_crossbeam<T, S>(source: crossbeam_channel::Receiver<T>, mut sink: S) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    S: Sink<Item = T> + Unpin + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Ok(value) = source.recv() {
+            if sink.blocking_send(value).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Forwards messages from a postage stream into a `crossbeam_channel::Sender`, on a worker thread.
+///
+/// The thread exits when the stream is closed, or all the crossbeam receivers are dropped.
+///
+/// Requires the `crossbeam` feature.
+#[cfg(feature = "crossbeam")]
+pub fn to_crossbeam<T, S>(mut stream: S, sink: crossbeam_channel::Sender<T>) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    S: Stream<Item = T> + Unpin + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Some(value) = stream.blocking_recv() {
+            if sink.send(value).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mpsc;
+
+    #[test]
+    fn from_std() {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        let (tx, mut rx) = mpsc::channel(2);
+
+        let join = super::from_std(std_rx, tx);
+
+        for i in 0..10usize {
+            std_tx.send(i).unwrap();
+        }
+        drop(std_tx);
+
+        for i in 0..10usize {
+            assert_eq!(Some(i), rx.blocking_recv());
+        }
+
+        assert_eq!(None, rx.blocking_recv());
+        join.join().expect("bridge thread panicked");
+    }
+
+    #[test]
+    fn from_std_sink_closed() {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        let (tx, rx) = mpsc::channel(2);
+        drop(rx);
+
+        let join = super::from_std(std_rx, tx);
+        std_tx.send(1usize).unwrap();
+
+        join.join().expect("bridge thread panicked");
+    }
+
+    #[test]
+    fn to_std() {
+        let (mut tx, rx) = mpsc::channel(2);
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+
+        let join = super::to_std(rx, std_tx);
+
+        for i in 0..10usize {
+            tx.blocking_send(i).unwrap();
+        }
+        drop(tx);
+
+        let received: Vec<usize> = std_rx.iter().collect();
+        assert_eq!((0..10).collect::<Vec<_>>(), received);
+        join.join().expect("bridge thread panicked");
+    }
+
+    #[test]
+    fn to_std_receiver_closed() {
+        let (mut tx, rx) = mpsc::channel(2);
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        drop(std_rx);
+
+        let join = super::to_std(rx, std_tx);
+        tx.blocking_send(1usize).unwrap();
+
+        join.join().expect("bridge thread panicked");
+    }
+
+    #[test]
+    fn from_std_receiver() {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        let mut rx = super::from_std_receiver(std_rx);
+
+        for i in 0..10usize {
+            std_tx.send(i).unwrap();
+        }
+        drop(std_tx);
+
+        for i in 0..10usize {
+            assert_eq!(Some(i), rx.blocking_recv());
+        }
+
+        assert_eq!(None, rx.blocking_recv());
+    }
+
+    #[test]
+    fn to_std_sender() {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        let mut tx = super::to_std_sender(std_tx);
+
+        for i in 0..10usize {
+            tx.blocking_send(i).unwrap();
+        }
+        drop(tx);
+
+        let received: Vec<usize> = std_rx.iter().collect();
+        assert_eq!((0..10).collect::<Vec<_>>(), received);
+    }
+
+    #[test]
+    fn to_std_sender_receiver_closed() {
+        let (std_tx, std_rx) = std::sync::mpsc::channel();
+        let mut tx = super::to_std_sender(std_tx);
+        drop(std_rx);
+
+        // the first message is accepted by the worker, which then observes the closed receiver
+        tx.blocking_send(1usize).unwrap();
+        while tx.blocking_send(2usize).is_ok() {}
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn crossbeam_endpoints() {
+        let (cb_tx, cb_rx) = crossbeam_channel::bounded(1);
+        let (cb_out_tx, cb_out_rx) = crossbeam_channel::unbounded();
+
+        let mut rx = super::from_crossbeam_receiver(cb_rx);
+        let mut tx = super::to_crossbeam_sender(cb_out_tx);
+
+        let join = std::thread::spawn(move || {
+            for i in 0..10usize {
+                cb_tx.send(i).unwrap();
+            }
+        });
+
+        while let Some(value) = rx.blocking_recv() {
+            tx.blocking_send(value).unwrap();
+        }
+        drop(tx);
+
+        let received: Vec<usize> = cb_out_rx.iter().collect();
+        assert_eq!((0..10).collect::<Vec<_>>(), received);
+        join.join().expect("sender thread panicked");
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn crossbeam_round_trip() {
+        let (cb_tx, cb_rx) = crossbeam_channel::bounded(1);
+        let (tx, rx) = mpsc::channel(2);
+        let (cb_out_tx, cb_out_rx) = crossbeam_channel::unbounded();
+
+        let from = super::from_crossbeam(cb_rx, tx);
+        let to = super::to_crossbeam(rx, cb_out_tx);
+
+        for i in 0..10usize {
+            cb_tx.send(i).unwrap();
+        }
+        drop(cb_tx);
+
+        let received: Vec<usize> = cb_out_rx.iter().collect();
+        assert_eq!((0..10).collect::<Vec<_>>(), received);
+
+        from.join().expect("bridge thread panicked");
+        to.join().expect("bridge thread panicked");
+    }
+}
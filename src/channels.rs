@@ -1,11 +1,14 @@
 pub mod barrier;
 pub mod broadcast;
 pub mod dispatch;
+pub mod history;
+pub mod mpmc;
 pub mod mpsc;
 pub mod oneshot;
+pub mod priority;
 pub mod watch;
 
-use std::{cell::Cell, marker::Sync};
+use core::{cell::Cell, marker::Sync};
 
 use static_assertions::{assert_impl_all, assert_not_impl_all};
 
@@ -1,16 +1,21 @@
 //! Barriers transmit when the sender half is dropped, and can synchronize events in async tasks.
 //!
 //! The barrier can also be triggered with `tx.send(())`.
+//!
+//! Reusable barriers for multiple parties can be created with `channel_n(parties)`.  The receiver produces each generation
+//! once every sender has signaled, and the barrier resets for the next phase.
 
-use std::fmt;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
 
+use crate::sync::lock::Mutex;
 use atomic::{Atomic, Ordering};
 use static_assertions::{assert_impl_all, assert_not_impl_all};
 
 use crate::{
     sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    stream::{ClosedReason, PollRecv, Stream},
     sync::notifier::Notifier,
 };
 
@@ -46,7 +51,7 @@ impl Sink for Sender {
     type Item = ();
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         _cx: &mut crate::Context<'_>,
         _value: (),
     ) -> PollSend<Self::Item> {
@@ -71,22 +76,22 @@ mod impl_futures {
     use super::State;
     use crate::sink::SendError;
     use atomic::Ordering;
-    use std::task::{Context, Poll};
+    use core::task::{Context, Poll};
 
     impl futures::sink::Sink<()> for super::Sender {
         type Error = SendError<()>;
 
         fn poll_ready(
-            self: std::pin::Pin<&mut Self>,
+            self: core::pin::Pin<&mut Self>,
             _cx: &mut Context<'_>,
-        ) -> std::task::Poll<Result<(), Self::Error>> {
+        ) -> core::task::Poll<Result<(), Self::Error>> {
             match self.shared.state.load(Ordering::Acquire) {
                 State::Pending => Poll::Ready(Ok(())),
                 State::Sent => Poll::Ready(Err(SendError(()))),
             }
         }
 
-        fn start_send(self: std::pin::Pin<&mut Self>, _item: ()) -> Result<(), Self::Error> {
+        fn start_send(self: core::pin::Pin<&mut Self>, _item: ()) -> Result<(), Self::Error> {
             match self.shared.state.load(Ordering::Acquire) {
                 State::Pending => {
                     self.shared.close();
@@ -97,14 +102,14 @@ mod impl_futures {
         }
 
         fn poll_flush(
-            self: std::pin::Pin<&mut Self>,
+            self: core::pin::Pin<&mut Self>,
             _cx: &mut Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
         fn poll_close(
-            self: std::pin::Pin<&mut Self>,
+            self: core::pin::Pin<&mut Self>,
             _cx: &mut Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
@@ -148,7 +153,7 @@ impl Stream for Receiver {
     type Item = ();
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
         match self.shared.state.load(Ordering::Acquire) {
@@ -172,18 +177,213 @@ impl fmt::Debug for Receiver {
     }
 }
 
+/// Constructs a reusable barrier for `parties` senders.
+///
+/// The receiver produces the generation number each time every sender has signaled with `tx.send(())`.
+/// The barrier then resets, and senders can signal the next generation.  Dropping a sender removes it from the barrier.
+///
+/// Panics if `parties` is zero.
+pub fn channel_n(parties: usize) -> (Vec<PhaseSender>, PhaseReceiver) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating barrier channel with {} parties", parties);
+
+    assert!(parties > 0, "barrier must have at least one party");
+
+    let shared = Arc::new(PhaseShared {
+        state: Mutex::new(PhaseState {
+            generation: 0,
+            arrived: 0,
+            parties,
+        }),
+        notify_rx: Notifier::new(),
+        notify_tx: Notifier::new(),
+    });
+
+    let senders = (0..parties)
+        .map(|_| PhaseSender {
+            shared: shared.clone(),
+            signaled: None,
+        })
+        .collect();
+
+    let receiver = PhaseReceiver {
+        shared,
+        generation: 0,
+    };
+
+    (senders, receiver)
+}
+
+/// One party of a reusable barrier, created by `channel_n`.
+///
+/// Sending `()` signals the current generation.  If the sender has already signaled, the send waits until the generation completes.
+pub struct PhaseSender {
+    shared: Arc<PhaseShared>,
+    // the generation most recently signaled by this sender
+    signaled: Option<usize>,
+}
+
+assert_impl_all!(PhaseSender: Send, Sync, fmt::Debug);
+assert_not_impl_all!(PhaseSender: Clone);
+
+impl Sink for PhaseSender {
+    type Item = ();
+
+    fn poll_send(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        _value: (),
+    ) -> PollSend<Self::Item> {
+        let this = self.get_mut();
+
+        loop {
+            let guard = this.shared.notify_tx.guard();
+            let mut state = this.shared.state.lock();
+
+            if this.signaled != Some(state.generation) {
+                this.signaled = Some(state.generation);
+                state.arrived += 1;
+
+                if state.arrived >= state.parties {
+                    state.complete();
+                    drop(state);
+                    this.shared.notify();
+                }
+
+                return PollSend::Ready;
+            }
+
+            drop(state);
+            this.shared.notify_tx.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollSend::Pending(());
+        }
+    }
+}
+
+impl Drop for PhaseSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+
+        if self.signaled == Some(state.generation) {
+            state.arrived -= 1;
+        }
+
+        state.parties -= 1;
+
+        // the remaining parties may have all signaled
+        if state.parties > 0 && state.arrived >= state.parties {
+            state.complete();
+        }
+
+        drop(state);
+        self.shared.notify();
+    }
+}
+
+impl fmt::Debug for PhaseSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PhaseSender").finish()
+    }
+}
+
+/// The receiver of a reusable barrier, created by `channel_n`.
+///
+/// Produces the number of each completed generation, starting at zero.  The stream is closed once all senders are dropped.
+/// Cloned receivers observe the same generations as the original.
+#[derive(Clone)]
+pub struct PhaseReceiver {
+    shared: Arc<PhaseShared>,
+    // the next generation to be observed
+    generation: usize,
+}
+
+assert_impl_all!(PhaseReceiver: Clone, Send, Sync, fmt::Debug);
+
+impl Stream for PhaseReceiver {
+    type Item = usize;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        loop {
+            let guard = this.shared.notify_rx.guard();
+            let state = this.shared.state.lock();
+
+            if this.generation < state.generation {
+                let generation = this.generation;
+                this.generation += 1;
+                return PollRecv::Ready(generation);
+            }
+
+            if state.parties == 0 {
+                return PollRecv::Closed(ClosedReason::SendersDropped);
+            }
+
+            drop(state);
+            this.shared.notify_rx.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl fmt::Debug for PhaseReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PhaseReceiver").finish()
+    }
+}
+
+struct PhaseShared {
+    state: Mutex<PhaseState>,
+    notify_rx: Notifier,
+    notify_tx: Notifier,
+}
+
+impl PhaseShared {
+    pub fn notify(&self) {
+        self.notify_rx.notify();
+        self.notify_tx.notify();
+    }
+}
+
+struct PhaseState {
+    // the number of completed generations
+    generation: usize,
+    arrived: usize,
+    parties: usize,
+}
+
+impl PhaseState {
+    pub fn complete(&mut self) {
+        self.generation += 1;
+        self.arrived = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{pin::Pin, task::Context};
 
     use crate::{
         sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         test::{noop_context, panic_context},
     };
     use futures_test::task::new_count_waker;
 
-    use super::channel;
+    use super::{channel, channel_n};
 
     #[test]
     fn send_accepted() {
@@ -311,6 +511,78 @@ mod tests {
 
         assert_eq!(1, w1_count.get());
     }
+
+    #[test]
+    fn phase_generations() {
+        let mut cx = noop_context();
+        let (mut senders, mut rx) = channel_n(2);
+        let mut tx1 = senders.pop().unwrap();
+        let mut tx0 = senders.pop().unwrap();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx1).poll_send(&mut cx, ()));
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx1).poll_send(&mut cx, ()));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn phase_sender_waits_for_generation() {
+        let mut cx = noop_context();
+        let (mut senders, _rx) = channel_n(2);
+        let mut tx1 = senders.pop().unwrap();
+        let mut tx0 = senders.pop().unwrap();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(()),
+            Pin::new(&mut tx0).poll_send(&mut w1_context.into(), ())
+        );
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx1).poll_send(&mut cx, ()));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+    }
+
+    #[test]
+    fn phase_sender_disconnect() {
+        let mut cx = noop_context();
+        let (mut senders, mut rx) = channel_n(3);
+        let tx2 = senders.pop().unwrap();
+        let mut tx1 = senders.pop().unwrap();
+        let mut tx0 = senders.pop().unwrap();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx1).poll_send(&mut cx, ()));
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        // the remaining parties have signaled, so the generation completes
+        drop(tx2);
+        assert_eq!(1, w1_count.get());
+        assert_eq!(PollRecv::Ready(0), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx0).poll_send(&mut cx, ()));
+        drop(tx0);
+        drop(tx1);
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
 }
 
 #[cfg(test)]
@@ -367,6 +639,29 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn phases() {
+        let (senders, mut rx) = super::channel_n(CHANNEL_TEST_RECEIVERS);
+
+        for mut tx in senders {
+            spawn(async move {
+                for _ in 0..CHANNEL_TEST_ITERATIONS {
+                    tx.send(()).await.expect("Should send message");
+                }
+            });
+        }
+
+        timeout(TEST_TIMEOUT, async move {
+            for generation in 0..CHANNEL_TEST_ITERATIONS {
+                assert_eq!(Some(generation), rx.recv().await);
+            }
+
+            assert_eq!(None, rx.recv().await);
+        })
+        .await
+        .expect("test timeout");
+    }
+
     #[tokio::test]
     async fn multi_receiver() {
         for _ in 0..CHANNEL_TEST_ITERATIONS {
@@ -1,37 +1,195 @@
-//! Provides a lossless, MPMC channel.  All receivers are guaranteed to recieve each message.
+//! Provides an MPMC broadcast channel.  By default it is lossless, and all receivers are guaranteed to recieve each message.
 //!
 //! When a receiver is cloned, the new receive will observe the same series of messages as the original.
 //! When a receiver is created with `Sender::subscribe`, it will observe new messages.
+//!
+//! By default, senders are suspended while the slowest receiver catches up.  Channels created with
+//! `channel_with_policy(capacity, OverflowPolicy::DropOldest)` never block senders.  Instead, the oldest
+//! message is overwritten, and lagging receivers skip ahead.  The number of skipped messages can be observed with `Receiver::lagged`.
+//!
+//! Channels created with `channel_with_transform(capacity, transform)` accept raw messages, and produce transformed messages.
+//! The transform runs at most once per message, regardless of the number of receivers.
+//!
+//! Receivers created with `Sender::subscribe_filtered` only observe messages which match a predicate.
+//! Other messages are marked as read without being cloned, so they don't hold back the sender.
+//!
+//! Producers can observe their audience with `Sender::subscriber_events`, which produces an event each time a receiver joins or leaves.
+//!
+//! The capacity can be changed at runtime with `Sender::resize`, which preserves buffered messages.
+//!
+//! `StaticChannel<T, N>` stores its buffer inline, so it can be placed in a `static` without heap allocation.
 
-use std::fmt;
+use crate::sync::lock::OnceLock;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::{fmt, future::Future, task::Poll};
 
 use super::SendMessage;
 use static_assertions::assert_impl_all;
+use thiserror::Error;
 
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    sink::{PollSend, Sink, TrySendError},
+    stream::{ClosedReason, PollRecv, Stream, TryRecvError},
     sync::{
-        mpmc_circular_buffer::{BufferReader, MpmcCircularBuffer, TryRead, TryWrite},
-        shared, ReceiverShared, SenderShared,
+        mpmc_circular_buffer::{
+            BufferReader, MpmcCircularBuffer, ReaderChange, ReaderLog, TryRead, TryWrite,
+        },
+        shared, ReceiverShared, SenderShared, WeakSenderShared,
     },
 };
 
+#[cfg(not(feature = "loom"))]
+mod static_channel;
+
+#[cfg(not(feature = "loom"))]
+pub use static_channel::{StaticChannel, StaticReceiver, StaticSender};
+
 /// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity
 pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    channel_with_policy(capacity, OverflowPolicy::Block)
+}
+
+/// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity,
+/// and the given policy for handling a full buffer.
+pub fn channel_with_policy<T: Clone>(
+    capacity: usize,
+    policy: OverflowPolicy,
+) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
-    log::error!("Creating broadcast channel with capacity {}", capacity);
-    // we add one spare capacity so that receivers have an empty slot to wait on
-    let (buffer, reader) = MpmcCircularBuffer::new(capacity);
+    log::error!(
+        "Creating broadcast channel with capacity {} and policy {:?}",
+        capacity,
+        policy
+    );
+    let overwrite = policy == OverflowPolicy::DropOldest;
+    let (buffer, reader) = MpmcCircularBuffer::new(capacity, overwrite);
 
     let (tx_shared, rx_shared) = shared(buffer);
-    let sender = Sender { shared: tx_shared };
+    let sender = Sender::new(tx_shared);
+
+    let receiver = Receiver::new(rx_shared, reader);
+
+    (sender, receiver)
+}
+
+/// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity.
+/// The channel reports events to the `metrics` recorder under the given name.
+///
+/// Requires the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub fn channel_named<T: Clone>(name: &str, capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let (buffer, reader) = MpmcCircularBuffer::new(capacity, false);
+
+    let (tx_shared, rx_shared) = crate::sync::shared_named(name, buffer);
+    let sender = Sender::new(tx_shared);
 
     let receiver = Receiver::new(rx_shared, reader);
 
     (sender, receiver)
 }
 
+/// Constructs a pair of broadcast endpoints, with a fixed-size buffer of the given capacity.
+///
+/// The sender accepts `Raw` messages, and receivers produce the result of `transform`.  The transform is
+/// executed once per message, by the first receiver to read it.  Other receivers clone the cached result.
+/// The cached value is released when the message leaves the buffer.
+///
+/// This is useful when messages need expensive decoding, and the channel has many receivers.
+pub fn channel_with_transform<Raw, T, F>(
+    capacity: usize,
+    transform: F,
+) -> (TransformSender<Raw, T>, TransformReceiver<Raw, T>)
+where
+    T: Clone,
+    F: Fn(&Raw) -> T + Send + Sync + 'static,
+{
+    let (tx, rx) = channel(capacity);
+    let transform = Arc::new(transform);
+
+    let sender = TransformSender {
+        sender: tx,
+        transform: transform.clone(),
+    };
+    let receiver = TransformReceiver {
+        receiver: rx,
+        transform,
+    };
+
+    (sender, receiver)
+}
+
+/// Determines how a broadcast channel handles a full buffer.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Senders are suspended until the slowest receiver has read the oldest message.  No messages are lost.
+    #[default]
+    Block,
+    /// Senders overwrite the oldest message.  Receivers which fall behind skip ahead to the oldest retained message.
+    DropOldest,
+}
+
+/// A change in the receivers which are subscribed to a broadcast channel.  Produced by `Sender::subscriber_events`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SubscriberEvent {
+    /// A receiver was created with `subscribe` or `clone`.  The channel now has the given number of receivers.
+    Joined { receivers: usize },
+    /// A receiver was dropped.  The channel now has the given number of receivers.
+    Left { receivers: usize },
+}
+
+/// A stream of `SubscriberEvent`s, created by `Sender::subscriber_events`.
+///
+/// Events are queued until they are received, so no join or leave is missed.
+pub struct SubscriberEvents {
+    log: Arc<ReaderLog>,
+}
+
+assert_impl_all!(SubscriberEvents: Send, Sync, fmt::Debug);
+
+impl Stream for SubscriberEvents {
+    type Item = SubscriberEvent;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        match self.log.poll_next(cx) {
+            Poll::Ready(Some(ReaderChange::Joined(receivers))) => {
+                PollRecv::Ready(SubscriberEvent::Joined { receivers })
+            }
+            Poll::Ready(Some(ReaderChange::Left(receivers))) => {
+                PollRecv::Ready(SubscriberEvent::Left { receivers })
+            }
+            Poll::Ready(None) => PollRecv::Closed(ClosedReason::Closed),
+            Poll::Pending => PollRecv::Pending,
+        }
+    }
+}
+
+impl fmt::Debug for SubscriberEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubscriberEvents").finish()
+    }
+}
+
+/// An error returned by a `LaggedReceiver`, when messages were overwritten before the receiver could read them.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecvError {
+    /// The receiver fell behind, and skipped the given number of messages
+    #[error("RecvError::Lagged({0})")]
+    Lagged(usize),
+}
+
+/// An error returned by `Sender::resize`, when the buffer can't be shrunk to the requested capacity.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ResizeError {
+    /// Shrinking the buffer would discard messages which have not been received by every receiver.
+    /// Contains the number of unread messages
+    #[error("ResizeError::Unread({0})")]
+    Unread(usize),
+}
+
 /// A broadcast sender that can be used with the postage::Sink trait.  Can be cloned.
 ///
 /// The sender task is suspended when the internal buffer is filled.
@@ -39,6 +197,8 @@ pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
 /// Note: no implementation of the `futures::Sink` trait is provided for the broadcast Sender.
 pub struct Sender<T> {
     pub(in crate::channels::broadcast) shared: SenderShared<MpmcCircularBuffer<T>>,
+    // the id of the message most recently written by this sender, which `send_latest` may replace
+    last_written: Option<usize>,
 }
 
 unsafe impl<T: Send> Send for Sender<T> {}
@@ -46,9 +206,7 @@ unsafe impl<T: Send> Sync for Sender<T> {}
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
-        Self {
-            shared: self.shared.clone(),
-        }
+        Self::new(self.shared.clone())
     }
 }
 
@@ -61,7 +219,7 @@ where
     type Item = T;
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
         value: Self::Item,
     ) -> PollSend<Self::Item> {
@@ -78,15 +236,147 @@ where
         //   register for wakeup
         // else
         //   overwrite the element
-        let buffer = self.shared.extension();
+        let this = self.get_mut();
+        let buffer = this.shared.extension();
         match buffer.try_write(value, cx) {
-            TryWrite::Pending(value) => PollSend::Pending(value),
-            TryWrite::Ready => PollSend::Ready,
+            TryWrite::Pending(value) => {
+                #[cfg(feature = "metrics")]
+                this.shared.record(crate::metrics::Event::SendBlocked);
+
+                PollSend::Pending(value)
+            }
+            TryWrite::Ready(id) => {
+                this.last_written = Some(id);
+
+                #[cfg(feature = "metrics")]
+                this.shared.record(crate::metrics::Event::Send);
+
+                #[cfg(feature = "diagnostics")]
+                this.shared.record_write();
+
+                PollSend::Ready
+            }
+        }
+    }
+}
+
+impl<T> Sender<T>
+where
+    T: Clone,
+{
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Pending(value))` if the buffer is full, and a receiver has not read the oldest message.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
+    }
+
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
+
+    /// Sends a message which supersedes the previous message from this sender.
+    ///
+    /// If no receiver has read the previous message written by this sender, it is replaced in place, and no new slot is used.
+    /// Otherwise, the message is sent like `Sink::send`.  This reduces buffer pressure for senders which emit rapidly superseded state.
+    ///
+    /// Returns `Err(SendError(value))` if the channel is closed.
+    pub async fn send_latest(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        let value = match self.try_replace_last(value) {
+            Ok(()) => {
+                #[cfg(feature = "metrics")]
+                self.shared.record(crate::metrics::Event::Send);
+
+                #[cfg(feature = "diagnostics")]
+                self.shared.record_write();
+
+                return Ok(());
+            }
+            Err(value) => value,
+        };
+
+        Sink::send(self, value).await
+    }
+
+    fn try_replace_last(&mut self, value: T) -> Result<(), T> {
+        if self.shared.is_closed() {
+            return Err(value);
+        }
+
+        match self.last_written {
+            Some(id) => self.shared.extension().try_replace(id, value),
+            None => Err(value),
         }
     }
 }
 
 impl<T> Sender<T> {
+    fn new(shared: SenderShared<MpmcCircularBuffer<T>>) -> Self {
+        Self {
+            shared,
+            last_written: None,
+        }
+    }
+
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.extension().waiters()
+    }
+
+    /// Reports the parked tasks, the slot which blocks writers, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        crate::sync::ChannelDiagnostics {
+            since_last_write: self.shared.diagnostics().since_last_write,
+            ..self.shared.extension().diagnostics()
+        }
+    }
+
+    /// Returns true if every receiver has been dropped.  Messages sent to a closed channel are rejected.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+
+    /// Returns `Poll::Ready(())` if the channel is closed.  Otherwise, registers the task to be woken when it is closed.
+    pub fn poll_closed(&self, cx: &mut crate::Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.shared.recv_guard();
+
+            if self.shared.is_closed() {
+                return Poll::Ready(());
+            }
+
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Waits until the channel is closed.
+    ///
+    /// This allows a producer to stop generating messages as soon as they are no longer needed,
+    /// for example with `select!` on `tx.closed()` and the work.
+    pub async fn closed(&self) {
+        core::future::poll_fn(|cx| self.poll_closed(&mut cx.into())).await
+    }
+
     /// Subscribes to the channel, creating a new receiver.  The receiver
     /// will observe all messages sent after the call to subscribe.
     ///
@@ -98,6 +388,130 @@ impl<T> Sender<T> {
 
         Receiver::new(shared, reader)
     }
+
+    /// Subscribes to the channel, creating a new receiver which only observes messages that match the predicate.
+    ///
+    /// The predicate is evaluated by the receiver as it reads each message.  Messages which don't match are released
+    /// immediately, and are never cloned.  Clones of the receiver share the predicate.
+    pub fn subscribe_filtered<F>(&self, predicate: F) -> Receiver<T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        let mut receiver = self.subscribe();
+        receiver.filter = Some(Arc::new(predicate));
+        receiver
+    }
+
+    /// The number of receivers which are subscribed to the channel.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receiver_count()
+    }
+
+    /// Returns a stream of events, which are produced each time a receiver is created or dropped.
+    ///
+    /// Only changes after the call are reported.  The current count is available from `receiver_count`.
+    /// The stream is closed once every sender and receiver has been dropped.
+    pub fn subscriber_events(&self) -> SubscriberEvents {
+        SubscriberEvents {
+            log: self.shared.extension().reader_log(),
+        }
+    }
+
+    /// Registers a callback, which runs each time the receiver count changes from zero to one.
+    ///
+    /// This allows an expensive upstream source to be started on demand.  The callback runs on the thread which
+    /// creates the receiver, while the receiver count is locked, so transitions are observed in order.
+    /// It should be brief (such as sending a signal to a task), and must not create or drop receivers of this channel,
+    /// or register callbacks.
+    pub fn on_first_subscriber<F>(&self, callback: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.shared
+            .extension()
+            .on_first_reader(alloc::boxed::Box::new(callback));
+    }
+
+    /// Registers a callback, which runs each time the receiver count changes from one to zero.
+    ///
+    /// This allows an expensive upstream source to be stopped when it has no audience.
+    /// The callback has the same restrictions as `on_first_subscriber`.
+    pub fn on_last_unsubscribe<F>(&self, callback: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.shared
+            .extension()
+            .on_last_reader(alloc::boxed::Box::new(callback));
+    }
+
+    /// The number of senders, including this one.  Weak senders are not counted.
+    pub fn sender_count(&self) -> usize {
+        self.shared.sender_count()
+    }
+
+    /// The number of messages in the buffer which have not been received by every receiver.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.extension().occupied()
+    }
+
+    /// True if every receiver has received every message.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// Drops any buffered values which have been received by every receiver, and returns the number of values dropped.
+    ///
+    /// Values are usually dropped as soon as the last receiver reads them.  If the last read races with another receiver
+    /// being dropped, both may miss the release, and the value is retained until the slot is reused.  This maintenance call releases it.
+    pub fn shrink_slots(&self) -> usize {
+        self.shared.extension().shrink_slots()
+    }
+
+    /// Changes the capacity of the channel, preserving buffered messages and the position of each receiver.
+    ///
+    /// Growing the buffer always succeeds, and wakes suspended senders.  Shrinking succeeds if the unread
+    /// messages fit in the new capacity, and returns `Err(ResizeError::Unread(n))` otherwise.
+    /// On `OverflowPolicy::DropOldest` channels, shrinking always succeeds, and lagging receivers skip the oldest messages.
+    ///
+    /// Sends and receives don't lock the buffer.  The resize waits for operations in progress,
+    /// and operations which reach the buffer during the resize wait until it completes.
+    /// Each resize keeps the previous buffer, without its messages, until the channel is dropped.
+    ///
+    /// The capacity is at least 2.
+    pub fn resize(&self, capacity: usize) -> Result<(), ResizeError> {
+        self.shared
+            .extension()
+            .resize(capacity)
+            .map_err(ResizeError::Unread)
+    }
+
+    /// Creates a weak sender, which does not keep the channel open.
+    ///
+    /// When all strong senders are dropped, receivers observe the channel as closed, even if weak senders remain.
+    pub fn downgrade(&self) -> WeakSender<T> {
+        WeakSender {
+            shared: self.shared.downgrade(),
+        }
+    }
+
+    /// Returns a future which resolves once every receiver has received the messages sent before the barrier.
+    ///
+    /// Messages sent after the call are not included, even if the future has not yet been polled.
+    /// Receivers which are dropped no longer hold the barrier.  With `OverflowPolicy::DropOldest`,
+    /// messages which were overwritten are considered complete.
+    pub fn barrier(&self) -> impl Future<Output = ()> + '_ {
+        let id = self.shared.extension().last_written();
+
+        core::future::poll_fn(move |cx| self.shared.extension().poll_read_by_all(id, &cx.into()))
+    }
 }
 
 impl<T> fmt::Debug for Sender<T> {
@@ -106,22 +520,165 @@ impl<T> fmt::Debug for Sender<T> {
     }
 }
 
+/// A weak reference to a broadcast sender, created with `Sender::downgrade`.  Can be cloned.
+///
+/// Weak senders do not keep the channel open, and cannot send messages until upgraded.
+pub struct WeakSender<T> {
+    shared: WeakSenderShared<MpmcCircularBuffer<T>>,
+}
+
+unsafe impl<T: Send> Send for WeakSender<T> {}
+unsafe impl<T: Send> Sync for WeakSender<T> {}
+
+assert_impl_all!(WeakSender<SendMessage>: Send, Sync, Clone, fmt::Debug);
+
+impl<T> WeakSender<T> {
+    /// Upgrades to a strong sender.  Returns `None` if all strong senders have been dropped.
+    pub fn upgrade(&self) -> Option<Sender<T>> {
+        self.shared.upgrade().map(Sender::new)
+    }
+}
+
+impl<T> Clone for WeakSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakSender").finish()
+    }
+}
+
 /// A broadcast receiver that can be used with the postage::Stream trait.
 ///
 /// When cloned, the new receiver will begin processing messages at the same location as the original.
 pub struct Receiver<T> {
     shared: ReceiverShared<MpmcCircularBuffer<T>>,
     reader: BufferReader,
+    filter: Option<Filter<T>>,
 }
 
+type Filter<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
 unsafe impl<T: Send> Send for Receiver<T> {}
 unsafe impl<T: Send> Sync for Receiver<T> {}
 
 assert_impl_all!(Receiver<SendMessage>: Send, Sync, Clone, fmt::Debug);
 
 impl<T> Receiver<T> {
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.extension().waiters()
+    }
+
+    /// Reports the parked tasks, the slot which blocks writers, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        crate::sync::ChannelDiagnostics {
+            since_last_write: self.shared.diagnostics().since_last_write,
+            ..self.shared.extension().diagnostics()
+        }
+    }
+
     fn new(shared: ReceiverShared<MpmcCircularBuffer<T>>, reader: BufferReader) -> Self {
-        Self { shared, reader }
+        Self {
+            shared,
+            reader,
+            filter: None,
+        }
+    }
+
+    /// The number of receivers which are subscribed to the channel, including this one.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receiver_count()
+    }
+
+    /// The number of senders.  Weak senders are not counted.
+    pub fn sender_count(&self) -> usize {
+        self.shared.sender_count()
+    }
+
+    /// The number of messages which this receiver has not yet received.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.reader.pending(self.shared.extension())
+    }
+
+    /// True if this receiver has received every message in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// The number of messages which have been sent since this receiver's position.
+    ///
+    /// Unlike `len`, this includes messages which were overwritten on `OverflowPolicy::DropOldest` channels,
+    /// so it reports how far the receiver has fallen behind the senders.
+    pub fn lag(&self) -> usize {
+        self.reader.lag(self.shared.extension())
+    }
+
+    /// Skips every message which has been sent, so the next message received is the next one sent.
+    /// The skipped messages are released, and senders which were waiting for space are woken.
+    ///
+    /// Useful for streams where only the newest value matters, such as video frames.  Returns the number of skipped messages.
+    pub fn skip_to_latest(&mut self) -> usize {
+        self.reader.skip_to_head(self.shared.extension())
+    }
+
+    /// Borrows the receiver as a stream which reports skipped messages.
+    ///
+    /// On `OverflowPolicy::DropOldest` channels, the stream produces `Err(RecvError::Lagged(n))`
+    /// if `n` messages were overwritten before they could be read.  Reading then resumes at the oldest retained message.
+    pub fn lagged(&mut self) -> LaggedReceiver<'_, T> {
+        LaggedReceiver { receiver: self }
+    }
+}
+
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    fn poll_recv_lagged(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<Result<T, RecvError>> {
+        let reader = &mut self.reader;
+        let buffer = self.shared.extension();
+        let filter = self.filter.as_deref();
+
+        loop {
+            return match reader.try_read(buffer, cx, filter) {
+                TryRead::Pending => {
+                    self.shared.subscribe_send(cx);
+
+                    if self.shared.is_closed() {
+                        return PollRecv::Closed(self.shared.closed_reason());
+                    }
+
+                    PollRecv::Pending
+                }
+                TryRead::Ready(value) => {
+                    #[cfg(feature = "metrics")]
+                    self.shared.record(crate::metrics::Event::Recv);
+
+                    PollRecv::Ready(Ok(value))
+                }
+                TryRead::Lagged(skipped) => PollRecv::Ready(Err(RecvError::Lagged(skipped))),
+                TryRead::Filtered => continue,
+            };
+        }
     }
 }
 
@@ -132,64 +689,242 @@ where
     type Item = T;
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
-        // unpin self, so Rust can infer that the borrows of reader and buffer are disjoint
         let this = self.get_mut();
-        let reader = &mut this.reader;
-        let buffer = this.shared.extension();
-
-        match reader.try_read(buffer, cx) {
-            TryRead::Pending => {
-                this.shared.subscribe_send(cx);
-
-                if this.shared.is_closed() {
-                    return PollRecv::Closed;
-                }
 
-                PollRecv::Pending
+        loop {
+            match this.poll_recv_lagged(cx) {
+                PollRecv::Ready(Ok(value)) => return PollRecv::Ready(value),
+                PollRecv::Ready(Err(RecvError::Lagged(_))) => continue,
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
             }
-            TryRead::Ready(value) => PollRecv::Ready(value),
         }
     }
 }
 
-impl<T> Clone for Receiver<T> {
-    fn clone(&self) -> Self {
-        let buffer = self.shared.extension();
-        let reader = self.reader.clone_with(buffer);
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
 
-        Self::new(self.shared.clone(), reader)
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
     }
-}
 
-impl<T> Drop for Receiver<T> {
-    fn drop(&mut self) {
-        let buffer = self.shared.extension();
-        self.reader.drop_with(buffer);
+    /// Converts the receiver into an iterator, which blocks the current thread until each message is received.
+    /// The iterator ends when the channel is closed.  Equivalent to `Stream::into_blocking_iter`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn into_blocking_iter(self) -> impl Iterator<Item = T> {
+        Stream::into_blocking_iter(self)
     }
 }
 
-impl<T> fmt::Debug for Receiver<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Receiver").finish()
-    }
+/// A borrowed broadcast receiver, which produces `Err(RecvError::Lagged(n))` when messages are skipped.
+///
+/// Created by `Receiver::lagged`.
+pub struct LaggedReceiver<'r, T> {
+    receiver: &'r mut Receiver<T>,
 }
 
-#[cfg(test)]
-mod tests {
-    use std::pin::Pin;
+impl<'r, T> Stream for LaggedReceiver<'r, T>
+where
+    T: Clone,
+{
+    type Item = Result<T, RecvError>;
 
-    use crate::{
-        sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        self.get_mut().receiver.poll_recv_lagged(cx)
+    }
+}
+
+impl<'r, T> fmt::Debug for LaggedReceiver<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LaggedReceiver").finish()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let buffer = self.shared.extension();
+        let reader = self.reader.clone_with(buffer);
+
+        let mut receiver = Self::new(self.shared.clone(), reader);
+        receiver.filter = self.filter.clone();
+        receiver
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let buffer = self.shared.extension();
+        self.reader.drop_with(buffer);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+// A message in a transform channel.  The buffer holds an Arc, so the cached value is shared by all receivers,
+// and is dropped when every receiver has read the message, and the slot is released.
+struct Memo<Raw, T> {
+    raw: Raw,
+    value: OnceLock<T>,
+}
+
+type Transform<Raw, T> = Arc<dyn Fn(&Raw) -> T + Send + Sync>;
+
+/// The sender half of a transform channel, created with `channel_with_transform`.  Accepts `Raw` messages.  Can be cloned.
+pub struct TransformSender<Raw, T> {
+    sender: Sender<Arc<Memo<Raw, T>>>,
+    transform: Transform<Raw, T>,
+}
+
+assert_impl_all!(TransformSender<String, String>: Send, Sync, Clone, fmt::Debug);
+
+impl<Raw, T> Clone for TransformSender<Raw, T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            transform: self.transform.clone(),
+        }
+    }
+}
+
+impl<Raw, T> Sink for TransformSender<Raw, T> {
+    type Item = Raw;
+
+    fn poll_send(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let memo = Arc::new(Memo {
+            raw: value,
+            value: OnceLock::new(),
+        });
+
+        // the memo is only shared once the buffer accepts it
+        let unwrap = |memo: Arc<Memo<Raw, T>>| match Arc::try_unwrap(memo) {
+            Ok(memo) => memo.raw,
+            Err(_) => unreachable!("rejected broadcast messages are not shared"),
+        };
+
+        match core::pin::Pin::new(&mut self.get_mut().sender).poll_send(cx, memo) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(memo) => PollSend::Pending(unwrap(memo)),
+            PollSend::Rejected(memo) => PollSend::Rejected(unwrap(memo)),
+        }
+    }
+}
+
+impl<Raw, T> TransformSender<Raw, T> {
+    /// Subscribes to the channel, creating a new receiver.  The receiver
+    /// will observe all messages sent after the call to subscribe.
+    ///
+    /// Messages currently in the buffer are not received.
+    pub fn subscribe(&self) -> TransformReceiver<Raw, T> {
+        TransformReceiver {
+            receiver: self.sender.subscribe(),
+            transform: self.transform.clone(),
+        }
+    }
+}
+
+impl<Raw, T> fmt::Debug for TransformSender<Raw, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformSender").finish()
+    }
+}
+
+/// The receiver half of a transform channel, created with `channel_with_transform`.  Produces transformed messages.
+///
+/// When cloned, the new receiver will begin processing messages at the same location as the original.
+pub struct TransformReceiver<Raw, T> {
+    receiver: Receiver<Arc<Memo<Raw, T>>>,
+    transform: Transform<Raw, T>,
+}
+
+assert_impl_all!(TransformReceiver<String, String>: Send, Sync, Clone, fmt::Debug);
+
+impl<Raw, T> Clone for TransformReceiver<Raw, T> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+            transform: self.transform.clone(),
+        }
+    }
+}
+
+impl<Raw, T> Stream for TransformReceiver<Raw, T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        match core::pin::Pin::new(&mut this.receiver).poll_recv(cx) {
+            PollRecv::Ready(memo) => {
+                let value = memo.value.get_or_init(|| (this.transform)(&memo.raw));
+                PollRecv::Ready(value.clone())
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed(reason) => PollRecv::Closed(reason),
+        }
+    }
+}
+
+impl<Raw, T> fmt::Debug for TransformReceiver<Raw, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream},
         test::{noop_context, panic_context},
         Context,
     };
     use futures_test::task::new_count_waker;
 
-    use super::{channel, Receiver, Sender};
+    use super::{
+        channel, channel_with_policy, OverflowPolicy, Receiver, RecvError, ResizeError, Sender,
+        SubscriberEvent,
+    };
 
     //TODO: add test covering rx location when cloned on an in-progress channel (exercising tail)
     fn pin<'a, 'b>(
@@ -376,57 +1111,718 @@ mod tests {
         );
 
         assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn try_send_try_recv() {
+        use crate::{sink::TrySendError, stream::TryRecvError};
+
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(
+            Err(TrySendError::Pending(Message(3))),
+            tx.try_send(Message(3))
+        );
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+
+        drop(tx);
+        assert_eq!(Ok(Message(2)), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    }
+
+    #[test]
+    fn len_capacity() {
+        let (mut tx, mut rx) = channel(4);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(4, tx.capacity());
+        assert!(tx.is_empty());
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(2, tx.len());
+        assert_eq!(2, rx.len());
+
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+        assert_eq!(Ok(Message(2)), rx.try_recv());
+        assert!(rx.is_empty());
+        assert_eq!(2, rx2.len());
+        assert_eq!(2, tx.len());
+
+        assert_eq!(Ok(Message(1)), rx2.try_recv());
+        assert_eq!(1, tx.len());
+        assert_eq!(Ok(Message(2)), rx2.try_recv());
+        assert!(tx.is_empty());
+        assert_eq!(4, rx2.capacity());
+    }
+
+    #[test]
+    fn skip_to_latest() {
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(2, rx.lag());
+
+        assert_eq!(2, rx.skip_to_latest());
+        assert_eq!(0, rx.lag());
+        assert_eq!(0, rx.skip_to_latest());
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx.try_recv());
+
+        // the slots are released once the other receiver has skipped them too
+        assert!(tx.try_send(Message(3)).is_err());
+        assert_eq!(2, rx2.skip_to_latest());
+        assert_eq!(Ok(()), tx.try_send(Message(3)));
+
+        assert_eq!(Ok(Message(3)), rx.try_recv());
+        assert_eq!(Ok(Message(3)), rx2.try_recv());
+    }
+
+    #[test]
+    fn subscribe_filtered() {
+        let (mut tx, rx) = channel(2);
+        let mut even = tx.subscribe_filtered(|message: &Message| message.0 % 2 == 0);
+        drop(rx);
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(Ok(Message(2)), even.try_recv());
+
+        // the filtered message was released, so the buffer has room again
+        assert_eq!(Ok(()), tx.try_send(Message(3)));
+        assert_eq!(Ok(()), tx.try_send(Message(5)));
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), even.try_recv());
+
+        let mut clone = even.clone();
+        assert_eq!(Ok(()), tx.try_send(Message(4)));
+        assert_eq!(Ok(Message(4)), even.try_recv());
+        assert_eq!(Ok(Message(4)), clone.try_recv());
+    }
+
+    #[test]
+    fn subscribe_filtered_does_not_clone() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug, PartialEq)]
+        struct Counted(usize);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                CLONES.fetch_add(1, Ordering::SeqCst);
+                Counted(self.0)
+            }
+        }
+
+        let (mut tx, rx) = channel(4);
+        let mut filtered = tx.subscribe_filtered(|value: &Counted| value.0 > 2);
+        drop(rx);
+
+        for i in 1..=4 {
+            assert!(tx.try_send(Counted(i)).is_ok());
+        }
+
+        assert_eq!(Ok(Counted(3)), filtered.try_recv());
+        assert_eq!(Ok(Counted(4)), filtered.try_recv());
+        assert_eq!(2, CLONES.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn lag_includes_overwritten() {
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+
+        for i in 1..=5 {
+            assert_eq!(Ok(()), tx.try_send(Message(i)));
+        }
+
+        assert_eq!(5, rx.lag());
+        assert_eq!(2, rx.len());
+
+        assert_eq!(5, rx.skip_to_latest());
+        assert_eq!(Ok(()), tx.try_send(Message(6)));
+        assert_eq!(Ok(Message(6)), rx.try_recv());
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(100);
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+        );
+        drop(tx);
+        drop(tx2);
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[test]
+    fn diagnostics() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+        assert_eq!(None, tx.diagnostics().since_last_write);
+        assert_eq!(None, tx.diagnostics().blocking_slot);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        let (w1, _w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(3))
+        );
+
+        let diagnostics = tx.diagnostics();
+        assert_eq!(1, diagnostics.waiting_senders);
+        assert_eq!(Some(1), diagnostics.blocking_slot);
+        assert!(diagnostics.since_last_write.is_some());
+
+        // the slot is blocked until both receivers have read it
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(Some(1), tx.diagnostics().blocking_slot);
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(None, rx.diagnostics().blocking_slot);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn waiters() {
+        let (mut tx, mut rx) = channel(2);
+
+        let (w1, _w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+        assert_eq!(1, tx.waiters().receivers);
+
+        let mut cx = noop_context();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+        assert_eq!(0, rx.waiters().receivers);
+
+        let w1_context = std::task::Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(3))
+        );
+        assert_eq!(1, rx.waiters().senders);
+    }
+
+    #[test]
+    fn barrier() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(4);
+        let mut rx2 = rx.clone();
+
+        let ready = tx.barrier();
+        let mut ready = Box::pin(ready);
+        let (w0, _w0_count) = new_count_waker();
+        let mut w0_context = std::task::Context::from_waker(&w0);
+        assert_eq!(Poll::Ready(()), ready.as_mut().poll(&mut w0_context));
+        drop(ready);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = std::task::Context::from_waker(&w1);
+        let mut barrier = Box::pin(tx.barrier());
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(0, w1_count.get());
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert!(w1_count.get() >= 1);
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+    }
+
+    #[test]
+    fn barrier_receiver_dropped() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(4);
+        let rx2 = rx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = std::task::Context::from_waker(&w1);
+        let mut barrier = Box::pin(tx.barrier());
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        drop(rx2);
+
+        assert!(w1_count.get() >= 1);
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+    }
+
+    #[test]
+    fn subscribe_counts() {
+        let (tx, rx) = channel::<Message>(4);
+        assert_eq!(1, tx.receiver_count());
+        assert_eq!(1, tx.sender_count());
+
+        let tx2 = tx.clone();
+        let rx2 = tx.subscribe();
+        drop(rx);
+        assert_eq!(1, tx.receiver_count());
+        assert_eq!(2, rx2.sender_count());
+
+        let rx3 = rx2.clone();
+        assert_eq!(2, rx3.receiver_count());
+
+        drop(tx2);
+        drop(rx2);
+        drop(rx3);
+        assert_eq!(0, tx.receiver_count());
+        assert_eq!(1, tx.sender_count());
+
+        let _rx4 = tx.subscribe();
+        assert_eq!(1, tx.receiver_count());
+    }
+
+    #[test]
+    fn shrink_slots() {
+        let (mut tx, mut rx) = channel(2);
+
+        let value = std::sync::Arc::new(vec![0u8; 16]);
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+
+        // the value has not yet been received
+        assert_eq!(0, tx.shrink_slots());
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        drop(rx.try_recv().unwrap());
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+        assert_eq!(0, tx.shrink_slots());
+
+        // without receivers, no buffered value can be received
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+        drop(rx);
+        assert_eq!(0, tx.shrink_slots());
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn resize_grow() {
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+
+        assert_eq!(Ok(()), tx.resize(4));
+        assert_eq!(4, tx.capacity());
+        assert_eq!(2, tx.len());
+
+        assert_eq!(Ok(()), tx.try_send(Message(3)));
+        assert_eq!(Ok(()), tx.try_send(Message(4)));
+
+        // each receiver resumes from its own position
+        assert_eq!(Ok(Message(2)), rx.try_recv());
+        assert_eq!(Ok(Message(3)), rx.try_recv());
+        assert_eq!(Ok(Message(4)), rx.try_recv());
+
+        for i in 1..=4 {
+            assert_eq!(Ok(Message(i)), rx2.try_recv());
+        }
+        assert!(tx.is_empty());
+    }
+
+    #[test]
+    fn resize_shrink() {
+        let (mut tx, mut rx) = channel(4);
+
+        for i in 1..=3 {
+            assert_eq!(Ok(()), tx.try_send(Message(i)));
+        }
+
+        assert_eq!(Err(ResizeError::Unread(3)), tx.resize(2));
+        assert_eq!(4, tx.capacity());
+
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+        assert_eq!(Ok(()), tx.resize(2));
+        assert_eq!(2, tx.capacity());
+
+        assert_eq!(Ok(Message(2)), rx.try_recv());
+        assert_eq!(Ok(Message(3)), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(4)));
+        assert_eq!(Ok(()), tx.try_send(Message(5)));
+        assert_eq!(
+            PollSend::Pending(Message(6)),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(6))
+        );
+        assert_eq!(Ok(Message(4)), rx.try_recv());
+        assert_eq!(Ok(Message(5)), rx.try_recv());
+    }
+
+    #[test]
+    fn resize_minimum_capacity() {
+        let (tx, _rx) = channel::<Message>(4);
+
+        assert_eq!(Ok(()), tx.resize(0));
+        assert_eq!(2, tx.capacity());
+    }
+
+    #[test]
+    fn resize_wakes_sender() {
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+        );
+        assert_eq!(0, count.get());
+
+        assert_eq!(Ok(()), tx.resize(3));
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+        );
+
+        for i in 1..=3 {
+            assert_eq!(Ok(Message(i)), rx.try_recv());
+        }
+    }
+
+    #[test]
+    fn resize_wakes_receiver() {
+        let (mut tx, mut rx) = channel(2);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(Ok(()), tx.resize(8));
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        // the receiver is subscribed to the new buffer
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(2, count.get());
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn resize_drop_oldest_shrink() {
+        let (mut tx, mut rx) = channel_with_policy(4, OverflowPolicy::DropOldest);
+
+        for i in 1..=4 {
+            assert_eq!(Ok(()), tx.try_send(Message(i)));
+        }
+
+        assert_eq!(Ok(()), tx.resize(2));
+        assert_eq!(
+            PollRecv::Ready(Err(RecvError::Lagged(2))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut noop_context())
+        );
+        assert_eq!(Ok(Message(3)), rx.try_recv());
+        assert_eq!(Ok(Message(4)), rx.try_recv());
+    }
+
+    #[test]
+    fn resize_drop_oldest_grow() {
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+
+        for i in 1..=5 {
+            assert_eq!(Ok(()), tx.try_send(Message(i)));
+        }
+
+        // the receiver is behind the oldest kept message, and the slot at its position is empty
+        assert_eq!(Ok(()), tx.resize(8));
+        assert_eq!(
+            PollRecv::Ready(Err(RecvError::Lagged(3))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut noop_context())
+        );
+        assert_eq!(Ok(Message(4)), rx.try_recv());
+        assert_eq!(Ok(Message(5)), rx.try_recv());
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx.try_recv());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn concurrent_writers() {
+        // writers race on the head without a lock.  each message must claim a unique slot
+        let (tx, mut rx) = channel(4);
+
+        let threads: Vec<_> = (0..8usize)
+            .map(|writer| {
+                let mut tx = tx.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100usize {
+                        tx.blocking_send((writer, i)).expect("send failed");
+                    }
+                })
+            })
+            .collect();
+
+        drop(tx);
+
+        let mut next = [0usize; 8];
+        while let Some((writer, i)) = rx.blocking_recv() {
+            assert_eq!(next[writer], i);
+            next[writer] += 1;
+        }
+
+        for thread in threads {
+            thread.join().expect("join failed");
+        }
+
+        assert_eq!([100usize; 8], next);
+    }
+
+    #[test]
+    fn subscriber_events() {
+        let (tx, rx) = channel::<usize>(2);
+        let mut events = tx.subscriber_events();
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut events).poll_recv(&mut cx));
+
+        let rx2 = tx.subscribe();
+        let rx3 = rx.clone();
+        drop(rx);
+        drop(rx2);
+
+        assert_eq!(
+            PollRecv::Ready(SubscriberEvent::Joined { receivers: 2 }),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
         assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            PollRecv::Ready(SubscriberEvent::Joined { receivers: 3 }),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
+            PollRecv::Ready(SubscriberEvent::Left { receivers: 2 }),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
-
         assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx2).poll_recv(&mut cx)
+            PollRecv::Ready(SubscriberEvent::Left { receivers: 1 }),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut events).poll_recv(&mut cx));
+
+        drop(rx3);
+        drop(tx);
+
         assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx2).poll_recv(&mut cx)
+            PollRecv::Ready(SubscriberEvent::Left { receivers: 0 }),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx2).poll_recv(&mut noop_context())
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut events).poll_recv(&mut cx)
         );
     }
 
     #[test]
-    fn sender_disconnect() {
+    fn subscriber_events_wake() {
+        let (tx, _rx) = channel::<usize>(2);
+        let mut events = tx.subscriber_events();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut cx: Context<'_> = w1_context.into();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut events).poll_recv(&mut cx));
+
+        let _rx2 = tx.subscribe();
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn weak_sender_upgrade() {
         let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(100);
-        let mut tx2 = tx.clone();
+        let (tx, mut rx) = channel(100);
+        let weak = tx.downgrade();
 
+        let mut upgraded = weak.upgrade().expect("sender is alive");
         assert_eq!(
             PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+            Pin::new(&mut upgraded).poll_send(&mut cx, Message(1))
         );
+
         drop(tx);
-        drop(tx2);
+        drop(upgraded);
+
+        assert!(weak.upgrade().is_none());
         assert_eq!(
             PollRecv::Ready(Message(1)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
         assert_eq!(
-            PollRecv::Ready(Message(2)),
+            PollRecv::Closed(ClosedReason::SendersDropped),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn subscriber_hooks() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let (tx, rx) = channel::<Message>(2);
+        let first = Arc::new(AtomicUsize::new(0));
+        let last = Arc::new(AtomicUsize::new(0));
+
+        let count = first.clone();
+        tx.on_first_subscriber(move || {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+        let count = last.clone();
+        tx.on_last_unsubscribe(move || {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let rx2 = rx.clone();
+        drop(rx);
+        assert_eq!(0, first.load(Ordering::SeqCst));
+        assert_eq!(0, last.load(Ordering::SeqCst));
+
+        drop(rx2);
+        assert_eq!(0, first.load(Ordering::SeqCst));
+        assert_eq!(1, last.load(Ordering::SeqCst));
+
+        let rx = tx.subscribe();
+        let rx2 = rx.clone();
+        assert_eq!(1, first.load(Ordering::SeqCst));
+
+        drop(rx);
+        drop(rx2);
+        assert_eq!(2, last.load(Ordering::SeqCst));
+
+        let _rx = tx.subscribe();
+        assert_eq!(2, first.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn poll_closed() {
+        let (tx, rx) = channel::<Message>(2);
+        let rx2 = rx.clone();
+        assert!(!tx.is_closed());
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
+
+        drop(rx);
+        assert!(!tx.is_closed());
+        assert_eq!(0, w1_count.get());
+
+        drop(rx2);
+
+        assert_eq!(1, w1_count.get());
+        assert!(tx.is_closed());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
     }
 
     #[test]
@@ -606,6 +2002,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn drop_value_after_last_read() {
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+
+        let value = std::sync::Arc::new(vec![0u8; 16]);
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        let read = rx.try_recv().unwrap();
+        drop(read);
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        let read = rx2.try_recv().unwrap();
+        drop(read);
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn drop_value_after_receiver_dropped() {
+        let (mut tx, mut rx) = channel(2);
+        let rx2 = rx.clone();
+
+        let value = std::sync::Arc::new(vec![0u8; 16]);
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+
+        drop(rx.try_recv().unwrap());
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        drop(rx2);
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+    }
+
     #[test]
     fn wake_sender_on_disconnect() {
         let (mut tx, rx) = channel(2);
@@ -774,6 +2203,177 @@ mod tests {
             Pin::new(&mut tx).poll_send(&mut cx, Message(3))
         );
     }
+
+    #[test]
+    fn drop_oldest_send_never_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, _rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+
+        for i in 0..10 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+    }
+
+    #[test]
+    fn drop_oldest_reports_lag() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+
+        for i in 1..=5 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        assert_eq!(
+            PollRecv::Ready(Err(RecvError::Lagged(3))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Ok(Message(4))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Ok(Message(5))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn drop_oldest_stream_skips_lag() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+
+        for i in 2..=4 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(4)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(4)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn drop_oldest_closed() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn transform_runs_once_per_message() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let mut cx = noop_context();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transform_calls = calls.clone();
+
+        let (mut tx, mut rx) = super::channel_with_transform(4, move |raw: &usize| {
+            transform_calls.fetch_add(1, Ordering::SeqCst);
+            Message(*raw * 10)
+        });
+        let mut rx2 = rx.clone();
+        let mut rx3 = tx.subscribe();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+
+        for rx in [&mut rx, &mut rx2, &mut rx3] {
+            assert_eq!(
+                PollRecv::Ready(Message(10)),
+                Pin::new(&mut *rx).poll_recv(&mut cx)
+            );
+            assert_eq!(
+                PollRecv::Ready(Message(20)),
+                Pin::new(&mut *rx).poll_recv(&mut cx)
+            );
+        }
+
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn transform_sender_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = super::channel_with_transform(4, |raw: &usize| Message(*raw));
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn transform_receiver_disconnect() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = super::channel_with_transform(4, |raw: &usize| Message(*raw));
+
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut tx).poll_send(&mut cx, 1)
+        );
+    }
 }
 
 #[cfg(test)]
@@ -789,11 +2389,112 @@ mod tokio_tests {
     use crate::{
         stream::{Stream, TryRecvError},
         test::{
-            capacity_iter, Channel, Channels, Message, CHANNEL_TEST_RECEIVERS,
-            CHANNEL_TEST_SENDERS, TEST_TIMEOUT,
+            capacity_iter, Channel, Channels, Message, CHANNEL_TEST_ITERATIONS,
+            CHANNEL_TEST_RECEIVERS, CHANNEL_TEST_SENDERS, TEST_TIMEOUT,
         },
     };
 
+    #[tokio::test]
+    async fn send_latest() {
+        let (mut tx, mut rx) = super::channel(2);
+        let mut rx2 = rx.clone();
+
+        tx.send_latest(1usize).await.expect("send failed");
+        tx.send_latest(2usize).await.expect("send failed");
+        tx.send_latest(3usize).await.expect("send failed");
+
+        // the unread message was replaced, so the buffer has capacity
+        assert_eq!(Ok(()), tx.try_send(4usize));
+        assert_eq!(Ok(3), rx.try_recv());
+        assert_eq!(Ok(4), rx.try_recv());
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx.try_recv());
+
+        // rx has read 4, so it is not replaced, and 5 is sent once rx2 frees a slot
+        assert_eq!(Ok(3), rx2.try_recv());
+        tx.send_latest(5usize).await.expect("send failed");
+        assert_eq!(Ok(4), rx2.try_recv());
+        assert_eq!(Ok(5), rx2.try_recv());
+        assert_eq!(Ok(5), rx.try_recv());
+    }
+
+    #[tokio::test]
+    async fn resize_while_receiving() {
+        let (mut tx, mut rx) = super::channel(2);
+
+        let receiver = spawn(async move {
+            let mut next = 0usize;
+            while let Some(i) = rx.recv().await {
+                assert_eq!(next, i);
+                next += 1;
+            }
+
+            next
+        });
+
+        let resize = tx.clone();
+        for i in 0..CHANNEL_TEST_ITERATIONS {
+            if i % 64 == 0 {
+                let capacity = 2 + (i / 64) % 8;
+                // a shrink fails while unread messages don't fit
+                resize.resize(capacity).ok();
+            }
+
+            tx.send(i).await.expect("send failed");
+        }
+        drop(tx);
+        drop(resize);
+
+        let received = timeout(TEST_TIMEOUT, receiver)
+            .await
+            .expect("test timeout")
+            .expect("receiver panicked");
+        assert_eq!(CHANNEL_TEST_ITERATIONS, received);
+    }
+
+    #[tokio::test]
+    async fn subscriber_events() {
+        let (mut tx, rx) = super::channel(4);
+        let mut events = tx.subscriber_events();
+        drop(rx);
+
+        assert_eq!(
+            Some(super::SubscriberEvent::Left { receivers: 0 }),
+            events.recv().await
+        );
+
+        let subscriber = tx.clone();
+        let producer = tokio::task::spawn(async move {
+            // wait for an audience before producing data
+            while let Some(event) = events.recv().await {
+                if let super::SubscriberEvent::Joined { .. } = event {
+                    break;
+                }
+            }
+
+            tx.send(1usize).await.expect("send failed");
+            tx
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let mut rx = subscriber.subscribe();
+        assert_eq!(Some(1), rx.recv().await);
+
+        producer.await.expect("join failed");
+    }
+
+    #[tokio::test]
+    async fn send_latest_closed() {
+        let (mut tx, rx) = super::channel(2);
+
+        tx.send_latest(1usize).await.expect("send failed");
+        drop(rx);
+
+        assert_eq!(
+            Err(crate::sink::SendError(2usize)),
+            tx.send_latest(2usize).await
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn simple() {
         // crate::logging::enable_log();
@@ -891,6 +2592,45 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn drop_oldest_multi_receiver() {
+        for cap in capacity_iter() {
+            let (mut tx, rx) = super::channel_with_policy(cap, super::OverflowPolicy::DropOldest);
+
+            let handles: Vec<JoinHandle<()>> = (0..CHANNEL_TEST_RECEIVERS)
+                .map(|_| {
+                    let mut rx2 = rx.clone();
+                    let mut channels = Channels::new(1).allow_skips();
+
+                    spawn(async move {
+                        while let Some(message) = rx2.recv().await {
+                            channels.assert_message(&message);
+                        }
+                    })
+                })
+                .collect();
+
+            drop(rx);
+
+            spawn(async move {
+                for message in Message::new_iter(0) {
+                    tx.send(message).await.expect("send failed");
+                }
+            });
+
+            let rx_handle = spawn(async move {
+                for handle in handles {
+                    handle.await.expect("Assertion failure");
+                }
+            });
+
+            timeout(TEST_TIMEOUT, rx_handle)
+                .await
+                .expect("test timeout")
+                .expect("join failure");
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn multi_sender_multi_receiver() {
         // crate::logging::enable_log();
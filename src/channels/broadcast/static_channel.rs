@@ -0,0 +1,531 @@
+//! A statically allocated broadcast channel, with a const capacity.
+//!
+//! The buffer is stored inline, so a `StaticChannel` can be placed in a `static`, and the channel
+//! performs no heap allocation when it is created.  `split()` borrows the channel, and produces the sender and receiver.
+//! Like the heap-allocated channel, each receiver observes every message, and senders wait for the slowest receiver.
+//!
+//! The buffer is an inline `[Slot<T>; N]`, guarded by a single lock.  A slot holds the message, and the number of receivers which have
+//! not yet read it.  Unlike the heap-allocated slots, it needs no lock or notifiers of its own, so the channel can be built by a const fn.
+use core::{fmt, pin::Pin};
+
+use static_assertions::assert_impl_all;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{ClosedReason, PollRecv, Stream},
+    sync::{lock::Mutex, notifier::Notifier},
+    Context,
+};
+
+/// A fixed-capacity broadcast channel, with an inline buffer of `N` messages.
+///
+/// ```rust
+/// use postage::broadcast::StaticChannel;
+/// use postage::prelude::*;
+///
+/// static CHANNEL: StaticChannel<usize, 16> = StaticChannel::new();
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx, mut rx) = CHANNEL.split();
+///     let mut rx2 = tx.subscribe();
+///
+///     tx.send(1).await.ok();
+///     assert_eq!(Some(1), rx.recv().await);
+///     assert_eq!(Some(1), rx2.recv().await);
+/// }
+/// ```
+pub struct StaticChannel<T, const N: usize> {
+    state: Mutex<State<T, N>>,
+    // senders notify receivers, and receivers notify senders when a slot is released
+    on_send: Notifier,
+    on_release: Notifier,
+}
+
+// messages are identified by a sequential id, and stored in slot `id % N`.
+// messages in `tail..head` are buffered, and are released once every receiver has read them.
+struct State<T, const N: usize> {
+    slots: [Slot<T>; N],
+    head: u64,
+    tail: u64,
+    senders: usize,
+    receivers: usize,
+    split: bool,
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    // the number of receivers which have not yet read the message
+    remaining: usize,
+}
+
+impl<T> Slot<T> {
+    const EMPTY: Self = Self {
+        value: None,
+        remaining: 0,
+    };
+}
+
+impl<T, const N: usize> State<T, N> {
+    fn slot(&mut self, id: u64) -> &mut Slot<T> {
+        &mut self.slots[(id % N as u64) as usize]
+    }
+
+    /// Marks the message as read by one receiver.  Returns true if any slots were released.
+    fn mark_read(&mut self, id: u64) -> bool {
+        let slot = self.slot(id);
+        slot.remaining -= 1;
+        if slot.remaining == 0 {
+            slot.value = None;
+        }
+
+        let tail = self.tail;
+        while self.tail < self.head && self.slot(self.tail).remaining == 0 {
+            self.tail += 1;
+        }
+
+        self.tail != tail
+    }
+}
+
+assert_impl_all!(StaticChannel<usize, 4>: Send, Sync, fmt::Debug);
+assert_impl_all!(StaticSender<'static, usize, 4>: Send, Sync, Clone, fmt::Debug);
+assert_impl_all!(StaticReceiver<'static, usize, 4>: Send, Sync, Clone, fmt::Debug);
+
+impl<T, const N: usize> StaticChannel<T, N> {
+    /// Constructs the channel.  This is a const fn, so the channel can initialize a `static`.
+    ///
+    /// A capacity of zero fails to compile:
+    /// ```compile_fail
+    /// use postage::broadcast::StaticChannel;
+    ///
+    /// static CHANNEL: StaticChannel<usize, 0> = StaticChannel::new();
+    /// ```
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "a StaticChannel must have a capacity of at least 1") };
+
+        Self {
+            state: Mutex::new(State {
+                slots: [Slot::EMPTY; N],
+                head: 0,
+                tail: 0,
+                senders: 0,
+                receivers: 0,
+                split: false,
+            }),
+            on_send: Notifier::new(),
+            on_release: Notifier::new(),
+        }
+    }
+
+    /// The number of messages the channel can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Produces the sender and receiver.  The sender and receiver can be cloned, and the sender can subscribe new receivers.
+    ///
+    /// # Panics
+    /// Panics if the channel has already been split.
+    pub fn split(&self) -> (StaticSender<'_, T, N>, StaticReceiver<'_, T, N>) {
+        let mut state = self.state.lock();
+        assert!(!state.split, "StaticChannel::split called more than once");
+
+        state.split = true;
+        state.senders = 1;
+        state.receivers = 1;
+
+        (
+            StaticSender { channel: self },
+            StaticReceiver {
+                channel: self,
+                next: state.head,
+            },
+        )
+    }
+}
+
+impl<T, const N: usize> Default for StaticChannel<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for StaticChannel<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("StaticChannel")
+            .field("capacity", &N)
+            .field("len", &(state.head - state.tail))
+            .field("senders", &state.senders)
+            .field("receivers", &state.receivers)
+            .finish()
+    }
+}
+
+/// The sender half of a broadcast `StaticChannel`.  Can be cloned.
+pub struct StaticSender<'c, T, const N: usize> {
+    channel: &'c StaticChannel<T, N>,
+}
+
+impl<'c, T, const N: usize> StaticSender<'c, T, N> {
+    /// Subscribes to the channel, creating a new receiver.  The receiver
+    /// will observe all messages sent after the call to subscribe.
+    pub fn subscribe(&self) -> StaticReceiver<'c, T, N> {
+        let mut state = self.channel.state.lock();
+        state.receivers += 1;
+
+        StaticReceiver {
+            channel: self.channel,
+            next: state.head,
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Sink for StaticSender<'c, T, N> {
+    type Item = T;
+
+    fn poll_send(self: Pin<&mut Self>, cx: &mut Context<'_>, value: T) -> PollSend<T> {
+        let channel = self.channel;
+
+        loop {
+            let guard = channel.on_release.guard();
+
+            {
+                let mut state = channel.state.lock();
+                if state.receivers == 0 {
+                    return PollSend::Rejected(value);
+                }
+
+                if state.head - state.tail < N as u64 {
+                    let (head, receivers) = (state.head, state.receivers);
+                    *state.slot(head) = Slot {
+                        value: Some(value),
+                        remaining: receivers,
+                    };
+                    state.head += 1;
+                    drop(state);
+
+                    channel.on_send.notify();
+                    return PollSend::Ready;
+                }
+            }
+
+            channel.on_release.subscribe(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollSend::Pending(value);
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Clone for StaticSender<'c, T, N> {
+    fn clone(&self) -> Self {
+        self.channel.state.lock().senders += 1;
+
+        Self {
+            channel: self.channel,
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Drop for StaticSender<'c, T, N> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.senders -= 1;
+
+        if state.senders == 0 {
+            drop(state);
+            self.channel.on_send.notify();
+        }
+    }
+}
+
+impl<'c, T, const N: usize> fmt::Debug for StaticSender<'c, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSender").finish()
+    }
+}
+
+/// The receiver half of a broadcast `StaticChannel`.
+///
+/// When cloned, the new receiver will begin processing messages at the same location as the original.
+pub struct StaticReceiver<'c, T, const N: usize> {
+    channel: &'c StaticChannel<T, N>,
+    next: u64,
+}
+
+impl<'c, T, const N: usize> Stream for StaticReceiver<'c, T, N>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<T> {
+        let this = self.get_mut();
+        let channel = this.channel;
+
+        loop {
+            let guard = channel.on_send.guard();
+
+            {
+                let mut state = channel.state.lock();
+                if this.next < state.head {
+                    let id = this.next;
+                    let value = state.slot(id).value.clone().expect("unread slot is empty");
+
+                    this.next += 1;
+                    let released = state.mark_read(id);
+                    drop(state);
+
+                    if released {
+                        channel.on_release.notify();
+                    }
+
+                    return PollRecv::Ready(value);
+                }
+
+                if state.senders == 0 {
+                    return PollRecv::Closed(ClosedReason::SendersDropped);
+                }
+            }
+
+            channel.on_send.subscribe(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Clone for StaticReceiver<'c, T, N> {
+    fn clone(&self) -> Self {
+        let mut state = self.channel.state.lock();
+        state.receivers += 1;
+
+        // the clone must also read the messages this receiver hasn't read
+        for id in self.next..state.head {
+            state.slot(id).remaining += 1;
+        }
+
+        Self {
+            channel: self.channel,
+            next: self.next,
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Drop for StaticReceiver<'c, T, N> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.receivers -= 1;
+
+        let head = state.head;
+        for id in self.next..head {
+            state.mark_read(id);
+        }
+        drop(state);
+
+        self.channel.on_release.notify();
+    }
+}
+
+impl<'c, T, const N: usize> fmt::Debug for StaticReceiver<'c, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::Pin;
+
+    use super::StaticChannel;
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream, TryRecvError},
+        test::noop_context,
+    };
+    use futures_test::task::new_count_waker;
+
+    static CHANNEL: StaticChannel<usize, 2> = StaticChannel::new();
+
+    #[test]
+    fn static_placement() {
+        let (mut tx, mut rx) = CHANNEL.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(2, CHANNEL.capacity());
+    }
+
+    #[test]
+    #[should_panic(expected = "split called more than once")]
+    fn split_twice() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let _first = channel.split();
+        let _second = channel.split();
+    }
+
+    #[test]
+    fn every_receiver_reads_every_message() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+        let mut rx2 = tx.subscribe();
+
+        for i in 0..5 {
+            assert_eq!(Ok(()), tx.try_send(i));
+            assert_eq!(Ok(i), rx.try_recv());
+            assert_eq!(Ok(i), rx2.try_recv());
+        }
+    }
+
+    #[test]
+    fn slowest_receiver_applies_backpressure() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+        let mut rx2 = tx.subscribe();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), 3)
+        );
+
+        assert_eq!(Ok(1), rx2.try_recv());
+        assert_eq!(Ok(()), tx.try_send(3));
+        assert_eq!(Ok(2), rx2.try_recv());
+        assert_eq!(Ok(3), rx2.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+
+    #[test]
+    fn subscribe_starts_at_head() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, _rx) = channel.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        let mut rx2 = tx.subscribe();
+
+        assert_eq!(Err(TryRecvError::Pending), rx2.try_recv());
+    }
+
+    #[test]
+    fn clone_receiver_reads_unread_messages() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Ok(1), rx.try_recv());
+
+        let mut rx2 = rx.clone();
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(2), rx2.try_recv());
+        assert_eq!(Ok(()), tx.try_send(3));
+        assert_eq!(Ok(()), tx.try_send(4));
+    }
+
+    #[test]
+    fn drop_receiver_releases_slots() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+        let rx2 = tx.subscribe();
+
+        let (waker, count) = new_count_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut tx).poll_send(&mut cx, 3)
+        );
+
+        drop(rx2);
+        assert_eq!(1, count.get());
+        assert_eq!(Ok(()), tx.try_send(3));
+    }
+
+    #[test]
+    fn senders_dropped() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        drop(tx);
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn receivers_dropped() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, rx) = channel.split();
+
+        drop(rx);
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), 1)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use super::StaticChannel;
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{CHANNEL_TEST_ITERATIONS, CHANNEL_TEST_RECEIVERS, TEST_TIMEOUT},
+    };
+
+    static CHANNEL: StaticChannel<usize, 4> = StaticChannel::new();
+
+    #[tokio::test]
+    async fn multi_receiver() {
+        let (mut tx, rx) = CHANNEL.split();
+
+        let receivers: Vec<_> = (0..CHANNEL_TEST_RECEIVERS)
+            .map(|_| {
+                let mut rx = rx.clone();
+                spawn(async move {
+                    for i in 0..CHANNEL_TEST_ITERATIONS {
+                        assert_eq!(Some(i), rx.recv().await);
+                    }
+
+                    assert_eq!(None, rx.recv().await);
+                })
+            })
+            .collect();
+        drop(rx);
+
+        timeout(TEST_TIMEOUT, async move {
+            for i in 0..CHANNEL_TEST_ITERATIONS {
+                tx.send(i).await.expect("send failed");
+            }
+            drop(tx);
+
+            for receiver in receivers {
+                receiver.await.expect("receiver panicked");
+            }
+        })
+        .await
+        .expect("test timeout");
+    }
+}
@@ -1,15 +1,34 @@
 //! A fixed-capacity multi-producer, multi-consumer queue.  At most one receiver will observe each value.
 //!
+//! This is the MPMC work queue of postage, and is also available as `postage::mpmc`.  Messages are stored in a single array-based queue, with no per-receiver positions,
+//! so any number of producers and consumers share the capacity.  Use `Strategy::RoundRobin` for fair wakeups across consumers.
+//!
 //! Senders and recievers can be cloned, and additional recievers can be created with `tx.subscribe()`
 //!
 //! The producer can be cloned, and the sender task is suspended if the channel becomes full.
-
-use std::fmt;
+//!
+//! Receivers can declare a filter with `tx.subscribe_filtered(filter)` or `rx.clone_filtered(filter)`.
+//! Filtered receivers are only assigned messages which match the filter.  Other messages are left for the remaining receivers.
+//!
+//! By default, each message is received by whichever receiver polls first.  Channels created with
+//! `channel_with(capacity, Strategy::RoundRobin)` rotate deliveries across the waiting receivers, and
+//! `Strategy::Lifo` prefers the receiver which most recently became idle.
+//!
+//! Channels created with `channel_with_policy(capacity, OverflowPolicy::DropNewest)` or `OverflowPolicy::DropOldest`
+//! never suspend senders.  When the buffer is full, the new message or the oldest buffered message is discarded.
+
+use alloc::string::String;
+use alloc::{collections::VecDeque, sync::Arc};
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+};
 
 use super::SendMessage;
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    sink::{PollSend, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
     sync::{shared, ReceiverShared, SenderShared},
 };
 use crossbeam_queue::ArrayQueue;
@@ -17,30 +36,113 @@ use static_assertions::assert_impl_all;
 
 /// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    channel_with(capacity, Strategy::Race)
+}
+
+/// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity,
+/// and the given strategy for distributing messages among receivers.
+pub fn channel_with<T>(capacity: usize, strategy: Strategy) -> (Sender<T>, Receiver<T>) {
+    channel_with_options(capacity, strategy, OverflowPolicy::Block)
+}
+
+/// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity,
+/// and the given behavior when the buffer is full.
+pub fn channel_with_policy<T>(capacity: usize, policy: OverflowPolicy) -> (Sender<T>, Receiver<T>) {
+    channel_with_options(capacity, Strategy::Race, policy)
+}
+
+/// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity,
+/// the given strategy for distributing messages among receivers, and the given behavior when the buffer is full.
+pub fn channel_with_options<T>(
+    capacity: usize,
+    strategy: Strategy,
+    policy: OverflowPolicy,
+) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
-    log::error!("Creating dispatch channel with capacity {}", capacity);
-    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity));
-    let sender = Sender { shared: tx_shared };
+    log::error!(
+        "Creating dispatch channel with capacity {}, strategy {:?}, and policy {:?}",
+        capacity,
+        strategy,
+        policy
+    );
+    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity, strategy, policy));
+    let sender = Sender::new(tx_shared);
+
+    let receiver = Receiver::new(rx_shared, None);
+
+    (sender, receiver)
+}
 
-    let receiver = Receiver { shared: rx_shared };
+/// Constructs a pair of dispatch endpoints, with a fixed-size buffer of the given capacity.
+/// The channel reports events to the `metrics` recorder under the given name.
+///
+/// Requires the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub fn channel_named<T>(name: &str, capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx_shared, rx_shared) = crate::sync::shared_named(
+        name,
+        StateExtension::new(capacity, Strategy::Race, OverflowPolicy::Block),
+    );
+    let sender = Sender::new(tx_shared);
+
+    let receiver = Receiver::new(rx_shared, None);
 
     (sender, receiver)
 }
 
+/// Determines which receiver is assigned each message.
+///
+/// With `RoundRobin` and `Lifo`, a receiver which is waiting holds its turn until it receives a message,
+/// or is dropped.  A receiver only waits while a task is parked on it, so `try_recv` never takes a turn.
+/// The receive futures (`recv`, `recv_many`, `latest`, `recv_timeout`, and others) call `Stream::cancel_recv`
+/// when they are dropped before completing (for example, in `select!`), or time out, which gives up the turn.
+/// Combinators forward the call to the receiver.
+///
+/// Code which polls `poll_recv` directly, or `futures::StreamExt::next` through the `futures-traits` adapter,
+/// keeps the turn until the receiver is polled again or dropped.  Call `Stream::cancel_recv` when it stops waiting.
+/// Filtered receivers are not ordered, and receive matching messages whenever they poll.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    /// Each message is received by whichever receiver polls first.
+    #[default]
+    Race,
+    /// Messages are assigned to waiting receivers in the order they began waiting.
+    /// A receiver which was just assigned a message moves to the back of the line, so long-running jobs do not starve other receivers.
+    ///
+    /// Senders which are waiting for a slot are also served in the order they began waiting.
+    /// As with receivers, only a parked task holds a turn, and a cancelled send gives it up with `Sink::cancel_send`.
+    RoundRobin,
+    /// Messages are assigned to the receiver which most recently began waiting.
+    /// This keeps a small set of receivers busy, which is useful for cache-warm worker reuse.
+    Lifo,
+}
+
+/// Determines how a dispatch channel handles a full buffer.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Senders are suspended until a receiver takes a message.  No messages are lost.
+    #[default]
+    Block,
+    /// The message being sent is discarded.  The send succeeds, and buffered messages are kept.
+    DropNewest,
+    /// The oldest buffered message is discarded, to make room for the message being sent.
+    DropOldest,
+}
+
 /// The sender half of a dispatch channel.  Can send messages with the `postage::Sink` trait.
 ///
 /// Can be cloned.
 pub struct Sender<T> {
     shared: SenderShared<StateExtension<T>>,
+    // identifies the sender in the waiting list, for the RoundRobin strategy
+    id: usize,
 }
 
 assert_impl_all!(Sender<String>: Clone, Send, Sync, fmt::Debug);
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
-        Self {
-            shared: self.shared.clone(),
-        }
+        Self::new(self.shared.clone())
     }
 }
 
@@ -48,34 +150,196 @@ impl<T> Sink for Sender<T> {
     type Item = T;
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
-        mut value: Self::Item,
+        value: Self::Item,
     ) -> PollSend<Self::Item> {
+        // as with receivers, only a parked task holds a turn.  it is given up before the notification guard is acquired
+        if cx.waker().is_none() {
+            self.finish_turn();
+        }
+
         loop {
             if self.shared.is_closed() {
+                self.finish_turn();
                 return PollSend::Rejected(value);
             }
 
-            let queue = &self.shared.extension().queue;
+            let extension = self.shared.extension();
             let guard = self.shared.recv_guard();
 
-            match queue.push(value) {
-                Ok(_) => {
+            if extension.is_send_turn(self.id) && extension.try_acquire_with_policy() {
+                // the receiver may have closed the channel while the slot was acquired
+                if self.shared.is_closed() {
+                    extension.cancel();
                     self.shared.notify_receivers();
-                    return PollSend::Ready;
+                    self.finish_turn();
+                    return PollSend::Rejected(value);
                 }
-                Err(v) => {
-                    self.shared.subscribe_recv(cx);
-                    if guard.is_expired() {
-                        value = v;
-                        continue;
-                    }
 
-                    return PollSend::Pending(v);
-                }
+                extension.push(value);
+                self.shared.notify_receivers();
+                self.finish_turn();
+
+                #[cfg(feature = "metrics")]
+                self.shared.record(crate::metrics::Event::Send);
+
+                #[cfg(feature = "diagnostics")]
+                self.shared.record_write();
+
+                return PollSend::Ready;
+            }
+
+            if extension.policy == OverflowPolicy::DropNewest {
+                extension.discard(value);
+                return PollSend::Ready;
+            }
+
+            if cx.waker().is_some() {
+                extension.wait_send(self.id);
+            }
+
+            self.shared.subscribe_recv(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            #[cfg(feature = "metrics")]
+            self.shared.record(crate::metrics::Event::SendBlocked);
+
+            return PollSend::Pending(value);
+        }
+    }
+
+    fn cancel_send(self: core::pin::Pin<&mut Self>) {
+        // a sender whose send future was cancelled gives up its turn
+        self.finish_turn();
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.finish_turn();
+    }
+}
+
+impl<T> Sender<T> {
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.waiters()
+    }
+
+    /// Reports the parked tasks, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        self.shared.diagnostics()
+    }
+
+    /// The number of messages in the channel which have not yet been received.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// True if the channel contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().capacity()
+    }
+
+    /// The number of messages which have been discarded by the channel's `OverflowPolicy`.
+    pub fn dropped(&self) -> usize {
+        self.shared.extension().dropped()
+    }
+
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Pending(value))` if the channel is full.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
+    }
+
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
+
+    /// Returns true if every receiver has been dropped, or a receiver closed the channel.  Messages sent to a closed channel are rejected.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+
+    /// Returns `Poll::Ready(())` if the channel is closed.  Otherwise, registers the task to be woken when it is closed.
+    pub fn poll_closed(&self, cx: &mut crate::Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.shared.recv_guard();
+
+            if self.shared.is_closed() {
+                return Poll::Ready(());
+            }
+
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
             }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Waits until the channel is closed.
+    ///
+    /// This allows a producer to stop generating messages as soon as they are no longer needed,
+    /// for example with `select!` on `tx.closed()` and the work.
+    pub async fn closed(&self) {
+        core::future::poll_fn(|cx| self.poll_closed(&mut cx.into())).await
+    }
+
+    /// Schedules a message, which becomes available to receivers once the delay has elapsed.
+    ///
+    /// Scheduled messages are held outside the channel buffer, and do not count towards its capacity.
+    /// Receivers will continue to observe scheduled messages after all the senders have been dropped.
+    ///
+    /// Returns `Err(SendError(value))` if all the receivers have been dropped.
+    ///
+    /// Requires the `timer` feature.
+    #[cfg(feature = "timer")]
+    pub fn send_after(
+        &mut self,
+        value: T,
+        delay: core::time::Duration,
+    ) -> Result<(), crate::sink::SendError<T>> {
+        if self.shared.is_closed() {
+            return Err(crate::sink::SendError(value));
         }
+
+        let deadline = crate::sync::timer::now() + delay;
+        self.shared
+            .extension()
+            .delayed
+            .lock()
+            .insert(deadline, value);
+        self.shared.notify_receivers();
+
+        Ok(())
     }
 }
 
@@ -87,27 +351,30 @@ impl<T> fmt::Debug for Sender<T> {
 
 #[cfg(feature = "futures-traits")]
 mod impl_futures {
+    use super::OverflowPolicy;
     use crate::sink::SendError;
-    use std::task::Poll;
+    use core::task::Poll;
 
     impl<T> futures::sink::Sink<T> for super::Sender<T> {
         type Error = SendError<T>;
 
         fn poll_ready(
-            self: std::pin::Pin<&mut Self>,
-            cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             loop {
                 if self.shared.is_closed() {
                     return Poll::Ready(Ok(()));
                 }
 
-                let queue = &self.shared.extension().queue;
                 let guard = self.shared.recv_guard();
 
-                if queue.is_full() {
-                    let mut cx = cx.into();
-                    self.shared.subscribe_recv(&mut cx);
+                // full channels which drop messages are always ready to accept another
+                if self.shared.extension().policy == OverflowPolicy::Block
+                    && !self.shared.extension().has_permits()
+                {
+                    let cx = cx.into();
+                    self.shared.subscribe_recv(&cx);
 
                     if guard.is_expired() {
                         continue;
@@ -120,35 +387,49 @@ mod impl_futures {
             }
         }
 
-        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        fn start_send(self: core::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
             if self.shared.is_closed() {
                 return Err(SendError(item));
             }
 
-            let result = self
-                .shared
-                .extension()
-                .queue
-                .push(item)
-                .map_err(|item| SendError(item));
+            let extension = self.shared.extension();
+            if !extension.try_acquire_with_policy() {
+                if extension.policy == OverflowPolicy::DropNewest {
+                    extension.discard(item);
+                    return Ok(());
+                }
+
+                return Err(SendError(item));
+            }
 
-            if result.is_ok() {
+            if self.shared.is_closed() {
+                extension.cancel();
                 self.shared.notify_receivers();
+                return Err(SendError(item));
             }
 
-            result
+            extension.push(item);
+            self.shared.notify_receivers();
+
+            #[cfg(feature = "metrics")]
+            self.shared.record(crate::metrics::Event::Send);
+
+            #[cfg(feature = "diagnostics")]
+            self.shared.record_write();
+
+            Ok(())
         }
 
         fn poll_flush(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
         fn poll_close(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
@@ -156,19 +437,50 @@ mod impl_futures {
 }
 
 impl<T> Sender<T> {
+    fn new(shared: SenderShared<StateExtension<T>>) -> Self {
+        let id = shared
+            .extension()
+            .next_sender_id
+            .fetch_add(1, Ordering::Relaxed);
+
+        Self { shared, id }
+    }
+
+    /// Removes the sender from the waiting list, after it sent a message or stopped waiting.
+    /// If it held the turn, the next sender in line may be able to take a free slot, so senders are notified.
+    fn finish_turn(&self) {
+        if self.shared.extension().finish_send_waiting(self.id) {
+            self.shared.notify_self();
+        }
+    }
+
     /// Creates a new Receiver that listens to this channel.
     pub fn subscribe(&self) -> Receiver<T> {
-        Receiver {
-            shared: self.shared.clone_receiver(),
-        }
+        Receiver::new(self.shared.clone_receiver(), None)
+    }
+
+    /// Creates a new Receiver that is only assigned messages which match the filter.
+    ///
+    /// Messages which do not match are left in the channel for other receivers, and continue to occupy capacity.
+    /// If no receiver matches a message, it remains buffered until one is created.
+    pub fn subscribe_filtered<F>(&self, filter: F) -> Receiver<T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Receiver::new(self.shared.clone_receiver(), Some(Arc::new(filter)))
     }
 }
 
+type Filter<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
 /// The receiver half of a dispatch channel.
 ///
 /// Can receive messages with the `postage::Stream` trait.
 pub struct Receiver<T> {
     shared: ReceiverShared<StateExtension<T>>,
+    filter: Option<Filter<T>>,
+    // identifies the receiver in the waiting list, for the RoundRobin and Lifo strategies
+    id: usize,
 }
 
 assert_impl_all!(Receiver<SendMessage>: Clone, Send, Sync, fmt::Debug);
@@ -177,38 +489,200 @@ impl<T> Stream for Receiver<T> {
     type Item = T;
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
+        let ordered = self.filter.is_none();
+
+        // only a parked task can hold a turn.  a receiver polled without a waker (such as `try_recv`) gives it up,
+        // before the notification guard is acquired so the receiver is not woken by its own notification
+        if cx.waker().is_none() {
+            self.finish_turn(ordered);
+        }
+
         loop {
             let guard = self.shared.send_guard();
-            match self.shared.extension().queue.pop() {
-                Some(v) => {
+            let extension = self.shared.extension();
+
+            if !ordered || extension.is_turn(self.id) {
+                let (value, skipped) = extension.pop(self.filter.as_ref());
+
+                if skipped {
+                    self.shared.notify_receivers();
+                }
+
+                if let Some(v) = value {
+                    self.finish_turn(ordered);
                     self.shared.notify_senders();
+
+                    #[cfg(feature = "metrics")]
+                    self.shared.record(crate::metrics::Event::Recv);
+
                     return PollRecv::Ready(v);
                 }
-                None => {
-                    if self.shared.is_closed() {
-                        return PollRecv::Closed;
-                    }
 
-                    self.shared.subscribe_send(cx);
-                    if guard.is_expired() {
+                #[cfg(feature = "timer")]
+                let scheduled = match extension.poll_delayed(self.filter.as_ref(), cx) {
+                    PollRecv::Ready(v) => {
+                        self.finish_turn(ordered);
+
+                        #[cfg(feature = "metrics")]
+                        self.shared.record(crate::metrics::Event::Recv);
+
+                        return PollRecv::Ready(v);
+                    }
+                    PollRecv::Pending => true,
+                    PollRecv::Closed(_) => false,
+                };
+                #[cfg(not(feature = "timer"))]
+                let scheduled = false;
+
+                // wait for senders which acquired a slot before the channel was closed
+                if self.shared.is_closed() && !scheduled && !extension.is_sending() {
+                    if !extension.queue.is_empty() {
                         continue;
                     }
 
-                    return PollRecv::Pending;
+                    return PollRecv::Closed(self.shared.closed_reason());
                 }
+            } else if self.shared.is_closed() && !extension.is_sending() && extension.is_drained() {
+                return PollRecv::Closed(self.shared.closed_reason());
+            }
+
+            if ordered && cx.waker().is_some() {
+                extension.wait(self.id);
+            }
+
+            self.shared.subscribe_send(cx);
+            if guard.is_expired() {
+                continue;
             }
+
+            return PollRecv::Pending;
+        }
+    }
+
+    fn cancel_recv(self: core::pin::Pin<&mut Self>) {
+        // a receiver whose recv future was cancelled gives up its turn
+        self.finish_turn(self.filter.is_none());
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.waiters()
+    }
+
+    /// Reports the parked tasks, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        self.shared.diagnostics()
+    }
+
+    /// The number of messages in the channel which have not yet been received.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// True if the channel contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().capacity()
+    }
+
+    fn new(shared: ReceiverShared<StateExtension<T>>, filter: Option<Filter<T>>) -> Self {
+        let id = shared.extension().next_id.fetch_add(1, Ordering::Relaxed);
+
+        Self { shared, filter, id }
+    }
+
+    /// Removes the receiver from the waiting list, after it was assigned a message or stopped waiting.
+    /// If it held the turn, the next receiver in line may be able to take a buffered message, so receivers are notified.
+    fn finish_turn(&self, ordered: bool) {
+        if ordered && self.shared.extension().finish_waiting(self.id) {
+            self.shared.notify_receivers();
         }
     }
+
+    /// Creates a new Receiver that is only assigned messages which match the filter.
+    ///
+    /// Messages which do not match are left in the channel for other receivers, and continue to occupy capacity.
+    pub fn clone_filtered<F>(&self, filter: F) -> Receiver<T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Receiver::new(self.shared.clone(), Some(Arc::new(filter)))
+    }
+
+    /// Closes the channel, for all receivers.
+    ///
+    /// Subsequent sends are rejected, and waiting senders are woken.  Messages which are already buffered can still be received.
+    /// Once they have been drained, receivers produce `ClosedReason::Closed`.
+    pub fn close(&mut self) {
+        self.shared.close();
+        self.shared.notify_receivers();
+    }
+
+    /// Closes the channel, and returns a stream of the messages which remain in the channel.
+    ///
+    /// Messages which are already buffered, or scheduled for delayed delivery, are produced,
+    /// and then the stream closes.  Useful in shutdown sequences, which can't rely on every sender being dropped.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.close();
+        Drain { receiver: self }
+    }
+
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
+    }
+
+    /// Converts the receiver into an iterator, which blocks the current thread until each message is received.
+    /// The iterator ends when the channel is closed.  Equivalent to `Stream::into_blocking_iter`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn into_blocking_iter(self) -> impl Iterator<Item = T> {
+        Stream::into_blocking_iter(self)
+    }
 }
 
 impl<T> Clone for Receiver<T> {
     fn clone(&self) -> Self {
-        Self {
-            shared: self.shared.clone(),
-        }
+        Self::new(self.shared.clone(), self.filter.clone())
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // a receiver which is dropped while waiting gives up its turn
+        self.finish_turn(self.filter.is_none());
     }
 }
 
@@ -218,306 +692,1263 @@ impl<T> fmt::Debug for Receiver<T> {
     }
 }
 
+/// A stream of the messages which remained in a closed channel.  Created by `Receiver::drain`.
+pub struct Drain<'r, T> {
+    receiver: &'r mut Receiver<T>,
+}
+
+impl<'r, T> Stream for Drain<'r, T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        core::pin::Pin::new(&mut *self.get_mut().receiver).poll_recv(cx)
+    }
+
+    fn cancel_recv(self: core::pin::Pin<&mut Self>) {
+        core::pin::Pin::new(&mut *self.get_mut().receiver).cancel_recv()
+    }
+}
+
+impl<'r, T> fmt::Debug for Drain<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").finish()
+    }
+}
+
 struct StateExtension<T> {
     queue: ArrayQueue<T>,
+    // messages which were popped by a filtered receiver, but did not match the filter.  these are delivered before the queue.
+    skipped: crate::sync::lock::Mutex<VecDeque<T>>,
+    skipped_len: AtomicUsize,
+    // the number of unreserved slots.  skipped messages continue to hold their slot until they are received.
+    permits: AtomicUsize,
+    // the number of acquired slots which have not yet been pushed, or cancelled.  a closed receiver drains until this reaches zero.
+    sending: AtomicUsize,
+    #[cfg(feature = "timer")]
+    delayed: crate::sync::lock::Mutex<crate::sync::timing_wheel::TimingWheel<T>>,
+    strategy: Strategy,
+    policy: OverflowPolicy,
+    // the number of messages discarded by the overflow policy
+    dropped: AtomicUsize,
+    // the ids of unfiltered receivers which are waiting for a message, in the order they began waiting.  unused by `Strategy::Race`.
+    waiting: crate::sync::lock::Mutex<VecDeque<usize>>,
+    next_id: AtomicUsize,
+    // the ids of senders which are waiting for a slot, in the order they began waiting.  only used by `Strategy::RoundRobin`.
+    waiting_senders: crate::sync::lock::Mutex<VecDeque<usize>>,
+    next_sender_id: AtomicUsize,
 }
 
 impl<T> StateExtension<T> {
-    pub fn new(capacity: usize) -> Self {
+    pub fn new(capacity: usize, strategy: Strategy, policy: OverflowPolicy) -> Self {
         Self {
             queue: ArrayQueue::new(capacity),
+            skipped: crate::sync::lock::Mutex::new(VecDeque::new()),
+            skipped_len: AtomicUsize::new(0),
+            permits: AtomicUsize::new(capacity),
+            sending: AtomicUsize::new(0),
+            #[cfg(feature = "timer")]
+            delayed: crate::sync::lock::Mutex::new(crate::sync::timing_wheel::TimingWheel::new()),
+            strategy,
+            policy,
+            dropped: AtomicUsize::new(0),
+            waiting: crate::sync::lock::Mutex::new(VecDeque::new()),
+            next_id: AtomicUsize::new(0),
+            waiting_senders: crate::sync::lock::Mutex::new(VecDeque::new()),
+            next_sender_id: AtomicUsize::new(0),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{pin::Pin, task::Context};
+    /// True if the receiver may take a message, according to the strategy.
+    pub fn is_turn(&self, id: usize) -> bool {
+        match self.strategy {
+            Strategy::Race => true,
+            // receivers which are not waiting get in line behind the receivers which are
+            Strategy::RoundRobin => self
+                .waiting
+                .lock()
+                .front()
+                .map_or(true, |front| *front == id),
+            // receivers which are not waiting are the most recently active
+            Strategy::Lifo => {
+                let waiting = self.waiting.lock();
+                !waiting.contains(&id) || waiting.back() == Some(&id)
+            }
+        }
+    }
 
-    use crate::{
-        sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
-        test::{noop_context, panic_context},
-    };
-    use futures_test::task::new_count_waker;
+    /// Adds the receiver to the waiting list, if it is not already waiting.
+    pub fn wait(&self, id: usize) {
+        if self.strategy == Strategy::Race {
+            return;
+        }
 
-    use super::{channel, Receiver, Sender};
+        let mut waiting = self.waiting.lock();
+        if !waiting.contains(&id) {
+            waiting.push_back(id);
+        }
+    }
 
-    fn pin<'a, 'b>(
-        chan: &mut (Sender<Message>, Receiver<Message>),
-    ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
-        let tx = Pin::new(&mut chan.0);
-        let rx = Pin::new(&mut chan.1);
+    /// Removes the receiver from the waiting list.  Returns true if it held the turn, and other receivers are still waiting.
+    pub fn finish_waiting(&self, id: usize) -> bool {
+        let mut waiting = self.waiting.lock();
+        let held = match self.strategy {
+            Strategy::Race => return false,
+            Strategy::RoundRobin => waiting.front() == Some(&id),
+            Strategy::Lifo => waiting.back() == Some(&id),
+        };
+
+        waiting.retain(|waiting_id| *waiting_id != id);
+        held && !waiting.is_empty()
+    }
 
-        (tx, rx)
+    /// True if the sender may acquire a slot.  With `Strategy::RoundRobin`, senders which are not waiting get in line behind the senders which are.
+    pub fn is_send_turn(&self, id: usize) -> bool {
+        self.strategy != Strategy::RoundRobin
+            || self
+                .waiting_senders
+                .lock()
+                .front()
+                .map_or(true, |front| *front == id)
     }
 
-    #[derive(Debug, PartialEq, Eq)]
-    struct Message(usize);
+    /// Adds the sender to the waiting list, if it is not already waiting.
+    pub fn wait_send(&self, id: usize) {
+        if self.strategy != Strategy::RoundRobin {
+            return;
+        }
 
-    #[test]
-    fn send_accepted() {
-        let mut cx = panic_context();
-        let mut chan = channel(2);
-        let (tx, _) = pin(&mut chan);
+        let mut waiting = self.waiting_senders.lock();
+        if !waiting.contains(&id) {
+            waiting.push_back(id);
+        }
+    }
 
-        assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+    /// Removes the sender from the waiting list.  Returns true if it held the turn, and other senders are still waiting.
+    pub fn finish_send_waiting(&self, id: usize) -> bool {
+        if self.strategy != Strategy::RoundRobin {
+            return false;
+        }
+
+        let mut waiting = self.waiting_senders.lock();
+        let held = waiting.front() == Some(&id);
+        waiting.retain(|waiting_id| *waiting_id != id);
+        held && !waiting.is_empty()
     }
 
-    #[test]
-    fn send_blocks() {
-        let mut cx = panic_context();
-        let (mut tx, _rx) = channel(2);
+    /// True if no messages are buffered or scheduled.
+    pub fn is_drained(&self) -> bool {
+        #[cfg(feature = "timer")]
+        {
+            let delayed = self.delayed.lock();
+            if delayed.next_deadline().is_some() || delayed.has_expired() {
+                return false;
+            }
+        }
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+        self.queue.is_empty() && self.skipped_len.load(Ordering::Acquire) == 0
     }
 
-    #[test]
-    fn send_recv() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(2);
+    /// The number of buffered messages, including messages skipped by filtered receivers.  Scheduled messages are not counted.
+    pub fn len(&self) -> usize {
+        self.queue.len() + self.skipped_len.load(Ordering::Acquire)
+    }
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
-        );
-        assert_eq!(
-            PollSend::Pending(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
-        );
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        let acquired = self
+            .permits
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |permits| {
+                permits.checked_sub(1)
+            })
+            .is_ok();
+
+        if acquired {
+            self.sending.fetch_add(1, Ordering::SeqCst);
+        }
+
+        acquired
+    }
+
+    /// Acquires a slot.  If the buffer is full and the policy is `DropOldest`, the oldest message is evicted,
+    /// and its slot is transferred to the caller.
+    pub fn try_acquire_with_policy(&self) -> bool {
+        if self.try_acquire() {
+            return true;
+        }
+
+        if self.policy != OverflowPolicy::DropOldest {
+            return false;
+        }
+
+        // skipped messages were popped from the queue before any message which remains in it
+        let mut evicted = None;
+        if self.skipped_len.load(Ordering::Acquire) > 0 {
+            let mut skipped = self.skipped.lock();
+            evicted = skipped.pop_front();
+            self.skipped_len.store(skipped.len(), Ordering::Release);
+        }
+
+        // the queue may be empty if every slot is held by a sender which has not yet pushed
+        match evicted.or_else(|| self.queue.pop()) {
+            Some(value) => {
+                self.sending.fetch_add(1, Ordering::SeqCst);
+                self.discard(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Discards a message, and counts it as dropped.
+    pub fn discard(&self, value: T) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        drop(value);
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns an acquired slot which was not used.
+    pub fn cancel(&self) {
+        self.sending.fetch_sub(1, Ordering::SeqCst);
+        self.release();
+    }
+
+    fn release(&self) {
+        self.permits.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn is_sending(&self) -> bool {
+        self.sending.load(Ordering::SeqCst) > 0
+    }
+
+    #[cfg(feature = "futures-traits")]
+    pub fn has_permits(&self) -> bool {
+        self.permits.load(Ordering::Acquire) > 0
+    }
+
+    /// Pushes a value into the queue.  The caller must hold a permit.
+    pub fn push(&self, value: T) {
+        if self.queue.push(value).is_err() {
+            panic!("dispatch queue overflow: a value was pushed without a permit");
+        }
+
+        self.sending.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Takes the oldest message which matches the filter, releasing its permit.
+    ///
+    /// Messages which do not match are moved to the skipped list.  Returns true if any were skipped,
+    /// in which case other receivers should be notified.
+    pub fn pop(&self, filter: Option<&Filter<T>>) -> (Option<T>, bool) {
+        let accepts = |value: &T| filter.map(|filter| filter(value)).unwrap_or(true);
+
+        if self.skipped_len.load(Ordering::Acquire) > 0 {
+            let mut skipped = self.skipped.lock();
+            if let Some(index) = skipped.iter().position(accepts) {
+                let value = skipped.remove(index);
+                self.skipped_len.store(skipped.len(), Ordering::Release);
+                self.release();
+
+                return (value, false);
+            }
+        }
+
+        let mut skipped_any = false;
+        while let Some(value) = self.queue.pop() {
+            if accepts(&value) {
+                self.release();
+                return (Some(value), skipped_any);
+            }
+
+            let mut skipped = self.skipped.lock();
+            skipped.push_back(value);
+            self.skipped_len.store(skipped.len(), Ordering::Release);
+            skipped_any = true;
+        }
+
+        (None, skipped_any)
+    }
+
+    /// Takes an expired message which matches the filter from the timing wheel.  If messages are scheduled but not yet expired,
+    /// registers the waker with the timer and returns Pending.  Returns Closed if nothing is scheduled.
+    #[cfg(feature = "timer")]
+    fn poll_delayed(&self, filter: Option<&Filter<T>>, cx: &mut crate::Context<'_>) -> PollRecv<T> {
+        let mut delayed = self.delayed.lock();
+        let now = crate::sync::timer::now();
+
+        let value = match filter {
+            Some(filter) => delayed.pop_expired_matching(now, |value| filter(value)),
+            None => delayed.pop_expired(now),
+        };
+
+        if let Some(value) = value {
+            return PollRecv::Ready(value);
+        }
+
+        match delayed.next_deadline() {
+            Some(deadline) => {
+                if let Some(waker) = cx.waker() {
+                    crate::sync::timer::wake_at(deadline, waker.clone());
+                }
+
+                PollRecv::Pending
+            }
+            // expired messages which the filter rejected are left for other receivers
+            None if delayed.has_expired() => PollRecv::Pending,
+            None => PollRecv::Closed(crate::stream::ClosedReason::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream},
+        test::{noop_context, panic_context},
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{
+        channel, channel_with, channel_with_options, channel_with_policy, OverflowPolicy, Receiver,
+        Sender, Strategy,
+    };
+
+    fn pin<'a, 'b>(
+        chan: &mut (Sender<Message>, Receiver<Message>),
+    ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
+        let tx = Pin::new(&mut chan.0);
+        let rx = Pin::new(&mut chan.1);
+
+        (tx, rx)
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Message(usize);
+
+    #[test]
+    fn round_robin() {
+        let waker = futures_test::task::noop_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        for i in 1..=3 {
+            assert_eq!(Ok(()), tx.try_send(Message(i)));
+        }
+
+        // rx1 began waiting first
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx1).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx1).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn lifo() {
+        let waker = futures_test::task::noop_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+        let (mut tx, mut rx1) = channel_with(4, Strategy::Lifo);
+        let mut rx2 = rx1.clone();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+
+        // rx2 began waiting most recently
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn round_robin_drop_waiting() {
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        let waker = futures_test::task::noop_waker();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx1).poll_recv(&mut crate::Context::from_waker(&waker))
+        );
+
+        let (w2, w2_count) = new_count_waker();
+        let w2_context = Context::from_waker(&w2);
+        let mut w2_context: crate::Context<'_> = w2_context.into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut w2_context)
+        );
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut w2_context)
+        );
+
+        let woken = w2_count.get();
+        drop(rx1);
+        assert!(w2_count.get() > woken);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut w2_context)
+        );
+    }
+
+    #[test]
+    fn round_robin_closed() {
+        let waker = futures_test::task::noop_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+        let (tx, mut rx1) = channel_with::<Message>(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx1).poll_recv(&mut cx));
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx1).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn round_robin_try_recv_does_not_take_turn() {
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx1.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+
+        assert_eq!(Ok(Message(1)), rx2.try_recv());
+        assert_eq!(Ok(Message(2)), rx2.try_recv());
+    }
+
+    #[test]
+    fn round_robin_try_recv_while_waiting() {
+        use std::future::Future;
+
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        let mut recv = rx1.recv();
+        let mut cx = futures_test::task::noop_context();
+        assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+
+        // rx1 holds the turn, so rx2 waits behind it rather than spinning
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx2.try_recv());
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx2.try_recv());
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(
+            std::task::Poll::Ready(Some(Message(1))),
+            Pin::new(&mut recv).poll(&mut cx)
+        );
+    }
+
+    #[test]
+    fn lifo_try_recv_while_waiting() {
+        use std::future::Future;
+
+        let (_tx, mut rx1) = channel_with::<Message>(4, Strategy::Lifo);
+        let mut rx2 = rx1.clone();
+
+        let mut recv = rx1.recv();
+        let mut cx = futures_test::task::noop_context();
+        assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+
+        assert_eq!(Err(crate::stream::TryRecvError::Pending), rx2.try_recv());
+    }
+
+    #[test]
+    fn round_robin_cancelled_recv() {
+        use std::future::Future;
+
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        // rx1 begins waiting, and then the recv future is cancelled
+        {
+            let mut recv = rx1.recv();
+            let mut cx = futures_test::task::noop_context();
+            assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+        }
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+
+        assert_eq!(Ok(Message(1)), rx2.try_recv());
+        assert_eq!(Ok(Message(2)), rx2.try_recv());
+    }
+
+    #[test]
+    fn round_robin_recv_timeout() {
+        use crate::stream::RecvTimeoutError;
+        use crate::test::timer::ExpiredTimer;
+        use std::{future::Future, time::Duration};
+
+        let (mut tx, mut rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+
+        // rx1 begins waiting, and times out
+        let mut timeout = rx1.recv_timeout(Duration::from_millis(10), ExpiredTimer);
+        let mut cx = futures_test::task::noop_context();
+        assert_eq!(
+            std::task::Poll::Ready(Err(RecvTimeoutError::Timeout)),
+            Pin::new(&mut timeout).poll(&mut cx)
+        );
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(Message(1)), rx2.try_recv());
+    }
+
+    #[test]
+    fn round_robin_cancelled_combinator() {
+        use std::future::Future;
+
+        let (mut tx, rx1) = channel_with(4, Strategy::RoundRobin);
+        let mut rx2 = rx1.clone();
+        let mut rx1 = rx1.map(|message: Message| message.0).fuse();
+
+        {
+            let mut recv = rx1.recv();
+            let mut cx = futures_test::task::noop_context();
+            assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+        }
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(Message(1)), rx2.try_recv());
+    }
+
+    #[test]
+    fn send_accepted() {
+        let mut cx = panic_context();
+        let mut chan = channel(2);
+        let (tx, _) = pin(&mut chan);
+
+        assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+    }
+
+    #[test]
+    fn send_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, _rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+    }
+
+    #[test]
+    fn send_recv() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn try_send_try_recv() {
+        use crate::{sink::TrySendError, stream::TryRecvError};
+
+        let (mut tx, mut rx) = channel(1);
+
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(
+            Err(TrySendError::Pending(Message(2))),
+            tx.try_send(Message(2))
+        );
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+
+        drop(tx);
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    }
+
+    #[test]
+    fn len_capacity() {
+        let (mut tx, mut rx) = channel(4);
+        let mut even = rx.clone_filtered(|message: &Message| message.0 % 2 == 0);
+
+        assert_eq!(4, tx.capacity());
+        assert!(tx.is_empty());
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(2, tx.len());
+
+        // the skipped message is still counted
+        assert_eq!(Ok(Message(2)), even.try_recv());
+        assert_eq!(1, rx.len());
+
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+        assert!(rx.is_empty());
+        assert_eq!(4, rx.capacity());
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(100);
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+        );
+
+        drop(tx);
+        drop(tx2);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn drain() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        let mut drain = rx.drain();
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut drain).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut drain).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut drain).poll_recv(&mut cx)
+        );
+
+        // the channel is closed for every receiver
+        assert_eq!(
+            PollSend::Rejected(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn poll_closed() {
+        use std::task::Poll;
+
+        let (tx, rx) = channel::<Message>(2);
+        let rx2 = rx.clone();
+        assert!(!tx.is_closed());
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
+
+        drop(rx);
+        assert!(!tx.is_closed());
+
+        drop(rx2);
+
+        assert_eq!(1, w1_count.get());
+        assert!(tx.is_closed());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
+    }
+
+    #[test]
+    fn receiver_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, rx) = channel(100);
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+        );
+
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+        );
+
+        assert_eq!(
+            PollSend::Rejected(Message(4)),
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(4))
+        );
+    }
+
+    #[test]
+    fn wake_sender() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(1);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        let (w2, w2_count) = new_count_waker();
+        let w2_context = Context::from_waker(&w2);
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w2_context.into(), Message(2))
+        );
+
+        assert_eq!(0, w2_count.get());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(1, w2_count.get());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+
+        assert_eq!(1, w2_count.get());
+    }
+
+    #[test]
+    fn wake_receiver() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(100);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(0, w1_count.get());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(1, w1_count.get());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn wake_sender_on_disconnect() {
+        let (mut tx, rx) = channel(1);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        let mut w1_context: crate::Context<'_> = w1_context.into();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(1))
+        );
+
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+        );
+
+        assert_eq!(0, w1_count.get());
+
+        drop(rx);
+
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn wake_receivers_on_disconnect() {
+        let (tx, mut rx) = channel::<()>(100);
+        let mut rx2 = rx.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+
+        let (w2, w2_count) = new_count_waker();
+        let w2_context = Context::from_waker(&w2);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut w2_context.into())
+        );
+
+        assert_eq!(0, w1_count.get());
+        assert_eq!(0, w2_count.get());
+
+        drop(tx);
+
+        assert_eq!(1, w1_count.get());
+        assert_eq!(1, w2_count.get());
+    }
+
+    #[test]
+    fn multi_receiver() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(100);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn close_drains() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(2);
+        let mut rx2 = rx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        rx.close();
+
+        assert_eq!(
+            PollSend::Rejected(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn close_wakes_receivers() {
+        let (_tx, mut rx) = channel::<Message>(2);
+        let mut rx2 = rx.clone();
 
-        assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
-        );
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
 
         assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            PollRecv::Pending,
+            Pin::new(&mut rx2).poll_recv(&mut w1_context.into())
         );
+        assert_eq!(0, w1_count.get());
 
+        rx.close();
+
+        assert_eq!(1, w1_count.get());
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx2).poll_recv(&mut noop_context())
         );
     }
 
     #[test]
-    fn sender_disconnect() {
-        let mut cx = panic_context();
+    fn filtered_receiver() {
+        let mut cx = noop_context();
         let (mut tx, mut rx) = channel(100);
-        let mut tx2 = tx.clone();
+        let mut even = tx.subscribe_filtered(|message: &Message| message.0 % 2 == 0);
+
+        for i in 1..=4 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
 
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut even).poll_recv(&mut cx)
         );
-
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+            PollRecv::Ready(Message(4)),
+            Pin::new(&mut even).poll_recv(&mut cx)
         );
-
-        drop(tx);
-        drop(tx2);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut even).poll_recv(&mut cx));
 
         assert_eq!(
             PollRecv::Ready(Message(1)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
-
         assert_eq!(
-            PollRecv::Ready(Message(2)),
+            PollRecv::Ready(Message(3)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
-
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
     }
 
     #[test]
-    fn receiver_disconnect() {
-        let mut cx = panic_context();
+    fn filtered_receiver_clone() {
+        let mut cx = noop_context();
         let (mut tx, rx) = channel(100);
-        let mut tx2 = tx.clone();
+        let even = rx.clone_filtered(|message: &Message| message.0 % 2 == 0);
+        let mut even2 = even.clone();
 
         assert_eq!(
             PollSend::Ready,
             Pin::new(&mut tx).poll_send(&mut cx, Message(1))
         );
-
         assert_eq!(
             PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
-        );
-
-        drop(rx);
-
-        assert_eq!(
-            PollSend::Rejected(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
         );
 
         assert_eq!(
-            PollSend::Rejected(Message(4)),
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(4))
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut even2).poll_recv(&mut cx)
         );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut even2).poll_recv(&mut cx));
     }
 
     #[test]
-    fn wake_sender() {
-        let mut cx = panic_context();
+    fn filtered_receiver_skipped_holds_capacity() {
+        let mut cx = noop_context();
         let (mut tx, mut rx) = channel(1);
+        let mut even = tx.subscribe_filtered(|message: &Message| message.0 % 2 == 0);
 
         assert_eq!(
             PollSend::Ready,
             Pin::new(&mut tx).poll_send(&mut cx, Message(1))
         );
-
-        let (w2, w2_count) = new_count_waker();
-        let w2_context = Context::from_waker(&w2);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut even).poll_recv(&mut cx));
         assert_eq!(
             PollSend::Pending(Message(2)),
-            Pin::new(&mut tx).poll_send(&mut w2_context.into(), Message(2))
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
         );
 
-        assert_eq!(0, w2_count.get());
-
         assert_eq!(
             PollRecv::Ready(Message(1)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
-
-        assert_eq!(1, w2_count.get());
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
         );
-
-        assert_eq!(1, w2_count.get());
     }
 
     #[test]
-    fn wake_receiver() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(100);
+    fn drop_newest() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropNewest);
 
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+        for i in 1..=3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
 
+        assert_eq!(1, tx.dropped());
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
 
-        assert_eq!(0, w1_count.get());
+    #[test]
+    fn drop_oldest() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_policy(2, OverflowPolicy::DropOldest);
 
+        for i in 1..=4 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        assert_eq!(2, tx.dropped());
+        assert_eq!(2, tx.len());
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
+        assert_eq!(
+            PollRecv::Ready(Message(4)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
 
-        assert_eq!(1, w1_count.get());
-
+        // the evicted slots are returned when the replacement messages are received
         assert_eq!(
             PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+            Pin::new(&mut tx).poll_send(&mut cx, Message(5))
         );
-
-        assert_eq!(1, w1_count.get());
+        assert_eq!(2, tx.dropped());
     }
 
     #[test]
-    fn wake_sender_on_disconnect() {
-        let (mut tx, rx) = channel(1);
-
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
-        let mut w1_context: crate::Context<'_> = w1_context.into();
+    fn drop_oldest_evicts_skipped() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with_options(2, Strategy::Race, OverflowPolicy::DropOldest);
+        let mut even = tx.subscribe_filtered(|message: &Message| message.0 % 2 == 0);
 
         assert_eq!(
             PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut w1_context, Message(1))
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
         );
-
         assert_eq!(
-            PollSend::Pending(Message(2)),
-            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
         );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut even).poll_recv(&mut cx));
 
-        assert_eq!(0, w1_count.get());
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(4))
+        );
+        assert_eq!(1, tx.dropped());
+        assert_eq!(
+            PollRecv::Ready(Message(4)),
+            Pin::new(&mut even).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
 
+    #[test]
+    fn drop_policy_rejects_closed() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = channel_with_policy(1, OverflowPolicy::DropNewest);
         drop(rx);
 
-        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollSend::Rejected(Message(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(0, tx.dropped());
     }
 
+    #[cfg(feature = "timer")]
     #[test]
-    fn wake_receivers_on_disconnect() {
-        let (tx, mut rx) = channel::<()>(100);
-        let mut rx2 = rx.clone();
+    fn send_after() {
+        use std::time::Duration;
 
-        let (w1, w1_count) = new_count_waker();
-        let w1_context = Context::from_waker(&w1);
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
 
-        let (w2, w2_count) = new_count_waker();
-        let w2_context = Context::from_waker(&w2);
+        assert!(tx.send_after(Message(1), Duration::from_millis(20)).is_ok());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        std::thread::sleep(Duration::from_millis(30));
 
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[cfg(feature = "timer")]
+    #[test]
+    fn send_after_sender_disconnect() {
+        use std::time::Duration;
+
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert!(tx.send_after(Message(1), Duration::from_millis(20)).is_ok());
+        drop(tx);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        std::thread::sleep(Duration::from_millis(30));
 
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx2).poll_recv(&mut w2_context.into())
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
+    }
 
-        assert_eq!(0, w1_count.get());
-        assert_eq!(0, w2_count.get());
+    #[cfg(feature = "timer")]
+    #[test]
+    fn send_after_receiver_disconnect() {
+        use std::time::Duration;
 
-        drop(tx);
+        let (mut tx, rx) = channel(2);
+        drop(rx);
 
-        assert_eq!(1, w1_count.get());
-        assert_eq!(1, w2_count.get());
+        assert!(tx
+            .send_after(Message(1), Duration::from_millis(20))
+            .is_err());
     }
 
+    #[cfg(feature = "timer")]
     #[test]
-    fn multi_receiver() {
+    fn send_after_filtered() {
+        use std::time::Duration;
+
         let mut cx = noop_context();
-        let (mut tx, mut rx) = channel(100);
-        let mut rx2 = rx.clone();
+        let (mut tx, mut rx) = channel(2);
+        let mut even = rx.clone_filtered(|message: &Message| message.0 % 2 == 0);
+
+        assert!(tx.send_after(Message(1), Duration::from_millis(10)).is_ok());
+        assert!(tx.send_after(Message(2), Duration::from_millis(10)).is_ok());
+        drop(tx);
+
+        std::thread::sleep(Duration::from_millis(20));
 
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut even).poll_recv(&mut cx)
         );
-
+        assert_eq!(PollRecv::Pending, Pin::new(&mut even).poll_recv(&mut cx));
         assert_eq!(
             PollRecv::Ready(Message(1)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
         assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx2).poll_recv(&mut cx)
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
-
-        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
     }
 }
 
@@ -534,8 +1965,8 @@ mod tokio_tests {
         sink::Sink,
         stream::Stream,
         test::{
-            capacity_iter, Channel, Channels, Message, CHANNEL_TEST_RECEIVERS,
-            CHANNEL_TEST_SENDERS, TEST_TIMEOUT,
+            capacity_iter, Channel, Channels, Message, CHANNEL_TEST_ITERATIONS,
+            CHANNEL_TEST_RECEIVERS, CHANNEL_TEST_SENDERS, TEST_TIMEOUT,
         },
     };
 
@@ -597,6 +2028,47 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn multi_receiver_strategies() {
+        for strategy in [super::Strategy::RoundRobin, super::Strategy::Lifo] {
+            for cap in capacity_iter() {
+                let (mut tx, rx) = super::channel_with(cap, strategy);
+
+                spawn(async move {
+                    for message in Message::new_iter(0) {
+                        tx.send(message).await.expect("send failed");
+                    }
+                });
+
+                let handles: Vec<JoinHandle<()>> = (0..CHANNEL_TEST_RECEIVERS)
+                    .map(|_| {
+                        let mut rx2 = rx.clone();
+                        let mut channels = Channels::new(1).allow_skips();
+
+                        spawn(async move {
+                            while let Some(message) = rx2.recv().await {
+                                channels.assert_message(&message);
+                            }
+                        })
+                    })
+                    .collect();
+
+                drop(rx);
+
+                let rx_handle = spawn(async move {
+                    for handle in handles {
+                        handle.await.expect("Assertion failure");
+                    }
+                });
+
+                timeout(TEST_TIMEOUT, rx_handle)
+                    .await
+                    .expect("test timeout")
+                    .expect("join error");
+            }
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn multi_receiver() {
         // crate::logging::enable_log();
@@ -682,6 +2154,54 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn drop_policies_account_for_every_message() {
+        for policy in [
+            super::OverflowPolicy::DropNewest,
+            super::OverflowPolicy::DropOldest,
+        ] {
+            for cap in capacity_iter() {
+                let (tx, mut rx) = super::channel_with_policy(cap, policy);
+
+                let senders: Vec<JoinHandle<()>> = (0..CHANNEL_TEST_SENDERS)
+                    .map(|_| {
+                        let mut tx2 = tx.clone();
+                        spawn(async move {
+                            for i in 0..CHANNEL_TEST_ITERATIONS {
+                                tx2.send(i).await.expect("send failed");
+                            }
+                        })
+                    })
+                    .collect();
+
+                let rx_handle = spawn(async move {
+                    let mut received = 0;
+                    while rx.recv().await.is_some() {
+                        received += 1;
+                    }
+                    received
+                });
+
+                for sender in senders {
+                    sender.await.expect("join failed");
+                }
+
+                let dropped = tx.dropped();
+                drop(tx);
+
+                let received = timeout(TEST_TIMEOUT, rx_handle)
+                    .await
+                    .expect("test timeout")
+                    .expect("join failed");
+
+                assert_eq!(
+                    CHANNEL_TEST_SENDERS * CHANNEL_TEST_ITERATIONS,
+                    received + dropped
+                );
+            }
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn clone_monster() {
         for cap in capacity_iter() {
@@ -734,6 +2254,74 @@ mod tokio_tests {
                 .expect("join failed");
         }
     }
+
+    #[cfg(feature = "timer")]
+    #[tokio::test]
+    async fn send_after_wakes_receiver() {
+        let (mut tx, mut rx) = super::channel(4);
+
+        let start = std::time::Instant::now();
+        tx.send_after(2usize, Duration::from_millis(40))
+            .expect("send_after failed");
+        tx.send_after(1usize, Duration::from_millis(20))
+            .expect("send_after failed");
+        drop(tx);
+
+        let received = timeout(TEST_TIMEOUT, async move {
+            let mut received = Vec::new();
+            while let Some(message) = rx.recv().await {
+                received.push(message);
+            }
+            received
+        })
+        .await
+        .expect("test timeout");
+
+        assert_eq!(vec![1, 2], received);
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn filtered_receivers() {
+        for cap in capacity_iter() {
+            let (mut tx, rx) = super::channel(cap);
+            let mut even = rx.clone_filtered(|message: &usize| message % 2 == 0);
+            let mut odd = rx.clone_filtered(|message: &usize| message % 2 == 1);
+            drop(rx);
+
+            let even_handle = spawn(async move {
+                let mut received = 0;
+                while let Some(message) = even.recv().await {
+                    assert_eq!(0, message % 2);
+                    received += 1;
+                }
+                received
+            });
+
+            let odd_handle = spawn(async move {
+                let mut received = 0;
+                while let Some(message) = odd.recv().await {
+                    assert_eq!(1, message % 2);
+                    received += 1;
+                }
+                received
+            });
+
+            spawn(async move {
+                for message in 0..1000usize {
+                    tx.send(message).await.expect("send failed");
+                }
+            });
+
+            let received = timeout(TEST_TIMEOUT, async move {
+                even_handle.await.expect("join error") + odd_handle.await.expect("join error")
+            })
+            .await
+            .expect("test timeout");
+
+            assert_eq!(1000, received);
+        }
+    }
 }
 
 #[cfg(test)]
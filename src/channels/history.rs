@@ -0,0 +1,597 @@
+//! A state distribution channel which retains the latest values.  A hybrid of watch and broadcast.
+//!
+//! The channel stores the most recent `capacity` values.  Receivers which keep up observe every value.
+//! Receivers which fall behind skip to the oldest retained value.  The number of skipped values can be observed with `Receiver::lagged`.
+//!
+//! Unlike broadcast, senders are never suspended.  Unlike watch, a receiver does not miss values which arrive between polls.
+//!
+//! When the channel is created, receivers observe the initial value.  Receivers created with `Sender::subscribe`
+//! observe the latest stored value, and all following values.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+
+use super::SendSyncMessage;
+use crate::sync::lock::Mutex;
+use static_assertions::{assert_impl_all, assert_not_impl_all};
+use thiserror::Error;
+
+use crate::{
+    sink::{PollSend, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
+    sync::{shared, ReceiverShared, SenderShared},
+};
+
+/// Constructs a new history channel pair which retains up to `capacity` values, filled with `T::default()`.
+pub fn channel<T: Clone + Default>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    channel_with(capacity, T::default())
+}
+
+/// Constructs a new history channel pair which retains up to `capacity` values, filled with the provided value.
+///
+/// Panics if the capacity is zero.
+pub fn channel_with<T: Clone>(capacity: usize, value: T) -> (Sender<T>, Receiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating history channel with capacity {}", capacity);
+
+    assert!(capacity > 0, "history channel capacity must be at least 1");
+
+    let (tx_shared, rx_shared) = shared(StateExtension::new(capacity, value));
+    let sender = Sender { shared: tx_shared };
+
+    let receiver = Receiver {
+        shared: rx_shared,
+        next: 0,
+    };
+
+    (sender, receiver)
+}
+
+/// The sender half of a history channel.  Values are stored with the postage::Sink trait, and senders are never suspended.
+pub struct Sender<T> {
+    pub(in crate::channels::history) shared: SenderShared<StateExtension<T>>,
+}
+
+assert_impl_all!(Sender<SendSyncMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Sender<SendSyncMessage>: Clone);
+
+impl<T> Sink for Sender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: core::pin::Pin<&mut Self>,
+        _cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        if self.shared.is_closed() {
+            return PollSend::Rejected(value);
+        }
+
+        self.shared.extension().push(value);
+        self.shared.notify_receivers();
+
+        PollSend::Ready
+    }
+}
+
+impl<T> Sender<T> {
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
+    }
+
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
+
+    /// Creates a new Receiver, which observes the latest stored value, and all following values.
+    pub fn subscribe(&mut self) -> Receiver<T> {
+        let next = self.shared.extension().latest_id();
+
+        Receiver {
+            shared: self.shared.clone_receiver(),
+            next,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+/// The receiver half of a history channel.  Can receive values with the postage::Stream trait.
+///
+/// When a receiver is cloned, the new receiver observes the same series of values as the original.
+pub struct Receiver<T> {
+    pub(in crate::channels::history) shared: ReceiverShared<StateExtension<T>>,
+    // the id of the next value to be received
+    next: usize,
+}
+
+assert_impl_all!(Receiver<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
+    }
+
+    /// Borrows the receiver as a stream which reports skipped values.
+    ///
+    /// The stream produces `Err(RecvError::Lagged(n))` if `n` values were discarded before they could be read.
+    /// Reading then resumes at the oldest retained value.
+    pub fn lagged(&mut self) -> LaggedReceiver<'_, T> {
+        LaggedReceiver { receiver: self }
+    }
+
+    fn poll_recv_lagged(&mut self, cx: &mut crate::Context<'_>) -> PollRecv<Result<T, RecvError>> {
+        loop {
+            let guard = self.shared.send_guard();
+            // checked before the read, so values sent before the sender was dropped are not missed
+            let closed = self.shared.is_closed();
+
+            match self.shared.extension().try_read(self.next) {
+                TryRead::Ready(value) => {
+                    self.next += 1;
+                    return PollRecv::Ready(Ok(value));
+                }
+                TryRead::Lagged(oldest) => {
+                    let skipped = oldest - self.next;
+                    self.next = oldest;
+                    return PollRecv::Ready(Err(RecvError::Lagged(skipped)));
+                }
+                TryRead::Pending => {
+                    if closed {
+                        return PollRecv::Closed(self.shared.closed_reason());
+                    }
+
+                    self.shared.subscribe_send(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return PollRecv::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<T> Stream for Receiver<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        loop {
+            match this.poll_recv_lagged(cx) {
+                PollRecv::Ready(Ok(value)) => return PollRecv::Ready(value),
+                PollRecv::Ready(Err(RecvError::Lagged(_))) => continue,
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
+            }
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            next: self.next,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+/// An error returned by a `LaggedReceiver`, when values were discarded before the receiver could read them.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecvError {
+    /// The receiver fell behind, and skipped the given number of values
+    #[error("RecvError::Lagged({0})")]
+    Lagged(usize),
+}
+
+/// A borrowed history receiver, which produces `Err(RecvError::Lagged(n))` when values are skipped.
+///
+/// Created by `Receiver::lagged`.
+pub struct LaggedReceiver<'r, T> {
+    receiver: &'r mut Receiver<T>,
+}
+
+impl<'r, T> Stream for LaggedReceiver<'r, T>
+where
+    T: Clone,
+{
+    type Item = Result<T, RecvError>;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        self.get_mut().receiver.poll_recv_lagged(cx)
+    }
+}
+
+impl<'r, T> fmt::Debug for LaggedReceiver<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LaggedReceiver").finish()
+    }
+}
+
+enum TryRead<T> {
+    Pending,
+    Ready(T),
+    /// The requested value was discarded.  Contains the id of the oldest retained value.
+    Lagged(usize),
+}
+
+struct StateExtension<T> {
+    capacity: usize,
+    ring: Mutex<Ring<T>>,
+}
+
+struct Ring<T> {
+    values: VecDeque<T>,
+    // the id which will be assigned to the next value
+    head: usize,
+}
+
+impl<T> StateExtension<T> {
+    pub fn new(capacity: usize, value: T) -> Self {
+        let mut values = VecDeque::with_capacity(capacity);
+        values.push_back(value);
+
+        Self {
+            capacity,
+            ring: Mutex::new(Ring { values, head: 1 }),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let mut ring = self.ring.lock();
+
+        if ring.values.len() >= self.capacity {
+            ring.values.pop_front();
+        }
+
+        ring.values.push_back(value);
+        ring.head += 1;
+    }
+
+    pub fn latest_id(&self) -> usize {
+        self.ring.lock().head - 1
+    }
+
+    pub fn try_read(&self, id: usize) -> TryRead<T>
+    where
+        T: Clone,
+    {
+        let ring = self.ring.lock();
+        let oldest = ring.head - ring.values.len();
+
+        if id < oldest {
+            return TryRead::Lagged(oldest);
+        }
+
+        match ring.values.get(id - oldest) {
+            Some(value) => TryRead::Ready(value.clone()),
+            None => TryRead::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use super::{channel, channel_with, RecvError};
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream},
+        test::{noop_context, panic_context},
+    };
+    use futures_test::task::new_count_waker;
+
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    struct State(usize);
+
+    #[test]
+    fn recv_default() {
+        let mut cx = panic_context();
+        let (_tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn recv_every_value() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel_with(3, State(0));
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, State(2))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(State(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn sender_never_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, _rx) = channel(1);
+
+        for i in 1..10 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, State(i))
+            );
+        }
+    }
+
+    #[test]
+    fn lagged() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        for i in 1..5 {
+            assert_eq!(Ok(()), tx.try_send(State(i)));
+        }
+
+        assert_eq!(
+            PollRecv::Ready(Err(RecvError::Lagged(3))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Ok(State(3))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(Ok(State(4))),
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx.lagged()).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn lagged_skipped_by_stream() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        for i in 1..5 {
+            assert_eq!(Ok(()), tx.try_send(State(i)));
+        }
+
+        assert_eq!(
+            PollRecv::Ready(State(3)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn subscribe_observes_latest() {
+        let mut cx = noop_context();
+        let (mut tx, _rx) = channel(4);
+
+        assert_eq!(Ok(()), tx.try_send(State(1)));
+        assert_eq!(Ok(()), tx.try_send(State(2)));
+
+        let mut rx2 = tx.subscribe();
+        assert_eq!(
+            PollRecv::Ready(State(2)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx2).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn clone_observes_same_series() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
+
+        assert_eq!(Ok(()), tx.try_send(State(1)));
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let mut rx2 = rx.clone();
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn wake_receiver() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(Ok(()), tx.try_send(State(1)));
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn sender_disconnect_drains() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(Ok(()), tx.try_send(State(1)));
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn receiver_disconnect_rejects() {
+        let mut cx = noop_context();
+        let (mut tx, rx) = channel(2);
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(State(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, State(1))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::time::timeout;
+
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{Channel, Channels, Message, CHANNEL_TEST_RECEIVERS, TEST_TIMEOUT},
+    };
+
+    #[tokio::test]
+    async fn simple() {
+        let (mut tx, mut rx) = super::channel(4);
+
+        tokio::task::spawn(async move {
+            let mut iter = Message::new_iter(0);
+            // skip state 0
+            iter.next();
+            for message in iter {
+                tx.send(message).await.expect("send failed");
+            }
+        });
+
+        timeout(TEST_TIMEOUT, async move {
+            let mut channel = Channel::new(0).allow_skips();
+            while let Some(message) = rx.recv().await {
+                channel.assert_message(&message);
+            }
+        })
+        .await
+        .expect("test timeout");
+    }
+
+    #[tokio::test]
+    async fn multi_receiver() {
+        let (mut tx, rx) = super::channel(4);
+
+        tokio::task::spawn(async move {
+            let mut iter = Message::new_iter(0);
+            // skip state 0
+            iter.next();
+            for message in iter {
+                tx.send(message).await.expect("send failed");
+            }
+        });
+
+        let handles = (0..CHANNEL_TEST_RECEIVERS).map(move |_| {
+            let mut rx2 = rx.clone();
+            let mut channels = Channels::new(CHANNEL_TEST_RECEIVERS).allow_skips();
+
+            tokio::task::spawn(async move {
+                while let Some(message) = rx2.recv().await {
+                    channels.assert_message(&message);
+                }
+            })
+        });
+
+        timeout(TEST_TIMEOUT, async move {
+            for handle in handles {
+                handle.await.expect("join failed");
+            }
+        })
+        .await
+        .expect("test timeout");
+    }
+}
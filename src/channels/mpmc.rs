@@ -0,0 +1,234 @@
+//! A multi-producer, multi-consumer work queue.  Each message is received by exactly one receiver.
+//!
+//! This module is an alias of [dispatch](../dispatch/index.html), which provides the channel.  Messages are stored in a single
+//! array-based queue, with no per-receiver positions.  The dispatch module also provides the filter and overflow policy constructors.
+//!
+//! `mpmc::channel` uses `Strategy::RoundRobin`, so wakeups are fair on both sides:
+//! - Waiting receivers are assigned messages in the order they began waiting.
+//! - Senders which are waiting for a slot are served in the order they began waiting.
+//!
+//! Only a parked task holds a turn.  `try_recv` and `try_send` never take one, and a receive or send future which times out
+//! or is dropped before it completes gives up its turn with `Stream::cancel_recv` or `Sink::cancel_send`.
+//!
+//! ```
+//! use postage::{mpmc, prelude::*};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut tx, rx) = mpmc::channel(4);
+//!     let mut rx2 = rx.clone();
+//!
+//!     tx.send(1usize).await.ok();
+//!     tx.send(2usize).await.ok();
+//!     drop(tx);
+//!
+//!     let mut received = Vec::new();
+//!     while let Some(value) = rx2.recv().await {
+//!         received.push(value);
+//!     }
+//!
+//!     assert_eq!(vec![1, 2], received);
+//! }
+//! ```
+
+pub use super::dispatch::{Receiver, Sender, Strategy};
+
+/// Constructs a pair of MPMC endpoints, with a fixed-size buffer of the given capacity.
+/// Waiting receivers are assigned messages, and waiting senders are given slots, in the order they began waiting.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    super::dispatch::channel_with(capacity, Strategy::RoundRobin)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+    use futures_test::task::{new_count_waker, noop_context};
+
+    use crate::{
+        sink::Sink,
+        stream::{RecvTimeoutError, Stream, TryRecvError},
+        test::timer::ExpiredTimer,
+    };
+
+    use super::channel;
+
+    #[test]
+    fn try_recv_while_waiting() {
+        let (mut tx, mut rx1) = channel(4);
+        let mut rx2 = rx1.clone();
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut recv = rx1.recv();
+        assert_eq!(Poll::Pending, Pin::new(&mut recv).poll(&mut cx));
+
+        assert_eq!(Err(TryRecvError::Pending), rx2.try_recv());
+        assert_eq!(0, count.get());
+
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert_eq!(1, count.get());
+        assert_eq!(Err(TryRecvError::Pending), rx2.try_recv());
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut recv).poll(&mut cx));
+    }
+
+    #[test]
+    fn cancelled_recv() {
+        let (mut tx, mut rx1) = channel(4);
+        let mut rx2 = rx1.clone();
+        let mut rx3 = rx1.clone();
+
+        let mut recv2 = rx2.recv();
+        {
+            let mut recv1 = rx1.recv();
+            assert_eq!(
+                Poll::Pending,
+                Pin::new(&mut recv1).poll(&mut noop_context())
+            );
+            assert_eq!(
+                Poll::Pending,
+                Pin::new(&mut recv2).poll(&mut noop_context())
+            );
+        }
+
+        // rx1 gave up its turn, so rx2 is next in line
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert_eq!(Err(TryRecvError::Pending), rx3.try_recv());
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut recv2).poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn recv_timeout() {
+        let (mut tx, mut rx1) = channel(4);
+        let mut rx2 = rx1.clone();
+
+        let mut timeout = rx1.recv_timeout(Duration::from_millis(10), ExpiredTimer);
+        assert_eq!(
+            Poll::Ready(Err(RecvTimeoutError::Timeout)),
+            Pin::new(&mut timeout).poll(&mut noop_context())
+        );
+
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert_eq!(Ok(1), rx2.try_recv());
+    }
+
+    #[test]
+    fn senders_served_in_order() {
+        let (mut tx1, mut rx) = channel(1);
+        let mut tx2 = tx1.clone();
+        let mut tx3 = tx1.clone();
+
+        assert_eq!(Ok(()), tx3.try_send(0usize));
+
+        let (waker1, count1) = new_count_waker();
+        let mut cx1 = std::task::Context::from_waker(&waker1);
+        let mut send1 = tx1.send(1);
+        assert_eq!(Poll::Pending, Pin::new(&mut send1).poll(&mut cx1));
+
+        let mut send2 = tx2.send(2);
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut send2).poll(&mut noop_context())
+        );
+
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(1, count1.get());
+
+        // the slot belongs to the first sender in line
+        assert_eq!(
+            Poll::Pending,
+            Pin::new(&mut send2).poll(&mut noop_context())
+        );
+        assert!(tx3.try_send(3).is_err());
+        assert_eq!(Poll::Ready(Ok(())), Pin::new(&mut send1).poll(&mut cx1));
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            Pin::new(&mut send2).poll(&mut noop_context())
+        );
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+
+    #[test]
+    fn cancelled_send() {
+        let (mut tx1, mut rx) = channel(1);
+        let mut tx2 = tx1.clone();
+
+        assert_eq!(Ok(()), tx2.try_send(0usize));
+
+        {
+            let mut send1 = tx1.send(1);
+            assert_eq!(
+                Poll::Pending,
+                Pin::new(&mut send1).poll(&mut noop_context())
+            );
+        }
+
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(Ok(()), tx2.try_send(2));
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use std::collections::HashSet;
+
+    use tokio::task::spawn;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    use super::channel;
+
+    #[tokio::test]
+    async fn multi_sender_multi_receiver() {
+        let (tx, rx) = channel(2);
+
+        let senders: Vec<_> = (0..4usize)
+            .map(|sender| {
+                let mut tx = tx.clone();
+                spawn(async move {
+                    for i in 0..100usize {
+                        tx.send(sender * 100 + i).await.expect("send failed");
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+
+        let receivers: Vec<_> = (0..4)
+            .map(|_| {
+                let mut rx = rx.clone();
+                spawn(async move {
+                    let mut received = Vec::new();
+                    while let Some(value) = rx.recv().await {
+                        received.push(value);
+                    }
+                    received
+                })
+            })
+            .collect();
+        drop(rx);
+
+        for sender in senders {
+            sender.await.expect("sender panicked");
+        }
+
+        let mut received = HashSet::new();
+        for receiver in receivers {
+            for value in receiver.await.expect("receiver panicked") {
+                assert!(
+                    received.insert(value),
+                    "message {} was received twice",
+                    value
+                );
+            }
+        }
+
+        assert_eq!(400, received.len());
+    }
+}
@@ -1,25 +1,66 @@
 //! A fixed-capacity multi-producer, single-consumer channel.  
 //!
 //! The producer can be cloned, and the sender task is suspended if the channel becomes full.
-
-use std::fmt;
+//!
+//! An unbounded variant is available with `mpsc::unbounded()`.  Unbounded senders never apply backpressure.
+//!
+//! Messages are moved out of the channel when they are received.  The channel does not retain a copy,
+//! so large payloads are freed as soon as the receiver drops them.
+//!
+//! `StaticChannel<T, N>` stores its buffer inline, so it can be placed in a `static` without heap allocation.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{
+    fmt,
+    future::Future,
+    sync::atomic::Ordering,
+    task::{Poll, Waker},
+};
 
 use super::SendMessage;
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
-    sync::{shared, ReceiverShared, SenderShared},
+    sink::{PollSend, SendError, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
+    sync::{primitive::AtomicUsize, shared, ReceiverShared, SenderShared, WeakSenderShared},
 };
-use crossbeam_queue::ArrayQueue;
+use crossbeam_queue::{ArrayQueue, SegQueue};
 use static_assertions::{assert_impl_all, assert_not_impl_all};
 
+#[cfg(not(feature = "loom"))]
+mod static_channel;
+
+#[cfg(not(feature = "loom"))]
+pub use static_channel::{StaticChannel, StaticReceiver, StaticSender};
+
+/// Constructs a pair of mpsc endpoints, with a fixed-size buffer of the given capacity
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
     #[cfg(feature = "debug")]
     log::error!("Creating mpsc channel with capacity {}", capacity);
     let (tx_shared, rx_shared) = shared(StateExtension::new(capacity));
     let sender = Sender { shared: tx_shared };
 
-    let receiver = Receiver { shared: rx_shared };
+    let receiver = Receiver {
+        shared: rx_shared,
+        waker: None,
+    };
+
+    (sender, receiver)
+}
+
+/// Constructs a pair of mpsc endpoints, with a fixed-size buffer of the given capacity.
+/// The channel reports events to the `metrics` recorder under the given name.
+///
+/// Requires the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub fn channel_named<T>(name: &str, capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx_shared, rx_shared) = crate::sync::shared_named(name, StateExtension::new(capacity));
+    let sender = Sender { shared: tx_shared };
+
+    let receiver = Receiver {
+        shared: rx_shared,
+        waker: None,
+    };
 
     (sender, receiver)
 }
@@ -45,66 +86,320 @@ impl<T> Sink for Sender<T> {
     type Item = T;
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
-        mut value: Self::Item,
+        value: Self::Item,
     ) -> PollSend<Self::Item> {
+        match self.poll_reserve(cx) {
+            PollSend::Ready => {
+                self.shared.extension().push(value);
+                self.shared.notify_receivers();
+
+                #[cfg(feature = "metrics")]
+                self.shared.record(crate::metrics::Event::Send);
+
+                #[cfg(feature = "diagnostics")]
+                self.shared.record_write();
+
+                PollSend::Ready
+            }
+            PollSend::Pending(()) => {
+                #[cfg(feature = "metrics")]
+                self.shared.record(crate::metrics::Event::SendBlocked);
+
+                PollSend::Pending(value)
+            }
+            PollSend::Rejected(()) => PollSend::Rejected(value),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.waiters()
+    }
+
+    /// Reports the parked tasks, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        self.shared.diagnostics()
+    }
+
+    /// The number of messages in the channel which have not yet been received.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// True if the channel contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().capacity()
+    }
+
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Pending(value))` if the channel is full.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
+    }
+
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
+
+    /// Returns true if the receiver has been dropped, or closed the channel.  Messages sent to a closed channel are rejected.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+
+    /// Returns `Poll::Ready(())` if the channel is closed.  Otherwise, registers the task to be woken when it is closed.
+    pub fn poll_closed(&self, cx: &mut crate::Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.shared.recv_guard();
+
+            if self.shared.is_closed() {
+                return Poll::Ready(());
+            }
+
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Waits until the channel is closed.
+    ///
+    /// This allows a producer to stop generating messages as soon as they are no longer needed,
+    /// for example with `select!` on `tx.closed()` and the work.
+    pub async fn closed(&self) {
+        core::future::poll_fn(|cx| self.poll_closed(&mut cx.into())).await
+    }
+
+    /// Waits for capacity in the channel, and reserves a slot for a single message.
+    ///
+    /// The slot is held by the returned `Permit` until a message is sent, or the permit is dropped.
+    /// This allows capacity to be acquired before constructing an expensive message.
+    ///
+    /// Reserve is cancel-safe.  If the future is dropped before completion, no capacity is held.
+    ///
+    /// Returns `Err(SendError(()))` if the receiver has been dropped.
+    pub async fn reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        core::future::poll_fn(|cx| {
+            let mut cx = cx.into();
+            match self.poll_reserve(&mut cx) {
+                PollSend::Ready => core::task::Poll::Ready(Ok(Permit { sender: self })),
+                PollSend::Pending(()) => core::task::Poll::Pending,
+                PollSend::Rejected(()) => core::task::Poll::Ready(Err(SendError(()))),
+            }
+        })
+        .await
+    }
+
+    /// Attempts to reserve a slot for a single message, without blocking.
+    ///
+    /// Returns:
+    /// - `Ok(permit)` if a slot was reserved.
+    /// - `Err(TrySendError::Pending(()))` if the channel is full.
+    /// - `Err(TrySendError::Rejected(()))` if the channel is closed.
+    pub fn try_reserve(&self) -> Result<Permit<'_, T>, TrySendError<()>> {
+        match self.poll_reserve(&mut crate::Context::empty()) {
+            PollSend::Ready => Ok(Permit { sender: self }),
+            PollSend::Pending(()) => Err(TrySendError::Pending(())),
+            PollSend::Rejected(()) => Err(TrySendError::Rejected(())),
+        }
+    }
+
+    /// Creates a weak sender, which does not keep the channel open.
+    ///
+    /// When all strong senders are dropped, the receiver observes the channel as closed, even if weak senders remain.
+    pub fn downgrade(&self) -> WeakSender<T> {
+        WeakSender {
+            shared: self.shared.downgrade(),
+        }
+    }
+
+    /// Returns a future which resolves once every message sent before the barrier has been received.
+    ///
+    /// Messages sent after the call are not included, even if the future has not yet been polled.
+    /// The future also resolves if the receiver is dropped.  This allows a sender to 'flush' the channel before taking a snapshot,
+    /// without closing or draining the channel.
+    pub fn barrier(&self) -> impl Future<Output = ()> + '_ {
+        let target = self.shared.extension().sent();
+
+        core::future::poll_fn(move |cx| self.poll_barrier(target, &mut cx.into()))
+    }
+
+    fn poll_barrier(&self, target: usize, cx: &mut crate::Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.shared.recv_guard();
+
+            if self.shared.extension().is_received(target) || !self.shared.has_receivers() {
+                return Poll::Ready(());
+            }
+
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    fn poll_reserve(&self, cx: &mut crate::Context<'_>) -> PollSend<()> {
         loop {
             if self.shared.is_closed() {
-                return PollSend::Rejected(value);
+                return PollSend::Rejected(());
             }
 
             let guard = self.shared.recv_guard();
-            let queue = &self.shared.extension().queue;
-            match queue.push(value) {
-                Ok(_) => {
+            if self.shared.extension().try_acquire() {
+                // the receiver may have closed the channel while the slot was acquired
+                if self.shared.is_closed() {
+                    self.shared.extension().cancel();
                     self.shared.notify_receivers();
-                    return PollSend::Ready;
+                    return PollSend::Rejected(());
                 }
-                Err(v) => {
-                    self.shared.subscribe_recv(cx);
 
-                    if guard.is_expired() {
-                        value = v;
-                        continue;
-                    }
+                return PollSend::Ready;
+            }
 
-                    return PollSend::Pending(v);
-                }
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
             }
+
+            return PollSend::Pending(());
         }
     }
 }
 
+/// A reserved slot in an mpsc channel, acquired with `Sender::reserve` or `Sender::try_reserve`.
+///
+/// If the permit is dropped without sending a message, the slot is released.
+pub struct Permit<'s, T> {
+    sender: &'s Sender<T>,
+}
+
+assert_impl_all!(Permit<'static, SendMessage>: Send, Sync, fmt::Debug);
+
+impl<'s, T> Permit<'s, T> {
+    /// Sends a message using the reserved slot.  The message is dropped if the receiver has been dropped.
+    pub fn send(self, value: T) {
+        let shared = &self.sender.shared;
+        shared.extension().push(value);
+        shared.notify_receivers();
+
+        #[cfg(feature = "metrics")]
+        shared.record(crate::metrics::Event::Send);
+
+        #[cfg(feature = "diagnostics")]
+        shared.record_write();
+
+        // the slot is now held by the message, and is released when the message is received
+        core::mem::forget(self);
+    }
+}
+
+impl<'s, T> Drop for Permit<'s, T> {
+    fn drop(&mut self) {
+        let shared = &self.sender.shared;
+        shared.extension().cancel();
+        shared.notify_self();
+        shared.notify_receivers();
+    }
+}
+
+impl<'s, T> fmt::Debug for Permit<'s, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Permit").finish()
+    }
+}
+
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Sender").finish()
     }
 }
 
+/// A weak reference to an mpsc sender, created with `Sender::downgrade`.  Can be cloned.
+///
+/// Weak senders do not keep the channel open, and cannot send messages until upgraded.
+pub struct WeakSender<T> {
+    shared: WeakSenderShared<StateExtension<T>>,
+}
+
+assert_impl_all!(WeakSender<String>: Clone, Send, Sync, fmt::Debug);
+
+impl<T> WeakSender<T> {
+    /// Upgrades to a strong sender.  Returns `None` if all strong senders have been dropped.
+    pub fn upgrade(&self) -> Option<Sender<T>> {
+        self.shared.upgrade().map(|shared| Sender { shared })
+    }
+}
+
+impl<T> Clone for WeakSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakSender").finish()
+    }
+}
+
 #[cfg(feature = "futures-traits")]
 mod impl_futures {
     use crate::sink::SendError;
-    use std::task::Poll;
+    use core::task::Poll;
 
     impl<T> futures::sink::Sink<T> for super::Sender<T> {
         type Error = SendError<T>;
 
         fn poll_ready(
-            self: std::pin::Pin<&mut Self>,
-            cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             loop {
                 if self.shared.is_closed() {
                     return Poll::Ready(Ok(()));
                 }
 
-                let queue = &self.shared.extension().queue;
                 let guard = self.shared.recv_guard();
 
-                if queue.is_full() {
-                    let mut cx = cx.into();
-                    self.shared.subscribe_recv(&mut cx);
+                if !self.shared.extension().has_permits() {
+                    let cx = cx.into();
+                    self.shared.subscribe_recv(&cx);
 
                     if guard.is_expired() {
                         continue;
@@ -117,35 +412,44 @@ mod impl_futures {
             }
         }
 
-        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        fn start_send(self: core::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
             if self.shared.is_closed() {
                 return Err(SendError(item));
             }
 
-            let result = self
-                .shared
-                .extension()
-                .queue
-                .push(item)
-                .map_err(|item| SendError(item));
+            let extension = self.shared.extension();
+            if !extension.try_acquire() {
+                return Err(SendError(item));
+            }
 
-            if result.is_ok() {
+            if self.shared.is_closed() {
+                extension.cancel();
                 self.shared.notify_receivers();
+                return Err(SendError(item));
             }
 
-            result
+            extension.push(item);
+            self.shared.notify_receivers();
+
+            #[cfg(feature = "metrics")]
+            self.shared.record(crate::metrics::Event::Send);
+
+            #[cfg(feature = "diagnostics")]
+            self.shared.record_write();
+
+            Ok(())
         }
 
         fn poll_flush(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
         fn poll_close(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
@@ -157,6 +461,8 @@ mod impl_futures {
 /// Can receive messages with the postage::Stream trait.
 pub struct Receiver<T> {
     pub(in crate::channels::mpsc) shared: ReceiverShared<StateExtension<T>>,
+    // the waker most recently registered by the receiver.  it is removed when the receiver is polled by a different task.
+    waker: Option<Waker>,
 }
 
 assert_impl_all!(Receiver<SendMessage>: Send, Sync, fmt::Debug);
@@ -166,22 +472,34 @@ impl<T> Stream for Receiver<T> {
     type Item = T;
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
         loop {
-            let guard = self.shared.send_guard();
-            match self.shared.extension().queue.pop() {
+            let guard = this.shared.send_guard();
+            match this.shared.extension().queue.pop() {
                 Some(v) => {
-                    self.shared.notify_senders();
+                    this.shared.extension().release(1);
+                    this.shared.notify_senders();
+
+                    #[cfg(feature = "metrics")]
+                    this.shared.record(crate::metrics::Event::Recv);
+
                     return PollRecv::Ready(v);
                 }
                 None => {
-                    if self.shared.is_closed() {
-                        return PollRecv::Closed;
+                    // wait for senders which acquired a slot before the channel was closed
+                    if this.shared.is_closed() && !this.shared.extension().is_sending() {
+                        if !this.shared.extension().queue.is_empty() {
+                            continue;
+                        }
+
+                        return PollRecv::Closed(this.shared.closed_reason());
                     }
 
-                    self.shared.subscribe_send(cx);
+                    subscribe(&this.shared, &mut this.waker, cx);
 
                     if guard.is_expired() {
                         continue;
@@ -192,6 +510,177 @@ impl<T> Stream for Receiver<T> {
             }
         }
     }
+
+    fn poll_recv_many(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        buffer: &mut Vec<Self::Item>,
+        limit: usize,
+    ) -> PollRecv<usize> {
+        if limit == 0 {
+            return PollRecv::Ready(0);
+        }
+
+        let this = self.get_mut();
+
+        loop {
+            let guard = this.shared.send_guard();
+
+            let mut received = 0;
+            while received < limit {
+                match this.shared.extension().queue.pop() {
+                    Some(v) => {
+                        buffer.push(v);
+                        received += 1;
+                    }
+                    None => break,
+                }
+            }
+
+            if received > 0 {
+                // the batch releases its slots together, so senders are notified once
+                this.shared.extension().release(received);
+                this.shared.notify_senders();
+
+                #[cfg(feature = "metrics")]
+                for _ in 0..received {
+                    this.shared.record(crate::metrics::Event::Recv);
+                }
+
+                return PollRecv::Ready(received);
+            }
+
+            // wait for senders which acquired a slot before the channel was closed
+            if this.shared.is_closed() && !this.shared.extension().is_sending() {
+                if !this.shared.extension().queue.is_empty() {
+                    continue;
+                }
+
+                return PollRecv::Closed(this.shared.closed_reason());
+            }
+
+            subscribe(&this.shared, &mut this.waker, cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Prepares the receiver to be moved to another task.
+    ///
+    /// The waker registered by the current task is removed, so the task is not woken by later messages.
+    /// The receiver registers the new task when it is next polled.  Polling from a different task also replaces the registration,
+    /// so this is only needed if the previous task should stop receiving wakeups before the receiver is polled again.
+    pub fn transfer(mut self) -> Self {
+        if let Some(waker) = self.waker.take() {
+            self.shared.unsubscribe_send(&waker);
+        }
+
+        self
+    }
+
+    /// Reports the number of tasks which are parked on the channel.  Useful when diagnosing a stalled pipeline.
+    ///
+    /// Requires the `debug` feature.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.shared.waiters()
+    }
+
+    /// Reports the parked tasks, and the time since the last write.  Useful for watchdogs which detect a stalled channel.
+    ///
+    /// Requires the `diagnostics` feature.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        self.shared.diagnostics()
+    }
+
+    /// The number of messages in the channel which have not yet been received.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn len(&self) -> usize {
+        self.shared.extension().len()
+    }
+
+    /// True if the channel contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of messages which can be buffered in the channel.
+    pub fn capacity(&self) -> usize {
+        self.shared.extension().capacity()
+    }
+
+    /// Closes the channel, without dropping the receiver.
+    ///
+    /// Subsequent sends are rejected, and waiting senders are woken.  Messages which are already buffered,
+    /// or were sent with an acquired `Permit`, can still be received.  Once they have been drained, the receiver produces `ClosedReason::Closed`.
+    pub fn close(&mut self) {
+        self.shared.close();
+    }
+
+    /// Closes the channel, and returns a stream of the messages which remain in the channel.
+    ///
+    /// Messages which are already buffered, or were sent with an acquired `Permit`, are produced,
+    /// and then the stream closes.  Useful in shutdown sequences, which can't rely on every sender being dropped.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.close();
+        Drain { receiver: self }
+    }
+
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
+    }
+
+    /// Converts the receiver into an iterator, which blocks the current thread until each message is received.
+    /// The iterator ends when the channel is closed.  Equivalent to `Stream::into_blocking_iter`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn into_blocking_iter(self) -> impl Iterator<Item = T> {
+        Stream::into_blocking_iter(self)
+    }
+}
+
+/// Registers the receiver's waker.  If the receiver was polled by a different task, the stale waker is removed.
+fn subscribe<T>(
+    shared: &ReceiverShared<StateExtension<T>>,
+    registered: &mut Option<Waker>,
+    cx: &crate::Context<'_>,
+) {
+    if let Some(waker) = cx.waker() {
+        match registered.as_ref() {
+            Some(current) if current.will_wake(waker) => {}
+            _ => {
+                if let Some(stale) = registered.replace(waker.clone()) {
+                    shared.unsubscribe_send(&stale);
+                }
+            }
+        }
+    }
+
+    shared.subscribe_send(cx);
 }
 
 impl<T> fmt::Debug for Receiver<T> {
@@ -200,131 +689,1137 @@ impl<T> fmt::Debug for Receiver<T> {
     }
 }
 
-struct StateExtension<T> {
-    queue: ArrayQueue<T>,
+/// A stream of the messages which remained in a closed channel.  Created by `Receiver::drain`.
+pub struct Drain<'r, T> {
+    receiver: &'r mut Receiver<T>,
 }
 
-impl<T> StateExtension<T> {
-    pub fn new(capacity: usize) -> Self {
+impl<'r, T> Stream for Drain<'r, T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        core::pin::Pin::new(&mut *self.get_mut().receiver).poll_recv(cx)
+    }
+}
+
+impl<'r, T> fmt::Debug for Drain<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").finish()
+    }
+}
+
+/// Constructs a pair of unbounded mpsc endpoints.
+///
+/// The buffer is allocated in segments as messages are sent, and senders are never suspended.
+pub fn unbounded<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating unbounded mpsc channel");
+    let (tx_shared, rx_shared) = shared(UnboundedStateExtension::new());
+    let sender = UnboundedSender { shared: tx_shared };
+
+    let receiver = UnboundedReceiver { shared: rx_shared };
+
+    (sender, receiver)
+}
+
+/// The sender half of an unbounded mpsc channel.  Can send messages with the postage::Sink trait.
+///
+/// The sender is always ready to accept a message, until the receiver is dropped.  Can be cloned.
+pub struct UnboundedSender<T> {
+    pub(in crate::channels::mpsc) shared: SenderShared<UnboundedStateExtension<T>>,
+}
+
+assert_impl_all!(UnboundedSender<String>: Clone, Send, Sync, fmt::Debug);
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
         Self {
-            queue: ArrayQueue::new(capacity),
+            shared: self.shared.clone(),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::{pin::Pin, task::Context};
+impl<T> Sink for UnboundedSender<T> {
+    type Item = T;
 
-    use crate::{
-        sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
-        test::{noop_context, panic_context},
-    };
-    use futures_test::task::new_count_waker;
+    fn poll_send(
+        self: core::pin::Pin<&mut Self>,
+        _cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        if self.shared.is_closed() {
+            return PollSend::Rejected(value);
+        }
 
-    use super::{channel, Receiver, Sender};
+        self.shared.extension().queue.push(value);
+        self.shared.notify_receivers();
 
-    fn pin<'a, 'b>(
-        chan: &mut (Sender<Message>, Receiver<Message>),
-    ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
-        let tx = Pin::new(&mut chan.0);
-        let rx = Pin::new(&mut chan.1);
+        PollSend::Ready
+    }
+}
 
-        (tx, rx)
+impl<T> UnboundedSender<T> {
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
     }
 
-    #[derive(Debug, PartialEq, Eq)]
-    struct Message(usize);
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
 
-    #[test]
-    fn send_accepted() {
-        let mut cx = panic_context();
-        let mut chan = channel(2);
-        let (tx, _) = pin(&mut chan);
+    /// Returns true if the receiver has been dropped, or closed the channel.  Messages sent to a closed channel are rejected.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
 
-        assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+    /// Returns `Poll::Ready(())` if the channel is closed.  Otherwise, registers the task to be woken when it is closed.
+    pub fn poll_closed(&self, cx: &mut crate::Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.shared.recv_guard();
+
+            if self.shared.is_closed() {
+                return Poll::Ready(());
+            }
+
+            self.shared.subscribe_recv(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Waits until the channel is closed.
+    ///
+    /// This allows a producer to stop generating messages as soon as they are no longer needed,
+    /// for example with `select!` on `tx.closed()` and the work.
+    pub async fn closed(&self) {
+        core::future::poll_fn(|cx| self.poll_closed(&mut cx.into())).await
+    }
+}
+
+impl<T> fmt::Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedSender").finish()
+    }
+}
+
+#[cfg(feature = "futures-traits")]
+mod impl_futures_unbounded {
+    use crate::sink::SendError;
+    use core::task::Poll;
+
+    impl<T> futures::sink::Sink<T> for super::UnboundedSender<T> {
+        type Error = SendError<T>;
+
+        fn poll_ready(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: core::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+            if self.shared.is_closed() {
+                return Err(SendError(item));
+            }
+
+            self.shared.extension().queue.push(item);
+            self.shared.notify_receivers();
+
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// The receiver half of an unbounded mpsc channel.  Cannot be cloned.
+///
+/// Can receive messages with the postage::Stream trait.
+pub struct UnboundedReceiver<T> {
+    pub(in crate::channels::mpsc) shared: ReceiverShared<UnboundedStateExtension<T>>,
+}
+
+assert_impl_all!(UnboundedReceiver<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(UnboundedReceiver<SendMessage>: Clone);
+
+impl<T> Stream for UnboundedReceiver<T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        loop {
+            let guard = self.shared.send_guard();
+            match self.shared.extension().queue.pop() {
+                Some(v) => return PollRecv::Ready(v),
+                None => {
+                    if self.shared.is_closed() {
+                        return PollRecv::Closed(self.shared.closed_reason());
+                    }
+
+                    self.shared.subscribe_send(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return PollRecv::Pending;
+                }
+            }
+        }
+    }
+}
+
+impl<T> UnboundedReceiver<T> {
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
+    }
+
+    /// Converts the receiver into an iterator, which blocks the current thread until each message is received.
+    /// The iterator ends when the channel is closed.  Equivalent to `Stream::into_blocking_iter`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn into_blocking_iter(self) -> impl Iterator<Item = T> {
+        Stream::into_blocking_iter(self)
+    }
+}
+
+impl<T> fmt::Debug for UnboundedReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedReceiver").finish()
+    }
+}
+
+struct StateExtension<T> {
+    queue: ArrayQueue<T>,
+    // the number of unreserved slots.  senders acquire a slot before pushing, and receivers release it after popping.
+    permits: AtomicUsize,
+    // the number of acquired slots which have not yet been pushed, or cancelled.  a closed receiver drains until this reaches zero.
+    sending: AtomicUsize,
+    // the total number of messages pushed and popped.  barriers wait for `received` to reach a snapshot of `sent`.
+    // `sent` is incremented before the push, so a snapshot covers every message which is already in the queue.
+    sent: AtomicUsize,
+    received: AtomicUsize,
+}
+
+impl<T> StateExtension<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: ArrayQueue::new(capacity),
+            permits: AtomicUsize::new(capacity),
+            sending: AtomicUsize::new(0),
+            sent: AtomicUsize::new(0),
+            received: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn try_acquire(&self) -> bool {
+        let acquired = self
+            .permits
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |permits| {
+                permits.checked_sub(1)
+            })
+            .is_ok();
+
+        if acquired {
+            self.sending.fetch_add(1, Ordering::SeqCst);
+        }
+
+        acquired
+    }
+
+    /// Returns an acquired slot which was not used.
+    pub fn cancel(&self) {
+        self.sending.fetch_sub(1, Ordering::SeqCst);
+        self.permits.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Releases the slots held by a batch of received messages.
+    pub fn release(&self, count: usize) {
+        self.received.fetch_add(count, Ordering::AcqRel);
+        self.permits.fetch_add(count, Ordering::AcqRel);
+    }
+
+    /// The number of messages which have been pushed into the queue, or are being pushed.
+    pub fn sent(&self) -> usize {
+        self.sent.load(Ordering::Acquire)
+    }
+
+    /// True if the receiver has popped at least `target` messages.
+    pub fn is_received(&self, target: usize) -> bool {
+        self.received.load(Ordering::Acquire) >= target
+    }
+
+    #[cfg(feature = "futures-traits")]
+    pub fn has_permits(&self) -> bool {
+        self.permits.load(Ordering::Acquire) > 0
+    }
+
+    pub fn is_sending(&self) -> bool {
+        self.sending.load(Ordering::SeqCst) > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Pushes a value into the queue.  The caller must hold a permit.
+    pub fn push(&self, value: T) {
+        // if the count followed the push, a barrier could count another sender's pending message in place of this one
+        self.sent.fetch_add(1, Ordering::AcqRel);
+
+        if self.queue.push(value).is_err() {
+            panic!("mpsc queue overflow: a value was pushed without a permit");
+        }
+
+        self.sending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct UnboundedStateExtension<T> {
+    queue: SegQueue<T>,
+}
+
+impl<T> UnboundedStateExtension<T> {
+    pub fn new() -> Self {
+        Self {
+            queue: SegQueue::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream},
+        test::{noop_context, panic_context},
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{channel, unbounded, Receiver, Sender};
+
+    fn pin<'a, 'b>(
+        chan: &mut (Sender<Message>, Receiver<Message>),
+    ) -> (Pin<&mut Sender<Message>>, Pin<&mut Receiver<Message>>) {
+        let tx = Pin::new(&mut chan.0);
+        let rx = Pin::new(&mut chan.1);
+
+        (tx, rx)
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Message(usize);
+
+    #[test]
+    fn send_accepted() {
+        let mut cx = panic_context();
+        let mut chan = channel(2);
+        let (tx, _) = pin(&mut chan);
+
+        assert_eq!(PollSend::Ready, tx.poll_send(&mut cx, Message(1)));
+    }
+
+    #[test]
+    fn send_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, _rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+    }
+
+    #[test]
+    fn send_recv() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn try_send_try_recv() {
+        use crate::{sink::TrySendError, stream::TryRecvError};
+
+        let (mut tx, mut rx) = channel(1);
+
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(
+            Err(TrySendError::Pending(Message(2))),
+            tx.try_send(Message(2))
+        );
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+
+        drop(tx);
+        assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
+    }
+
+    #[test]
+    fn received_values_are_moved() {
+        let (mut tx, mut rx) = channel(2);
+
+        let value = std::sync::Arc::new(vec![0u8; 16]);
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        let received = rx.try_recv().unwrap();
+        assert_eq!(2, std::sync::Arc::strong_count(&value));
+
+        drop(received);
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn unbounded_received_values_are_moved() {
+        let (mut tx, mut rx) = unbounded();
+
+        let value = std::sync::Arc::new(vec![0u8; 16]);
+        assert_eq!(Ok(()), tx.try_send(value.clone()));
+
+        drop(rx.try_recv().unwrap());
+        assert_eq!(1, std::sync::Arc::strong_count(&value));
+    }
+
+    #[test]
+    fn len_capacity() {
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(2, tx.capacity());
+        assert!(tx.is_empty());
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(()), tx.try_send(Message(2)));
+        assert_eq!(2, tx.len());
+        assert_eq!(2, rx.len());
+
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+        assert_eq!(1, tx.len());
+        assert_eq!(Ok(Message(2)), rx.try_recv());
+        assert!(rx.is_empty());
+        assert_eq!(2, rx.capacity());
+    }
+
+    #[test]
+    fn try_reserve() {
+        use crate::sink::TrySendError;
+
+        let (tx, mut rx) = channel(1);
+
+        let permit = tx.try_reserve().expect("reserve failed");
+        assert_eq!(Err(TrySendError::Pending(())), tx.try_reserve().map(drop));
+        assert_eq!(
+            Err(TrySendError::Pending(Message(2))),
+            tx.clone().try_send(Message(2))
+        );
+
+        permit.send(Message(1));
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+        assert!(tx.try_reserve().is_ok());
+    }
+
+    #[test]
+    fn permit_drop_releases_slot() {
+        let (tx, mut rx) = channel::<Message>(1);
+
+        let permit = tx.try_reserve().expect("reserve failed");
+        assert!(tx.try_reserve().is_err());
+        drop(permit);
+
+        assert!(tx.try_reserve().is_ok());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn permit_drop_wakes_sender() {
+        let (mut tx, _rx) = channel(1);
+        let tx2 = tx.clone();
+
+        let permit = tx2.try_reserve().expect("reserve failed");
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(1)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(1))
+        );
+        assert_eq!(0, w1_count.get());
+
+        drop(permit);
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn try_reserve_receiver_disconnect() {
+        use crate::sink::TrySendError;
+
+        let (tx, rx) = channel::<Message>(1);
+        drop(rx);
+
+        assert_eq!(Err(TrySendError::Rejected(())), tx.try_reserve().map(drop));
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(100);
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+        );
+
+        drop(tx);
+        drop(tx2);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn migrate_waker() {
+        let (mut tx, mut rx) = channel(2);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        let (w2, w2_count) = new_count_waker();
+        let w2_context = Context::from_waker(&w2);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w2_context.into())
+        );
+
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(0, w1_count.get());
+        assert_eq!(1, w2_count.get());
+    }
+
+    #[test]
+    fn transfer() {
+        let (mut tx, mut rx) = channel(2);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        let mut rx = rx.transfer();
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(0, w1_count.get());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn recv_many() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(3);
+        let mut buffer = Vec::new();
+
+        for i in 1..=3 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut rx).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+        assert_eq!(vec![Message(1), Message(2)], buffer);
+
+        // both slots were released
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(4))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(5))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(3),
+            Pin::new(&mut rx).poll_recv_many(&mut cx, &mut buffer, 10)
+        );
+        assert_eq!(5, buffer.len());
+
+        drop(tx);
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv_many(&mut cx, &mut buffer, 10)
+        );
+    }
+
+    #[test]
+    fn recv_many_wakes_sender_once() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(2);
+        let mut buffer = Vec::new();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(3))
+        );
+
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut rx).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+        assert_eq!(1, w1_count.get());
+
+        let (w2, w2_count) = new_count_waker();
+        let w2_context = Context::from_waker(&w2);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv_many(&mut w2_context.into(), &mut buffer, 2)
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
+        );
+        assert_eq!(1, w2_count.get());
+    }
+
+    #[test]
+    fn close_drains() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(2);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        rx.close();
+
+        assert_eq!(
+            PollSend::Rejected(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn close_wakes_sender() {
+        let (mut tx, mut rx) = channel(1);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        let mut w1_context: crate::Context<'_> = w1_context.into();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(1))
+        );
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+        );
+        assert_eq!(0, w1_count.get());
+
+        rx.close();
+
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollSend::Rejected(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context, Message(2))
+        );
+    }
+
+    #[test]
+    fn close_waits_for_permit() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(2);
+
+        let permit = tx.try_reserve().expect("reserve failed");
+        rx.close();
+
+        assert!(tx.try_reserve().is_err());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        permit.send(Message(1));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn close_permit_drop_wakes_receiver() {
+        let (tx, mut rx) = channel::<Message>(2);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        let mut w1_context: crate::Context<'_> = w1_context.into();
+
+        let permit = tx.try_reserve().expect("reserve failed");
+        rx.close();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+
+        drop(permit);
+
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[test]
+    fn diagnostics() {
+        let (mut tx, mut rx) = channel(1);
+        assert_eq!(None, tx.diagnostics().since_last_write);
+
+        let (w1, _w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+        assert_eq!(1, tx.diagnostics().waiting_receivers);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(1))
+        );
+
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(2))
+        );
+
+        let diagnostics = rx.diagnostics();
+        assert_eq!(1, diagnostics.waiting_senders);
+        assert_eq!(0, diagnostics.waiting_receivers);
+        assert_eq!(None, diagnostics.blocking_slot);
+        assert!(diagnostics.since_last_write.is_some());
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn waiters() {
+        let (mut tx, mut rx) = channel(1);
+
+        let (w1, _w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+        assert_eq!(1, tx.waiters().receivers);
+        assert_eq!(0, tx.waiters().senders);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(1))
+        );
+        assert_eq!(0, rx.waiters().receivers);
+
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollSend::Pending(Message(2)),
+            Pin::new(&mut tx).poll_send(&mut w1_context.into(), Message(2))
+        );
+        assert_eq!(1, rx.waiters().senders);
+    }
+
+    #[test]
+    fn barrier() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = channel(4);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1);
+        let mut barrier = Box::pin(tx.barrier());
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert!(w1_count.get() >= 1);
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+    }
+
+    #[test]
+    fn barrier_excludes_later_messages() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel(4);
+
+        let mut w1_context = Context::from_waker(futures_test::task::noop_waker_ref());
+        let mut barrier = Box::pin(tx.barrier());
+
+        let mut tx2 = tx.clone();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn barrier_receiver_dropped() {
+        let mut cx = panic_context();
+        let (mut tx, rx) = channel(4);
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = Context::from_waker(&w1);
+        let mut barrier = Box::pin(tx.barrier());
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        drop(rx);
+        assert_eq!(1, w1_count.get());
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+    }
+
+    #[test]
+    fn barrier_multiple_senders() {
+        let mut cx = noop_context();
+        let (mut tx1, mut rx) = channel(4);
+        let mut tx2 = tx1.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(1))
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx1).poll_send(&mut cx, Message(2))
+        );
+
+        // the barrier covers the messages of every sender, not just its own
+        let mut w1_context = Context::from_waker(futures_test::task::noop_waker_ref());
+        let mut barrier = Box::pin(tx1.barrier());
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(Poll::Pending, barrier.as_mut().poll(&mut w1_context));
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(Poll::Ready(()), barrier.as_mut().poll(&mut w1_context));
+    }
+
+    #[test]
+    fn weak_sender_upgrade() {
+        let mut cx = panic_context();
+        let (tx, mut rx) = channel(2);
+        let weak = tx.downgrade();
+
+        let mut upgraded = weak.upgrade().expect("sender is alive");
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut upgraded).poll_send(&mut cx, Message(1))
+        );
+
+        drop(tx);
+        drop(upgraded);
+
+        assert!(weak.upgrade().is_none());
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn weak_sender_does_not_keep_channel_open() {
+        let (tx, mut rx) = channel::<Message>(2);
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        let mut cx: crate::Context<'_> = w1_context.into();
+
+        let _weak = tx.downgrade();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(tx);
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
     }
 
+    #[cfg(feature = "std")]
     #[test]
-    fn send_blocks() {
-        let mut cx = panic_context();
-        let (mut tx, _rx) = channel(2);
+    fn sender_panic_poisons() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel::<Message>(2);
+        let tx2 = tx.clone();
+
+        drop(tx);
+        crate::test::drop_panicking(tx2);
 
         assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut rx).poll_recv(&mut cx)
         );
     }
 
     #[test]
-    fn send_recv() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(2);
+    fn drain() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel(4);
 
         assert_eq!(
             PollSend::Ready,
             Pin::new(&mut tx).poll_send(&mut cx, Message(1))
         );
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(2))
-        );
-        assert_eq!(
-            PollSend::Pending(Message(3)),
-            Pin::new(&mut tx).poll_send(&mut noop_context(), Message(3))
-        );
+        let permit = tx.try_reserve().expect("reserve failed");
 
+        let mut drain = rx.drain();
         assert_eq!(
             PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            Pin::new(&mut drain).poll_recv(&mut cx)
         );
 
+        // the drain waits for the acquired permit
+        assert_eq!(PollRecv::Pending, Pin::new(&mut drain).poll_recv(&mut cx));
+        permit.send(Message(2));
         assert_eq!(
             PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
+            Pin::new(&mut drain).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut drain).poll_recv(&mut cx)
         );
 
         assert_eq!(
-            PollRecv::Pending,
-            Pin::new(&mut rx).poll_recv(&mut noop_context())
+            PollSend::Rejected(Message(3)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(3))
         );
     }
 
     #[test]
-    fn sender_disconnect() {
-        let mut cx = panic_context();
-        let (mut tx, mut rx) = channel(100);
-        let mut tx2 = tx.clone();
+    fn poll_closed() {
+        let (tx, rx) = channel::<Message>(2);
+        let tx2 = tx.clone();
+        assert!(!tx.is_closed());
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
-        );
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
 
-        assert_eq!(
-            PollSend::Ready,
-            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
-        );
+        drop(rx);
 
-        drop(tx);
-        drop(tx2);
+        assert_eq!(1, w1_count.get());
+        assert!(tx.is_closed());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
+        assert_eq!(Poll::Ready(()), tx2.poll_closed(&mut noop_context()));
+    }
 
-        assert_eq!(
-            PollRecv::Ready(Message(1)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
-        );
+    #[test]
+    fn poll_closed_receiver_close() {
+        let (tx, mut rx) = channel::<Message>(2);
 
-        assert_eq!(
-            PollRecv::Ready(Message(2)),
-            Pin::new(&mut rx).poll_recv(&mut cx)
-        );
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
+
+        rx.close();
+
+        assert_eq!(1, w1_count.get());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
+    }
+
+    #[test]
+    fn unbounded_poll_closed() {
+        let (tx, rx) = unbounded::<Message>();
+        assert!(!tx.is_closed());
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
+
+        drop(rx);
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(1, w1_count.get());
+        assert!(tx.is_closed());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
     }
 
     #[test]
@@ -462,13 +1957,148 @@ mod tests {
 
         assert_eq!(1, w1_count.get());
     }
+
+    #[test]
+    fn unbounded_send_never_blocks() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = unbounded();
+
+        for i in 0..1000 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut cx, Message(i))
+            );
+        }
+
+        for i in 0..1000 {
+            assert_eq!(
+                PollRecv::Ready(Message(i)),
+                Pin::new(&mut rx).poll_recv(&mut cx)
+            );
+        }
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn unbounded_try_send_try_recv() {
+        use crate::{sink::TrySendError, stream::TryRecvError};
+
+        let (mut tx, mut rx) = unbounded();
+
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(Message(1)));
+        assert_eq!(Ok(Message(1)), rx.try_recv());
+
+        drop(rx);
+        assert_eq!(
+            Err(TrySendError::Rejected(Message(2))),
+            tx.try_send(Message(2))
+        );
+    }
+
+    #[test]
+    fn unbounded_sender_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = unbounded();
+        let mut tx2 = tx.clone();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx2).poll_send(&mut cx, Message(2))
+        );
+
+        drop(tx);
+        drop(tx2);
+
+        assert_eq!(
+            PollRecv::Ready(Message(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(Message(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn unbounded_receiver_disconnect() {
+        let mut cx = panic_context();
+        let (mut tx, rx) = unbounded();
+
+        drop(rx);
+
+        assert_eq!(
+            PollSend::Rejected(Message(1)),
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+    }
+
+    #[test]
+    fn unbounded_wake_receiver() {
+        let mut cx = panic_context();
+        let (mut tx, mut rx) = unbounded();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(0, w1_count.get());
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Message(1))
+        );
+
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn unbounded_wake_receiver_on_disconnect() {
+        let (tx, mut rx) = unbounded::<()>();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(0, w1_count.get());
+
+        drop(tx);
+
+        assert_eq!(1, w1_count.get());
+    }
 }
 
 #[cfg(test)]
 mod tokio_tests {
     use std::time::Duration;
 
-    use tokio::{task::spawn, time::timeout};
+    use tokio::{
+        task::spawn,
+        time::{sleep, timeout},
+    };
 
     use crate::{
         sink::Sink,
@@ -504,6 +2134,81 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recv_many() {
+        for cap in capacity_iter() {
+            let (mut tx, mut rx) = super::channel(cap);
+
+            let join = spawn(async move {
+                for message in Message::new_iter(0) {
+                    tx.send(message).await.expect("send failed");
+                }
+            });
+
+            let rx_handle = spawn(async move {
+                let mut channel = Channel::new(0);
+                let mut buffer = Vec::new();
+                while rx.recv_many(&mut buffer, 16).await > 0 {
+                    for message in buffer.drain(..) {
+                        channel.assert_message(&message);
+                    }
+                }
+                join.await.expect("Join failed");
+            });
+
+            timeout(TEST_TIMEOUT, rx_handle)
+                .await
+                .expect("test timeout")
+                .expect("join error");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transfer() {
+        let (mut tx, rx) = super::channel(4);
+
+        let join = spawn(async move {
+            for message in Message::new_iter(0) {
+                tx.send(message).await.expect("send failed");
+            }
+        });
+
+        // each task receives a few messages, then hands the receiver to a new task
+        let rx_handle = spawn(async move {
+            let mut channel = Channel::new(0);
+            let mut rx = Some(rx);
+
+            while let Some(receiver) = rx.take() {
+                let handoff = spawn(async move {
+                    let mut receiver = receiver;
+                    let mut received = Vec::new();
+                    for _ in 0..10 {
+                        match receiver.recv().await {
+                            Some(message) => received.push(message),
+                            None => return (None, received),
+                        }
+                    }
+
+                    (Some(receiver.transfer()), received)
+                });
+
+                let (receiver, received) = handoff.await.expect("join failed");
+                for message in received {
+                    channel.assert_message(&message);
+                }
+
+                rx = receiver;
+            }
+
+            join.await.expect("Join failed");
+        });
+
+        timeout(TEST_TIMEOUT, rx_handle)
+            .await
+            .expect("test timeout")
+            .expect("join error");
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn multi_sender() {
         for cap in capacity_iter() {
@@ -534,6 +2239,34 @@ mod tokio_tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unbounded_multi_sender() {
+        let (tx, mut rx) = super::unbounded();
+
+        for i in 0..CHANNEL_TEST_SENDERS {
+            let mut tx2 = tx.clone();
+            spawn(async move {
+                for message in Message::new_multi_sender(i) {
+                    tx2.send(message).await.expect("send failed");
+                }
+            });
+        }
+
+        drop(tx);
+
+        let rx_handle = spawn(async move {
+            let mut channel = Channels::new(CHANNEL_TEST_SENDERS);
+            while let Some(message) = rx.recv().await {
+                channel.assert_message(&message);
+            }
+        });
+
+        timeout(TEST_TIMEOUT, rx_handle)
+            .await
+            .expect("test timeout")
+            .expect("join error");
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn clone_monster() {
         for cap in capacity_iter() {
@@ -582,6 +2315,51 @@ mod tokio_tests {
                 .expect("join failed");
         }
     }
+
+    #[tokio::test]
+    async fn reserve() {
+        let (tx, mut rx) = super::channel(1);
+
+        let permit = tx.reserve().await.expect("reserve failed");
+        permit.send(1usize);
+
+        let recv = spawn(async move {
+            sleep(Duration::from_millis(10)).await;
+            let message = rx.recv().await;
+            (message, rx)
+        });
+
+        let permit = timeout(TEST_TIMEOUT, tx.reserve())
+            .await
+            .expect("test timeout")
+            .expect("reserve failed");
+        let (message, mut rx) = recv.await.expect("join error");
+        assert_eq!(Some(1), message);
+
+        permit.send(2usize);
+        assert_eq!(Some(2), rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn reserve_cancel_safe() {
+        let (tx, mut rx) = super::channel(1);
+
+        let permit = tx.reserve().await.expect("reserve failed");
+        assert!(timeout(Duration::from_millis(10), tx.reserve())
+            .await
+            .is_err());
+        drop(permit);
+
+        let permit = timeout(TEST_TIMEOUT, tx.reserve())
+            .await
+            .expect("test timeout")
+            .expect("reserve failed");
+        permit.send(1usize);
+
+        drop(tx);
+        assert_eq!(Some(1), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
 }
 
 #[cfg(test)]
@@ -696,3 +2474,38 @@ mod async_std_tests {
         }
     }
 }
+
+#[cfg(all(test, feature = "loom"))]
+mod loom_tests {
+    use std::{future::Future, task::Poll};
+
+    use futures_test::task::noop_context;
+    use loom::thread;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    use super::channel;
+
+    #[test]
+    fn barrier_covers_own_message() {
+        loom::model(|| {
+            let (mut tx1, mut rx) = channel(2);
+            let mut tx2 = tx1.clone();
+
+            let thread = thread::spawn(move || {
+                tx2.try_send(2usize).expect("send failed");
+            });
+
+            tx1.try_send(1usize).expect("send failed");
+            let mut barrier = Box::pin(tx1.barrier());
+
+            // the other sender's message may be received first, and must not resolve the barrier
+            let first = rx.try_recv();
+            if let Poll::Ready(()) = barrier.as_mut().poll(&mut noop_context()) {
+                assert_eq!(Ok(1), first);
+            }
+
+            thread.join().unwrap();
+        });
+    }
+}
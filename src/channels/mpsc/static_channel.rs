@@ -0,0 +1,428 @@
+//! A statically allocated mpsc channel, with a const capacity.
+//!
+//! The buffer is stored inline, so a `StaticChannel` can be placed in a `static`, and the channel
+//! performs no heap allocation when it is created.  `split()` borrows the channel, and produces the sender and receiver.
+//!
+//! The buffer is an inline array of `N` slots, guarded by a single lock.  Each message has one reader, so a slot holds only
+//! the message, as an `Option<T>`.  Unlike the heap-allocated slots, it needs no lock or counters of its own, so the channel can be built by a const fn.
+use core::{fmt, pin::Pin};
+
+use static_assertions::assert_impl_all;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{ClosedReason, PollRecv, Stream},
+    sync::{lock::Mutex, notifier::Notifier},
+    Context,
+};
+
+/// A fixed-capacity mpsc channel, with an inline buffer of `N` messages.
+///
+/// ```rust
+/// use postage::mpsc::StaticChannel;
+/// use postage::prelude::*;
+///
+/// static CHANNEL: StaticChannel<usize, 16> = StaticChannel::new();
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut tx, mut rx) = CHANNEL.split();
+///
+///     tx.send(1).await.ok();
+///     assert_eq!(Some(1), rx.recv().await);
+/// }
+/// ```
+pub struct StaticChannel<T, const N: usize> {
+    state: Mutex<State<T, N>>,
+    // senders notify the receiver, and the receiver notifies senders
+    on_send: Notifier,
+    on_recv: Notifier,
+}
+
+struct State<T, const N: usize> {
+    buffer: [Option<T>; N],
+    head: usize,
+    len: usize,
+    senders: usize,
+    receiver: bool,
+    split: bool,
+}
+
+assert_impl_all!(StaticChannel<usize, 4>: Send, Sync, fmt::Debug);
+assert_impl_all!(StaticSender<'static, usize, 4>: Send, Sync, Clone, fmt::Debug);
+assert_impl_all!(StaticReceiver<'static, usize, 4>: Send, Sync, fmt::Debug);
+
+impl<T, const N: usize> StaticChannel<T, N> {
+    /// Constructs the channel.  This is a const fn, so the channel can initialize a `static`.
+    ///
+    /// A capacity of zero fails to compile:
+    /// ```compile_fail
+    /// use postage::mpsc::StaticChannel;
+    ///
+    /// static CHANNEL: StaticChannel<usize, 0> = StaticChannel::new();
+    /// ```
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "a StaticChannel must have a capacity of at least 1") };
+
+        Self {
+            state: Mutex::new(State {
+                buffer: [const { None }; N],
+                head: 0,
+                len: 0,
+                senders: 0,
+                receiver: false,
+                split: false,
+            }),
+            on_send: Notifier::new(),
+            on_recv: Notifier::new(),
+        }
+    }
+
+    /// The number of messages the channel can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Produces the sender and receiver.  The sender can be cloned.
+    ///
+    /// # Panics
+    /// Panics if the channel has already been split.
+    pub fn split(&self) -> (StaticSender<'_, T, N>, StaticReceiver<'_, T, N>) {
+        let mut state = self.state.lock();
+        assert!(!state.split, "StaticChannel::split called more than once");
+
+        state.split = true;
+        state.senders = 1;
+        state.receiver = true;
+
+        (
+            StaticSender { channel: self },
+            StaticReceiver { channel: self },
+        )
+    }
+}
+
+impl<T, const N: usize> Default for StaticChannel<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for StaticChannel<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("StaticChannel")
+            .field("capacity", &N)
+            .field("len", &state.len)
+            .field("senders", &state.senders)
+            .finish()
+    }
+}
+
+/// The sender half of a `StaticChannel`.  Can be cloned.
+pub struct StaticSender<'c, T, const N: usize> {
+    channel: &'c StaticChannel<T, N>,
+}
+
+impl<'c, T, const N: usize> Sink for StaticSender<'c, T, N> {
+    type Item = T;
+
+    fn poll_send(self: Pin<&mut Self>, cx: &mut Context<'_>, value: T) -> PollSend<T> {
+        let channel = self.channel;
+
+        loop {
+            let guard = channel.on_recv.guard();
+
+            {
+                let mut state = channel.state.lock();
+                if !state.receiver {
+                    return PollSend::Rejected(value);
+                }
+
+                if state.len < N {
+                    let index = (state.head + state.len) % N;
+                    state.buffer[index] = Some(value);
+                    state.len += 1;
+                    drop(state);
+
+                    channel.on_send.notify();
+                    return PollSend::Ready;
+                }
+            }
+
+            channel.on_recv.subscribe(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollSend::Pending(value);
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Clone for StaticSender<'c, T, N> {
+    fn clone(&self) -> Self {
+        self.channel.state.lock().senders += 1;
+
+        Self {
+            channel: self.channel,
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Drop for StaticSender<'c, T, N> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.senders -= 1;
+
+        if state.senders == 0 {
+            drop(state);
+            self.channel.on_send.notify();
+        }
+    }
+}
+
+impl<'c, T, const N: usize> fmt::Debug for StaticSender<'c, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSender").finish()
+    }
+}
+
+/// The receiver half of a `StaticChannel`.
+pub struct StaticReceiver<'c, T, const N: usize> {
+    channel: &'c StaticChannel<T, N>,
+}
+
+impl<'c, T, const N: usize> Stream for StaticReceiver<'c, T, N> {
+    type Item = T;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<T> {
+        let channel = self.channel;
+
+        loop {
+            let guard = channel.on_send.guard();
+
+            {
+                let mut state = channel.state.lock();
+                if state.len > 0 {
+                    let index = state.head;
+                    let value = state.buffer[index].take();
+                    state.head = (index + 1) % N;
+                    state.len -= 1;
+                    drop(state);
+
+                    channel.on_recv.notify();
+                    return PollRecv::Ready(value.expect("occupied slot is empty"));
+                }
+
+                if state.senders == 0 {
+                    return PollRecv::Closed(ClosedReason::SendersDropped);
+                }
+            }
+
+            channel.on_send.subscribe(cx);
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<'c, T, const N: usize> Drop for StaticReceiver<'c, T, N> {
+    fn drop(&mut self) {
+        let mut state = self.channel.state.lock();
+        state.receiver = false;
+
+        // buffered messages can't be received, and are dropped now rather than when the channel is dropped
+        for slot in state.buffer.iter_mut() {
+            *slot = None;
+        }
+        state.len = 0;
+        drop(state);
+
+        self.channel.on_recv.notify();
+    }
+}
+
+impl<'c, T, const N: usize> fmt::Debug for StaticReceiver<'c, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticReceiver").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::Pin;
+
+    use super::StaticChannel;
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{ClosedReason, PollRecv, Stream},
+        test::{noop_context, panic_context},
+    };
+    use futures_test::task::new_count_waker;
+
+    static CHANNEL: StaticChannel<usize, 2> = StaticChannel::new();
+
+    #[test]
+    fn static_placement() {
+        let (mut tx, mut rx) = CHANNEL.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(2, CHANNEL.capacity());
+    }
+
+    #[test]
+    #[should_panic(expected = "split called more than once")]
+    fn split_twice() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let _first = channel.split();
+        let _second = channel.split();
+    }
+
+    #[test]
+    fn send_recv_in_order() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        for i in 0..5 {
+            assert_eq!(
+                PollSend::Ready,
+                Pin::new(&mut tx).poll_send(&mut panic_context(), i)
+            );
+            assert_eq!(
+                PollRecv::Ready(i),
+                Pin::new(&mut rx).poll_recv(&mut panic_context())
+            );
+        }
+    }
+
+    #[test]
+    fn full_buffer() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), 3)
+        );
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(3));
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+
+    #[test]
+    fn wake_sender() {
+        let channel = StaticChannel::<usize, 1>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        let (waker, count) = new_count_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut cx, 2)
+        );
+        assert_eq!(0, count.get());
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(1, count.get());
+    }
+
+    #[test]
+    fn wake_receiver() {
+        let channel = StaticChannel::<usize, 1>::new();
+        let (mut tx, mut rx) = channel.split();
+
+        let (waker, count) = new_count_waker();
+        let mut cx = crate::Context::from_waker(&waker);
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(0, count.get());
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(1, count.get());
+    }
+
+    #[test]
+    fn senders_dropped() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, mut rx) = channel.split();
+        let tx2 = tx.clone();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        drop(tx);
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+
+        drop(tx2);
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn receiver_dropped() {
+        let channel = StaticChannel::<usize, 2>::new();
+        let (mut tx, rx) = channel.split();
+
+        drop(rx);
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut tx).poll_send(&mut noop_context(), 1)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use super::StaticChannel;
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{CHANNEL_TEST_ITERATIONS, CHANNEL_TEST_SENDERS, TEST_TIMEOUT},
+    };
+
+    static CHANNEL: StaticChannel<(usize, usize), 4> = StaticChannel::new();
+
+    #[tokio::test]
+    async fn multi_sender() {
+        let (tx, mut rx) = CHANNEL.split();
+
+        for sender in 0..CHANNEL_TEST_SENDERS {
+            let mut tx = tx.clone();
+            spawn(async move {
+                for i in 0..CHANNEL_TEST_ITERATIONS {
+                    tx.send((sender, i)).await.expect("send failed");
+                }
+            });
+        }
+        drop(tx);
+
+        let mut next = [0usize; CHANNEL_TEST_SENDERS];
+        timeout(TEST_TIMEOUT, async move {
+            while let Some((sender, i)) = rx.recv().await {
+                assert_eq!(next[sender], i);
+                next[sender] += 1;
+            }
+
+            assert!(next.iter().all(|n| *n == CHANNEL_TEST_ITERATIONS));
+        })
+        .await
+        .expect("test timeout");
+    }
+}
@@ -1,8 +1,9 @@
 //! Oneshot channels transmit a single value between a sender and a reciever.  
 //!
 //! Neither can be cloned.  If the sender drops, the receiver recieves a `None` value.
-use std::fmt;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use core::fmt;
+use core::task::Poll;
 
 use super::SendMessage;
 use crate::{
@@ -39,7 +40,7 @@ impl<T> Sink for Sender<T> {
     type Item = T;
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         _cx: &mut crate::Context<'_>,
         value: Self::Item,
     ) -> PollSend<Self::Item> {
@@ -50,6 +51,26 @@ impl<T> Sink for Sender<T> {
     }
 }
 
+impl<T> Sender<T> {
+    /// Returns true if the receiver has been dropped.  A message sent to a closed channel is rejected.
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_receiver_closed()
+    }
+
+    /// Returns `Poll::Ready(())` if the receiver has been dropped.  Otherwise, registers the task to be woken when it is dropped.
+    pub fn poll_closed(&self, cx: &mut crate::Context<'_>) -> Poll<()> {
+        self.shared.poll_receiver_closed(cx)
+    }
+
+    /// Waits until the receiver has been dropped.
+    ///
+    /// This allows a producer to abandon expensive work if the response is no longer needed,
+    /// for example with `select!` on `tx.closed()` and the work.
+    pub async fn closed(&self) {
+        core::future::poll_fn(|cx| self.poll_closed(&mut cx.into())).await
+    }
+}
+
 impl<T> fmt::Debug for Sender<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Sender").finish()
@@ -59,32 +80,32 @@ impl<T> fmt::Debug for Sender<T> {
 #[cfg(feature = "futures-traits")]
 mod impl_futures {
     use crate::sink::SendError;
-    use std::task::Poll;
+    use core::task::Poll;
 
     impl<T> futures::sink::Sink<T> for super::Sender<T> {
         type Error = crate::sink::SendError<T>;
 
         fn poll_ready(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
-        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        fn start_send(self: core::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
             self.shared.send(item).map_err(|t| SendError(t))
         }
 
         fn poll_flush(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
         fn poll_close(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
@@ -109,7 +130,7 @@ impl<T> Stream for Receiver<T> {
     type Item = T;
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
         self.shared.recv(cx)
@@ -134,7 +155,7 @@ mod tests {
 
     use crate::{
         sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         test::{noop_context, panic_context},
         Context,
     };
@@ -174,7 +195,10 @@ mod tests {
             PollRecv::Ready(Message(1)),
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -184,7 +208,28 @@ mod tests {
 
         drop(tx);
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn poll_closed() {
+        use std::task::Poll;
+
+        let (tx, rx) = channel::<Message>();
+        assert!(!tx.is_closed());
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, tx.poll_closed(&mut w1_context.into()));
+
+        drop(rx);
+
+        assert_eq!(1, w1_count.get());
+        assert!(tx.is_closed());
+        assert_eq!(Poll::Ready(()), tx.poll_closed(&mut noop_context()));
     }
 
     #[test]
@@ -195,7 +240,10 @@ mod tests {
         assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
 
         drop(tx);
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -215,7 +263,24 @@ mod tests {
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sender_panic_poisons() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = channel::<Message>();
+
+        crate::test::drop_panicking(tx);
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -280,7 +345,7 @@ mod tests {
         assert_eq!(1, w1_count.get());
 
         assert_eq!(
-            PollRecv::Closed,
+            PollRecv::Closed(ClosedReason::SendersDropped),
             Pin::new(&mut rx).poll_recv(&mut w1_context)
         );
     }
@@ -300,6 +365,22 @@ mod tokio_tests {
 
     use super::channel;
 
+    #[tokio::test]
+    async fn closed() {
+        let (mut tx, rx) = channel::<usize>();
+
+        let rx_handle = spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(rx);
+        });
+
+        timeout(TEST_TIMEOUT, tx.closed())
+            .await
+            .expect("test timeout");
+        assert!(tx.send(1).await.is_err());
+        rx_handle.await.expect("join failed");
+    }
+
     #[tokio::test]
     async fn simple() {
         for _ in 0..CHANNEL_TEST_ITERATIONS {
@@ -0,0 +1,479 @@
+//! A multi-producer, single-consumer channel with prioritized lanes.
+//!
+//! Each lane has its own fixed capacity.  Lane `0` has the highest priority, and the receiver always drains
+//! the highest priority lane which has a message.  Messages within a lane are received in the order they were sent.
+//!
+//! A full lane only suspends senders to that lane, so control messages are not stuck behind bulk data:
+//! ```rust
+//! use postage::{priority, prelude::*};
+//!
+//! const CONTROL: usize = 0;
+//! const DATA: usize = 1;
+//!
+//! let (mut tx, mut rx) = priority::channel([4, 16]);
+//!
+//! tx.try_send_priority(DATA, "chunk").ok();
+//! tx.try_send_priority(CONTROL, "stop").ok();
+//!
+//! assert_eq!(Ok("stop"), rx.try_recv());
+//! assert_eq!(Ok("chunk"), rx.try_recv());
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+
+use super::SendMessage;
+use crate::{
+    sink::{PollSend, SendError, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
+    sync::{shared, ReceiverShared, SenderShared},
+};
+use crossbeam_queue::ArrayQueue;
+use static_assertions::{assert_impl_all, assert_not_impl_all};
+
+/// Constructs a pair of priority channel endpoints, with one lane for each capacity.  Lane `0` has the highest priority.
+///
+/// Panics if there are no lanes, or if a lane has zero capacity.
+pub fn channel<T, const N: usize>(capacities: [usize; N]) -> (Sender<T>, Receiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating priority channel with capacities {:?}", capacities);
+
+    assert!(N > 0, "a priority channel requires at least one lane");
+    assert!(
+        capacities.iter().all(|capacity| *capacity > 0),
+        "priority channel lanes require a non-zero capacity"
+    );
+
+    let (tx_shared, rx_shared) = shared(StateExtension::new(&capacities));
+    let sender = Sender {
+        shared: tx_shared,
+        level: N - 1,
+    };
+
+    let receiver = Receiver { shared: rx_shared };
+
+    (sender, receiver)
+}
+
+/// The sender half of a priority channel.  Can be cloned.
+///
+/// The `Sink` implementation sends to the sender's lane, which is the lowest priority lane unless the sender
+/// was created with `Sender::lane`.  Any lane can be targeted with `send_priority` and `try_send_priority`.
+pub struct Sender<T> {
+    shared: SenderShared<StateExtension<T>>,
+    level: usize,
+}
+
+assert_impl_all!(Sender<String>: Clone, Send, Sync, fmt::Debug);
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            level: self.level,
+        }
+    }
+}
+
+impl<T> Sink for Sender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        self.poll_send_priority(self.level, cx, value)
+    }
+}
+
+impl<T> Sender<T> {
+    /// Returns a sender whose `Sink` implementation sends to the given lane.
+    ///
+    /// Panics if the lane does not exist.
+    pub fn lane(&self, level: usize) -> Sender<T> {
+        assert!(
+            level < self.lanes(),
+            "priority lane {} does not exist",
+            level
+        );
+
+        Self {
+            shared: self.shared.clone(),
+            level,
+        }
+    }
+
+    /// The number of lanes in the channel.
+    pub fn lanes(&self) -> usize {
+        self.shared.extension().lanes.len()
+    }
+
+    /// Sends a message into the given lane, waiting if the lane is full.
+    ///
+    /// Returns `Err(SendError(value))` if the receiver has been dropped.  Panics if the lane does not exist.
+    pub async fn send_priority(&self, level: usize, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+
+        core::future::poll_fn(|cx| {
+            let mut cx = cx.into();
+            let v = value.take().expect("polled after completion");
+            match self.poll_send_priority(level, &mut cx, v) {
+                PollSend::Ready => core::task::Poll::Ready(Ok(())),
+                PollSend::Pending(v) => {
+                    value = Some(v);
+                    core::task::Poll::Pending
+                }
+                PollSend::Rejected(v) => core::task::Poll::Ready(Err(SendError(v))),
+            }
+        })
+        .await
+    }
+
+    /// Attempts to send a message into the given lane, without blocking.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Pending(value))` if the lane is full.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    ///
+    /// Panics if the lane does not exist.
+    pub fn try_send_priority(&self, level: usize, value: T) -> Result<(), TrySendError<T>> {
+        match self.poll_send_priority(level, &mut crate::Context::empty(), value) {
+            PollSend::Ready => Ok(()),
+            PollSend::Pending(value) => Err(TrySendError::Pending(value)),
+            PollSend::Rejected(value) => Err(TrySendError::Rejected(value)),
+        }
+    }
+
+    fn poll_send_priority(
+        &self,
+        level: usize,
+        cx: &mut crate::Context<'_>,
+        mut value: T,
+    ) -> PollSend<T> {
+        let lane = &self.shared.extension().lanes[level];
+
+        loop {
+            if self.shared.is_closed() {
+                return PollSend::Rejected(value);
+            }
+
+            let guard = self.shared.recv_guard();
+            match lane.push(value) {
+                Ok(()) => {
+                    self.shared.notify_receivers();
+                    return PollSend::Ready;
+                }
+                Err(returned) => {
+                    value = returned;
+
+                    self.shared.subscribe_recv(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    return PollSend::Pending(value);
+                }
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("level", &self.level)
+            .finish()
+    }
+}
+
+/// The receiver half of a priority channel.  Cannot be cloned.
+///
+/// Messages are received from the highest priority lane which is not empty.
+pub struct Receiver<T> {
+    shared: ReceiverShared<StateExtension<T>>,
+}
+
+assert_impl_all!(Receiver<SendMessage>: Send, Sync, fmt::Debug);
+assert_not_impl_all!(Receiver<SendMessage>: Clone);
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_recv(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        loop {
+            let guard = self.shared.send_guard();
+            if let Some(v) = self.shared.extension().pop() {
+                self.shared.notify_senders();
+                return PollRecv::Ready(v);
+            }
+
+            if self.shared.is_closed() {
+                // a sender may have pushed a message before it was dropped
+                if !self.shared.extension().is_empty() {
+                    continue;
+                }
+
+                return PollRecv::Closed(self.shared.closed_reason());
+            }
+
+            self.shared.subscribe_send(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// The number of messages in the given lane.  Panics if the lane does not exist.
+    ///
+    /// The value may be stale when other handles are active concurrently.
+    pub fn lane_len(&self, level: usize) -> usize {
+        self.shared.extension().lanes[level].len()
+    }
+
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+struct StateExtension<T> {
+    lanes: Box<[ArrayQueue<T>]>,
+}
+
+impl<T> StateExtension<T> {
+    pub fn new(capacities: &[usize]) -> Self {
+        Self {
+            lanes: capacities
+                .iter()
+                .map(|capacity| ArrayQueue::new(*capacity))
+                .collect(),
+        }
+    }
+
+    /// Pops a message from the highest priority lane which is not empty.
+    pub fn pop(&self) -> Option<T> {
+        self.lanes.iter().find_map(|lane| lane.pop())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        sink::{PollSend, Sink, TrySendError},
+        stream::{ClosedReason, PollRecv, Stream, TryRecvError},
+        test::{noop_context, panic_context},
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::channel;
+
+    #[test]
+    fn highest_lane_first() {
+        let (tx, mut rx) = channel([2, 2, 2]);
+
+        assert_eq!(Ok(()), tx.try_send_priority(2, "low"));
+        assert_eq!(Ok(()), tx.try_send_priority(1, "medium"));
+        assert_eq!(Ok(()), tx.try_send_priority(0, "high"));
+        assert_eq!(Ok(()), tx.try_send_priority(2, "low 2"));
+
+        assert_eq!(Ok("high"), rx.try_recv());
+        assert_eq!(Ok("medium"), rx.try_recv());
+        assert_eq!(Ok("low"), rx.try_recv());
+        assert_eq!(Ok("low 2"), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+    }
+
+    #[test]
+    fn sink_sends_lowest_lane() {
+        let (mut tx, mut rx) = channel([1, 1]);
+        let mut high = tx.lane(0);
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), high.try_send(2));
+
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(1), rx.try_recv());
+    }
+
+    #[test]
+    fn full_lane_does_not_block_other_lanes() {
+        let (tx, mut rx) = channel([1, 1]);
+
+        assert_eq!(Ok(()), tx.try_send_priority(1, 1));
+        assert_eq!(Err(TrySendError::Pending(2)), tx.try_send_priority(1, 2));
+        assert_eq!(Ok(()), tx.try_send_priority(0, 3));
+
+        assert_eq!(1, rx.lane_len(0));
+        assert_eq!(1, rx.lane_len(1));
+        assert_eq!(Ok(3), rx.try_recv());
+        assert_eq!(Ok(1), rx.try_recv());
+    }
+
+    #[test]
+    fn send_wakes_receiver() {
+        let (mut tx, mut rx) = channel([1, 1]);
+
+        let (waker, count) = new_count_waker();
+        let std_cx = std::task::Context::from_waker(&waker);
+        let mut cx: crate::Context<'_> = std_cx.into();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut panic_context(), 1)
+        );
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn recv_wakes_sender() {
+        let (mut tx, mut rx) = channel([1]);
+
+        let (waker, count) = new_count_waker();
+        let std_cx = std::task::Context::from_waker(&waker);
+        let mut cx: crate::Context<'_> = std_cx.into();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 1));
+        assert_eq!(
+            PollSend::Pending(2),
+            Pin::new(&mut tx).poll_send(&mut cx, 2)
+        );
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+        assert_eq!(1, count.get());
+        assert_eq!(PollSend::Ready, Pin::new(&mut tx).poll_send(&mut cx, 2));
+    }
+
+    #[test]
+    fn sender_disconnect() {
+        let (tx, mut rx) = channel([1, 1]);
+
+        assert_eq!(Ok(()), tx.try_send_priority(1, 1));
+        drop(tx);
+
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn receiver_disconnect() {
+        let (tx, rx) = channel([1, 1]);
+        drop(rx);
+
+        assert_eq!(Err(TrySendError::Rejected(1)), tx.try_send_priority(0, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_lane() {
+        let (tx, _rx) = channel::<usize, 2>([1, 1]);
+        let _ = tx.try_send_priority(2, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        let _ = channel::<usize, 2>([1, 0]);
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use crate::{
+        sink::Sink,
+        stream::Stream,
+        test::{CHANNEL_TEST_ITERATIONS, TEST_TIMEOUT},
+    };
+
+    use super::channel;
+
+    #[tokio::test]
+    async fn control_overtakes_data() {
+        let (tx, mut rx) = channel([1, 4]);
+        let mut data = tx.lane(1);
+
+        for i in 0..4usize {
+            data.send(i).await.expect("send failed");
+        }
+
+        // the data lane is full, but control messages are accepted
+        tx.send_priority(0, 100).await.expect("send failed");
+        assert_eq!(Some(100), rx.recv().await);
+        assert_eq!(Some(0), rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn lanes_preserve_order() {
+        let (tx, mut rx) = channel([4, 4]);
+        let control = tx.lane(0);
+        let data = tx.lane(1);
+        drop(tx);
+
+        let control_task = spawn(async move {
+            for i in 0..CHANNEL_TEST_ITERATIONS {
+                control.send_priority(0, (0, i)).await.expect("send failed");
+            }
+        });
+
+        let data_task = spawn(async move {
+            for i in 0..CHANNEL_TEST_ITERATIONS {
+                data.send_priority(1, (1, i)).await.expect("send failed");
+            }
+        });
+
+        let mut next = [0, 0];
+        timeout(TEST_TIMEOUT, async {
+            while let Some((lane, i)) = rx.recv().await {
+                assert_eq!(next[lane], i);
+                next[lane] += 1;
+            }
+        })
+        .await
+        .expect("test timeout");
+
+        assert_eq!([CHANNEL_TEST_ITERATIONS; 2], next);
+        control_task.await.expect("join failed");
+        data_task.await.expect("join failed");
+    }
+}
@@ -3,23 +3,34 @@
 //! When the channel is created, the receiver will immediately observe `T::default()`.  Cloned receivers will immediately observe the latest stored value.
 //!
 //! Senders can mutably borrow the contained value (which notifies receivers on release).  Receivers can immutably borrow the contained value.
+//!
+//! `channel_ref()` constructs a channel for values which are not `Clone`.  The value is stored in an `Arc`, and its receiver produces
+//! `Arc<T>` snapshots instead of clones.
+//!
+//! `map()` constructs a `WatchMap`, which stores values by key.  Receivers can watch a single key, or every change to the map.
 
 use super::SendSyncMessage;
-use std::{
+use alloc::sync::Arc;
+use core::{
     fmt,
     ops::{Deref, DerefMut},
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::sync::lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use static_assertions::{assert_impl_all, assert_not_impl_all};
+use thiserror::Error;
 
 use crate::{
-    sink::{PollSend, Sink},
-    stream::{PollRecv, Stream},
+    sink::{PollSend, Sink, TrySendError},
+    stream::{PollRecv, Stream, TryRecvError},
     sync::{shared, ReceiverShared, SenderShared},
 };
 
+mod map;
+
+pub use map::{map, KeyReceiver, MapReceiver, WatchMap};
+
 /// Constructs a new watch channel pair, filled with `T::default()`.
 pub fn channel<T: Clone + Default>() -> (Sender<T>, Receiver<T>) {
     channel_with(T::default())
@@ -48,6 +59,26 @@ pub fn channel_with_option<T: Clone>() -> (Sender<Option<T>>, Receiver<Option<T>
     channel::<Option<T>>()
 }
 
+/// Constructs a new watch channel pair for values which are not `Clone`, filled with the provided value.
+///
+/// The value is stored in an `Arc`, and the receiver produces `Arc<T>` snapshots, which share the value instead of cloning it.
+/// This is useful for large values (such as a routing table) which are expensive to clone on every change.
+/// The sender updates the channel by sending a new `Arc<T>`.
+pub fn channel_ref<T>(value: T) -> (Sender<Arc<T>>, RefReceiver<T>) {
+    #[cfg(feature = "debug")]
+    log::error!("Creating watch ref channel");
+
+    let (tx_shared, rx_shared) = shared(StateExtension::new(Arc::new(value)));
+    let sender = Sender { shared: tx_shared };
+
+    let receiver = Receiver {
+        shared: rx_shared,
+        generation: AtomicUsize::new(0),
+    };
+
+    (sender, RefReceiver { receiver })
+}
+
 /// The sender half of a watch channel.  The stored value can be updated with the postage::Sink trait.
 pub struct Sender<T> {
     pub(in crate::channels::watch) shared: SenderShared<StateExtension<T>>,
@@ -60,7 +91,7 @@ impl<T> Sink for Sender<T> {
     type Item = T;
 
     fn poll_send(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         _cx: &mut crate::Context<'_>,
         value: Self::Item,
     ) -> PollSend<Self::Item> {
@@ -75,6 +106,26 @@ impl<T> Sink for Sender<T> {
     }
 }
 
+impl<T> Sender<T> {
+    /// Attempts to send a message without blocking.  Equivalent to `Sink::try_send`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(TrySendError::Rejected(value))` if the channel is closed.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        Sink::try_send(self, value)
+    }
+
+    /// Sends a message, blocking the current thread until the message is accepted, or the channel is closed.
+    /// Equivalent to `Sink::blocking_send`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_send(&mut self, value: T) -> Result<(), crate::sink::SendError<T>> {
+        Sink::blocking_send(self, value)
+    }
+}
+
 #[allow(clippy::needless_lifetimes)]
 impl<T> Sender<T> {
     /// Mutably borrows the contained value, blocking the channel while the borrow is held.
@@ -90,6 +141,45 @@ impl<T> Sender<T> {
         }
     }
 
+    /// Stores a new value in the channel, returning the previous value.  Receivers are notified of the new value.
+    ///
+    /// Unlike `Sink::send`, the value is stored even if all receivers have been dropped.
+    pub fn send_replace(&mut self, value: T) -> T {
+        let previous = self.shared.extension().replace(value);
+        self.shared.notify_receivers();
+
+        previous
+    }
+
+    /// Modifies the stored value in place, and notifies receivers.
+    ///
+    /// This avoids cloning large values out of the channel.  The channel is blocked while the closure runs.
+    pub fn send_modify<F>(&mut self, modify: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        self.send_if_modified(|value| {
+            modify(value);
+            true
+        });
+    }
+
+    /// Modifies the stored value in place.  Receivers are only notified if the closure returns true.
+    ///
+    /// The closure should return false only if the value was not changed.  Returns the result of the closure.
+    pub fn send_if_modified<F>(&mut self, modify: F) -> bool
+    where
+        F: FnOnce(&mut T) -> bool,
+    {
+        let modified = self.shared.extension().modify(modify);
+
+        if modified {
+            self.shared.notify_receivers();
+        }
+
+        modified
+    }
+
     /// Creates a new Receiver that listens to this channel.
     pub fn subscribe(&mut self) -> Receiver<T> {
         Receiver {
@@ -115,7 +205,7 @@ impl<T> fmt::Debug for Sender<T> {
 
 #[cfg(feature = "futures-traits")]
 mod impl_futures {
-    use std::task::Poll;
+    use core::task::Poll;
 
     use crate::sink::SendError;
 
@@ -123,13 +213,13 @@ mod impl_futures {
         type Error = SendError<T>;
 
         fn poll_ready(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
-        fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        fn start_send(self: core::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
             if self.shared.is_closed() {
                 return Err(SendError(item));
             }
@@ -141,15 +231,15 @@ mod impl_futures {
         }
 
         fn poll_flush(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
 
         fn poll_close(
-            self: std::pin::Pin<&mut Self>,
-            _cx: &mut std::task::Context<'_>,
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
         ) -> Poll<Result<(), Self::Error>> {
             Poll::Ready(Ok(()))
         }
@@ -173,7 +263,7 @@ where
     type Item = T;
 
     fn poll_recv(
-        self: std::pin::Pin<&mut Self>,
+        self: core::pin::Pin<&mut Self>,
         cx: &mut crate::Context<'_>,
     ) -> PollRecv<Self::Item> {
         loop {
@@ -182,7 +272,7 @@ where
             match self.try_recv_internal() {
                 TryRecv::Pending => {
                     if self.shared.is_closed() {
-                        return PollRecv::Closed;
+                        return PollRecv::Closed(self.shared.closed_reason());
                     }
 
                     self.shared.subscribe_send(cx);
@@ -199,13 +289,46 @@ where
     }
 }
 
+impl<T> Receiver<T>
+where
+    T: Clone,
+{
+    /// Attempts to receive a message without blocking.  Equivalent to `Stream::try_recv`, but does not require the trait import.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message is ready.
+    /// - `Err(TryRecvError::Pending)` if the channel is open, but no messages are available.
+    /// - `Err(TryRecvError::Closed)` if the channel is closed, and no messages are available.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        Stream::try_recv(self)
+    }
+
+    /// Receives a message, blocking the current thread until a message is available, or the channel is closed.
+    /// Equivalent to `Stream::blocking_recv`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        Stream::blocking_recv(self)
+    }
+
+    /// Converts the receiver into an iterator, which blocks the current thread until each message is received.
+    /// The iterator ends when the channel is closed.  Equivalent to `Stream::into_blocking_iter`.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    pub fn into_blocking_iter(self) -> impl Iterator<Item = T> {
+        Stream::into_blocking_iter(self)
+    }
+}
+
 impl<T> Receiver<T>
 where
     T: Clone,
 {
     fn try_recv_internal(&self) -> TryRecv<T> {
         let state = self.shared.extension();
-        if self.generation.load(std::sync::atomic::Ordering::SeqCst)
+        if self.generation.load(core::sync::atomic::Ordering::SeqCst)
             > state.generation(Ordering::SeqCst)
         {
             return TryRecv::Pending;
@@ -282,12 +405,128 @@ impl<'t, T> Deref for Ref<'t, T> {
 
 impl<T> Receiver<T> {
     /// Borrows the value in the channel, blocking the channel while the value is held.
+    ///
+    /// The value is not marked as observed.  A following `recv` or `changed` call will still complete if the value is new.
     pub fn borrow(&self) -> Ref<'_, T> {
         let lock = self.shared.extension().value.read();
         Ref { lock }
     }
+
+    /// Borrows the value in the channel, and marks it as observed.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let lock = self.shared.extension().value.read();
+        let stored_generation = self.shared.extension().generation(Ordering::SeqCst);
+        self.generation
+            .store(stored_generation + 1, Ordering::Release);
+
+        Ref { lock }
+    }
+
+    /// Returns true if the channel contains a value which has not been observed by this receiver.
+    pub fn has_changed(&self) -> bool {
+        self.generation.load(Ordering::SeqCst)
+            <= self.shared.extension().generation(Ordering::SeqCst)
+    }
+
+    /// Waits until the channel contains a value which has not been observed by this receiver, and marks it as observed.
+    /// The value can then be inspected with `borrow`.
+    ///
+    /// New receivers have not observed the current value, and complete immediately.
+    ///
+    /// Returns `Err(ChangedError)` if the sender has been dropped, and no new value is available.
+    pub async fn changed(&mut self) -> Result<(), ChangedError> {
+        core::future::poll_fn(|cx| self.poll_changed(&mut cx.into())).await
+    }
+
+    fn poll_changed(
+        &self,
+        cx: &mut crate::Context<'_>,
+    ) -> core::task::Poll<Result<(), ChangedError>> {
+        loop {
+            let guard = self.shared.send_guard();
+
+            let stored_generation = self.shared.extension().generation(Ordering::SeqCst);
+            if self.generation.load(Ordering::SeqCst) <= stored_generation {
+                self.generation
+                    .store(stored_generation + 1, Ordering::Release);
+                return core::task::Poll::Ready(Ok(()));
+            }
+
+            if self.shared.is_closed() {
+                return core::task::Poll::Ready(Err(ChangedError));
+            }
+
+            self.shared.subscribe_send(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return core::task::Poll::Pending;
+        }
+    }
+}
+
+impl<T> Receiver<Arc<T>> {
+    /// Converts the receiver into a `RefReceiver`, which produces `Arc<T>` snapshots of the value.
+    pub fn into_ref_receiver(self) -> RefReceiver<T> {
+        RefReceiver { receiver: self }
+    }
+}
+
+/// A watch receiver which produces `Arc<T>` snapshots of the stored value, and does not require `T: Clone`.
+/// Created by `channel_ref` or `Receiver::into_ref_receiver`.
+///
+/// A snapshot does not hold a lock, so the sender can replace the value while snapshots are held.
+pub struct RefReceiver<T> {
+    receiver: Receiver<Arc<T>>,
+}
+
+assert_impl_all!(RefReceiver<SendSyncMessage>: Clone, Send, Sync, fmt::Debug);
+
+impl<T> RefReceiver<T> {
+    /// Borrows the value in the channel, without marking it as observed.  Equivalent to `Receiver::borrow`.
+    pub fn borrow(&self) -> Ref<'_, Arc<T>> {
+        self.receiver.borrow()
+    }
+
+    /// Returns true if the channel contains a value which has not been observed by this receiver.
+    pub fn has_changed(&self) -> bool {
+        self.receiver.has_changed()
+    }
 }
 
+impl<T> Stream for RefReceiver<T> {
+    type Item = Arc<T>;
+
+    fn poll_recv(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut crate::Context<'_>,
+    ) -> PollRecv<Self::Item> {
+        // the receiver reads the generation and the value under the same lock, so a concurrent send is not produced twice
+        core::pin::Pin::new(&mut self.receiver).poll_recv(cx)
+    }
+}
+
+impl<T> Clone for RefReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for RefReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefReceiver").finish()
+    }
+}
+
+/// An error returned by `Receiver::changed`, when the sender has been dropped.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("ChangedError")]
+pub struct ChangedError;
+
 struct StateExtension<T> {
     generation: AtomicUsize,
     value: RwLock<T>,
@@ -309,6 +548,31 @@ impl<T> StateExtension<T> {
         drop(lock);
     }
 
+    pub fn replace(&self, value: T) -> T {
+        let mut lock = self.value.write();
+        let previous = core::mem::replace(&mut *lock, value);
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        drop(lock);
+
+        previous
+    }
+
+    pub fn modify<F>(&self, modify: F) -> bool
+    where
+        F: FnOnce(&mut T) -> bool,
+    {
+        let mut lock = self.value.write();
+        let modified = modify(&mut lock);
+
+        if modified {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+
+        drop(lock);
+        modified
+    }
+
     pub fn increment(&self) {
         self.generation.fetch_add(1, Ordering::SeqCst);
     }
@@ -325,7 +589,7 @@ mod tests {
     use super::channel;
     use crate::{
         sink::{PollSend, Sink},
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         test::{noop_context, panic_context},
     };
     use futures_test::task::new_count_waker;
@@ -433,6 +697,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn send_replace_returns_previous() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert_eq!(State(0), tx.send_replace(State(1)));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(State(1), tx.send_replace(State(2)));
+
+        assert_eq!(
+            PollRecv::Ready(State(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn send_replace_without_receivers() {
+        let (mut tx, rx) = channel();
+        drop(rx);
+
+        assert_eq!(State(0), tx.send_replace(State(1)));
+        assert_eq!(&State(1), &*tx.borrow());
+    }
+
+    #[test]
+    fn borrow_and_update() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        assert!(rx.has_changed());
+        assert_eq!(&State(0), &*rx.borrow_and_update());
+        assert!(!rx.has_changed());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        tx.send_replace(State(1));
+
+        assert!(rx.has_changed());
+        assert_eq!(&State(1), &*rx.borrow());
+        assert!(rx.has_changed());
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert!(!rx.has_changed());
+    }
+
+    #[test]
+    fn poll_changed() {
+        use std::task::Poll;
+
+        let (mut tx, rx) = channel();
+
+        assert_eq!(Poll::Ready(Ok(())), rx.poll_changed(&mut noop_context()));
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, rx.poll_changed(&mut w1_context.into()));
+
+        tx.send_replace(State(1));
+
+        assert_eq!(1, w1_count.get());
+        assert_eq!(Poll::Ready(Ok(())), rx.poll_changed(&mut noop_context()));
+        assert_eq!(&State(1), &*rx.borrow());
+
+        drop(tx);
+
+        assert_eq!(
+            Poll::Ready(Err(super::ChangedError)),
+            rx.poll_changed(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn send_modify_notifies() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        tx.send_modify(|state| state.0 += 1);
+        assert_eq!(1, w1_count.get());
+
+        assert_eq!(
+            PollRecv::Ready(State(1)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn send_if_modified() {
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = channel();
+
+        assert_eq!(
+            PollRecv::Ready(State(0)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut rx).poll_recv(&mut w1_context.into())
+        );
+
+        assert!(!tx.send_if_modified(|_state| false));
+        assert_eq!(0, w1_count.get());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert!(tx.send_if_modified(|state| {
+            state.0 = 2;
+            true
+        }));
+        assert_eq!(1, w1_count.get());
+        assert_eq!(
+            PollRecv::Ready(State(2)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn try_send_try_recv() {
+        use crate::{sink::TrySendError, stream::TryRecvError};
+
+        let (mut tx, mut rx) = channel();
+
+        assert_eq!(Ok(State(0)), rx.try_recv());
+        assert_eq!(Err(TryRecvError::Pending), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(State(1)));
+        assert_eq!(Ok(State(1)), rx.try_recv());
+
+        drop(rx);
+        assert_eq!(Err(TrySendError::Rejected(State(2))), tx.try_send(State(2)));
+    }
+
     #[test]
     fn sender_disconnect() {
         let mut cx = noop_context();
@@ -451,14 +872,20 @@ mod tests {
             Pin::new(&mut rx).poll_recv(&mut cx)
         );
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
 
         assert_eq!(
             PollRecv::Ready(State(1)),
             Pin::new(&mut rx2).poll_recv(&mut cx)
         );
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut rx2).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx2).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -589,6 +1016,109 @@ mod tests {
             Pin::new(&mut rx2).poll_recv(&mut noop_context())
         );
     }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Table(Vec<usize>);
+
+    #[test]
+    fn channel_ref_recv() {
+        use std::sync::Arc;
+
+        let mut cx = noop_context();
+        let (mut tx, mut rx) = super::channel_ref(Table(vec![1]));
+
+        let first = match Pin::new(&mut rx).poll_recv(&mut cx) {
+            PollRecv::Ready(table) => table,
+            poll => panic!("unexpected poll: {:?}", poll),
+        };
+        assert_eq!(&Table(vec![1]), &*first);
+        assert!(matches!(
+            Pin::new(&mut rx).poll_recv(&mut cx),
+            PollRecv::Pending
+        ));
+
+        // the snapshot does not hold a lock, so the sender can replace the value
+        assert_eq!(Ok(()), tx.try_send(Arc::new(Table(vec![1, 2]))));
+        assert!(rx.has_changed());
+
+        match Pin::new(&mut rx).poll_recv(&mut cx) {
+            PollRecv::Ready(table) => assert_eq!(&Table(vec![1, 2]), &*table),
+            poll => panic!("unexpected poll: {:?}", poll),
+        }
+        assert!(!rx.has_changed());
+        assert_eq!(&Table(vec![1]), &*first);
+    }
+
+    #[test]
+    fn channel_ref_closed() {
+        let mut cx = noop_context();
+        let (tx, mut rx) = super::channel_ref(Table(vec![]));
+        assert_eq!(&Table(vec![]), &**rx.borrow());
+
+        let snapshot = match Pin::new(&mut rx).poll_recv(&mut cx) {
+            PollRecv::Ready(table) => table,
+            poll => panic!("unexpected poll: {:?}", poll),
+        };
+
+        // the snapshot keeps the value alive after the sender is dropped
+        drop(tx);
+        assert_eq!(&Table(vec![]), &*snapshot);
+
+        assert!(matches!(
+            Pin::new(&mut rx).poll_recv(&mut cx),
+            PollRecv::Closed(ClosedReason::SendersDropped)
+        ));
+    }
+
+    #[test]
+    fn channel_ref_concurrent_send() {
+        use crate::stream::TryRecvError;
+        use std::sync::Arc;
+
+        // a value sent while the receiver polls must not be produced twice
+        let (mut tx, mut rx) = super::channel_ref(Table(vec![0]));
+
+        let sender = std::thread::spawn(move || {
+            for i in 1..=1000 {
+                assert_eq!(Ok(()), tx.try_send(Arc::new(Table(vec![i]))));
+            }
+        });
+
+        let mut last = None;
+        loop {
+            match rx.try_recv() {
+                Ok(table) => {
+                    let value = table.0[0];
+                    assert!(last < Some(value), "{:?} followed {:?}", value, last);
+                    last = Some(value);
+                }
+                Err(TryRecvError::Pending) => std::thread::yield_now(),
+                Err(TryRecvError::Closed) => break,
+            }
+        }
+
+        sender.join().expect("join failed");
+        assert_eq!(Some(1000), last);
+    }
+
+    #[test]
+    fn into_ref_receiver() {
+        use std::sync::Arc;
+
+        let mut cx = noop_context();
+        let (mut tx, rx) = super::channel_with(Arc::new(State(0)));
+        let mut rx = rx.into_ref_receiver();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, Arc::new(State(1)))
+        );
+
+        match Pin::new(&mut rx).poll_recv(&mut cx) {
+            PollRecv::Ready(state) => assert_eq!(&State(1), &*state),
+            poll => panic!("unexpected poll: {:?}", poll),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -624,6 +1154,31 @@ mod tokio_tests {
         .expect("test timeout");
     }
 
+    #[tokio::test]
+    async fn changed() {
+        let (mut tx, mut rx) = super::channel::<usize>();
+
+        tokio::task::spawn(async move {
+            for i in 1..=100 {
+                tx.send(i).await.expect("send failed");
+                tokio::task::yield_now().await;
+            }
+        });
+
+        timeout(TEST_TIMEOUT, async move {
+            let mut last = 0;
+            while rx.changed().await.is_ok() {
+                let value = *rx.borrow();
+                assert!(value >= last);
+                last = value;
+            }
+
+            assert_eq!(100, last);
+        })
+        .await
+        .expect("test timeout");
+    }
+
     #[tokio::test]
     async fn send_borrow_mut() {
         let (mut tx, mut rx) = super::channel();
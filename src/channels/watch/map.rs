@@ -0,0 +1,706 @@
+//! A keyed watch map.  Writers insert and remove values by key, and readers watch individual keys, or all changes.
+//!
+//! Like the watch channel, readers observe the latest value of a key, and are not guaranteed to observe every value.
+//! Each key is notified independently, so a `KeyReceiver` is only woken when its own key changes.
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    fmt,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use static_assertions::assert_impl_all;
+
+use crate::{
+    channels::SendSyncMessage,
+    stream::{ClosedReason, PollRecv, Stream},
+    sync::{
+        lock::{Mutex, RwLock},
+        notifier::Notifier,
+    },
+    Context,
+};
+
+/// Constructs a new, empty watch map, and a receiver which observes every change to the map.
+///
+/// ```rust
+/// use postage::prelude::*;
+/// use postage::watch;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut map, mut updates) = watch::map();
+///     let mut device = map.watch("mouse");
+///
+///     assert_eq!(Some(None), device.recv().await);
+///
+///     map.insert("mouse", 800);
+///     assert_eq!(Some(Some(800)), device.recv().await);
+///     assert_eq!(Some(("mouse", Some(800))), updates.recv().await);
+/// }
+/// ```
+pub fn map<K, V>() -> (WatchMap<K, V>, MapReceiver<K, V>)
+where
+    K: Ord + Clone,
+{
+    let map = WatchMap {
+        writer: Arc::new(Writer {
+            state: Arc::new(MapState {
+                entries: RwLock::new(BTreeMap::new()),
+                subscribers: Mutex::new(Vec::new()),
+                closed: AtomicBool::new(false),
+            }),
+        }),
+    };
+
+    let receiver = map.subscribe();
+
+    (map, receiver)
+}
+
+/// The writer half of a watch map.  Values are inserted and removed by key, and watchers of the key are notified.
+///
+/// Can be cloned.  When every clone has been dropped, receivers observe the map as closed.
+pub struct WatchMap<K, V> {
+    writer: Arc<Writer<K, V>>,
+}
+
+assert_impl_all!(WatchMap<usize, SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+
+impl<K, V> WatchMap<K, V>
+where
+    K: Ord + Clone,
+{
+    /// Stores the value under the given key, returning the previous value.  Watchers of the key are notified.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.state().store(key, Some(value))
+    }
+
+    /// Removes the value stored under the given key, returning it.  Watchers of the key observe `None`.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if !self.contains_key(key) {
+            return None;
+        }
+
+        self.state().store(key.clone(), None)
+    }
+
+    /// Returns a clone of the value stored under the given key.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.state().get(key)
+    }
+
+    /// Returns true if a value is stored under the given key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.state().contains_key(key)
+    }
+
+    /// The number of values stored in the map.
+    pub fn len(&self) -> usize {
+        self.state().len()
+    }
+
+    /// Returns true if no values are stored in the map.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a receiver which observes the value of a single key.
+    ///
+    /// The receiver immediately observes the current value, and `None` if the key is not present.
+    pub fn watch(&self, key: K) -> KeyReceiver<K, V> {
+        KeyReceiver::new(self.state().clone(), key)
+    }
+
+    /// Creates a receiver which observes every change to the map.  The receiver first observes each stored entry.
+    pub fn subscribe(&self) -> MapReceiver<K, V> {
+        MapReceiver::new(self.state().clone())
+    }
+
+    fn state(&self) -> &Arc<MapState<K, V>> {
+        &self.writer.state
+    }
+}
+
+impl<K, V> Clone for WatchMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            writer: self.writer.clone(),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for WatchMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchMap").finish()
+    }
+}
+
+/// A receiver which observes every change to a watch map.  Each item contains a key, and the latest value of the key.
+///
+/// Changes are coalesced per key, so a key which changed several times between receives is observed once, with the latest value.
+/// A value of `None` indicates that the key was removed.
+pub struct MapReceiver<K, V> {
+    state: Arc<MapState<K, V>>,
+    updates: Arc<Updates<K>>,
+}
+
+assert_impl_all!(MapReceiver<usize, SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+
+impl<K, V> MapReceiver<K, V>
+where
+    K: Ord + Clone,
+{
+    fn new(state: Arc<MapState<K, V>>) -> Self {
+        let updates = Arc::new(Updates {
+            changed: Mutex::new(BTreeSet::new()),
+            notify: Notifier::new(),
+        });
+
+        // holding the entries lock prevents writers from storing a change which neither the seed nor the subscription observes
+        let entries = state.entries.read();
+        updates.changed.lock().extend(
+            entries
+                .iter()
+                .filter(|(_, entry)| entry.value.read().is_some())
+                .map(|(key, _)| key.clone()),
+        );
+        state.subscribers.lock().push(Arc::downgrade(&updates));
+        drop(entries);
+
+        Self { state, updates }
+    }
+
+    /// Creates a receiver which observes the value of a single key.
+    pub fn watch(&self, key: K) -> KeyReceiver<K, V> {
+        KeyReceiver::new(self.state.clone(), key)
+    }
+
+    /// Returns a clone of the value stored under the given key.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.state.get(key)
+    }
+}
+
+impl<K, V> Stream for MapReceiver<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Item = (K, Option<V>);
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        loop {
+            let guard = self.updates.notify.guard();
+
+            let changed = self.updates.changed.lock().pop_first();
+            if let Some(key) = changed {
+                let value = self.state.get(&key);
+                return PollRecv::Ready((key, value));
+            }
+
+            if self.state.closed.load(Ordering::SeqCst) {
+                return PollRecv::Closed(ClosedReason::SendersDropped);
+            }
+
+            self.updates.notify.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<K, V> Clone for MapReceiver<K, V>
+where
+    K: Ord + Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.state.clone())
+    }
+}
+
+impl<K, V> fmt::Debug for MapReceiver<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapReceiver").finish()
+    }
+}
+
+/// A receiver which observes the value of a single key in a watch map.  A value of `None` indicates that the key is not present.
+///
+/// The receiver is only woken when its key changes, and is not guaranteed to receive every value.
+pub struct KeyReceiver<K, V>
+where
+    K: Ord,
+{
+    state: Arc<MapState<K, V>>,
+    key: K,
+    entry: Arc<Entry<V>>,
+    generation: AtomicUsize,
+}
+
+assert_impl_all!(KeyReceiver<usize, SendSyncMessage>: Send, Sync, Clone, fmt::Debug);
+
+impl<K, V> KeyReceiver<K, V>
+where
+    K: Ord + Clone,
+{
+    fn new(state: Arc<MapState<K, V>>, key: K) -> Self {
+        let entry = state
+            .entries
+            .write()
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(Entry::new(None)))
+            .clone();
+
+        Self {
+            state,
+            key,
+            entry,
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    /// The key observed by this receiver.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a clone of the current value, without marking it as observed.
+    pub fn get(&self) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.entry.value.read().clone()
+    }
+
+    /// Returns true if the key has a value which has not been observed by this receiver.
+    pub fn has_changed(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) <= self.entry.generation.load(Ordering::SeqCst)
+    }
+}
+
+impl<K, V> Stream for KeyReceiver<K, V>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    type Item = Option<V>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        loop {
+            let guard = self.entry.notify.guard();
+
+            if self.has_changed() {
+                // writers increment the generation while holding the value lock
+                let value = self.entry.value.read();
+                let stored_generation = self.entry.generation.load(Ordering::SeqCst);
+                self.generation
+                    .store(stored_generation + 1, Ordering::Release);
+
+                return PollRecv::Ready(value.clone());
+            }
+
+            if self.state.closed.load(Ordering::SeqCst) {
+                return PollRecv::Closed(ClosedReason::SendersDropped);
+            }
+
+            self.entry.notify.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return PollRecv::Pending;
+        }
+    }
+}
+
+impl<K, V> Clone for KeyReceiver<K, V>
+where
+    K: Ord + Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.state.clone(), self.key.clone())
+    }
+}
+
+impl<K, V> fmt::Debug for KeyReceiver<K, V>
+where
+    K: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyReceiver")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl<K, V> Drop for KeyReceiver<K, V>
+where
+    K: Ord,
+{
+    fn drop(&mut self) {
+        // entries of removed keys are retained while they are watched.  the last watcher removes the entry
+        let mut entries = self.state.entries.write();
+        let watched = Arc::strong_count(&self.entry) > 2;
+        if !watched && self.entry.value.read().is_none() {
+            entries.remove(&self.key);
+        }
+    }
+}
+
+struct Writer<K, V> {
+    state: Arc<MapState<K, V>>,
+}
+
+impl<K, V> Drop for Writer<K, V> {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::SeqCst);
+
+        for entry in self.state.entries.read().values() {
+            entry.notify.notify();
+        }
+
+        for updates in self.state.subscribers.lock().iter() {
+            if let Some(updates) = updates.upgrade() {
+                updates.notify.notify();
+            }
+        }
+    }
+}
+
+// lock order: entries, then subscribers, then the changed set of a subscriber
+struct MapState<K, V> {
+    entries: RwLock<BTreeMap<K, Arc<Entry<V>>>>,
+    subscribers: Mutex<Vec<Weak<Updates<K>>>>,
+    closed: AtomicBool,
+}
+
+impl<K, V> MapState<K, V>
+where
+    K: Ord + Clone,
+{
+    fn store(&self, key: K, value: Option<V>) -> Option<V> {
+        let mut entries = self.entries.write();
+        let entry = entries
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(Entry::new(None)));
+
+        let mut lock = entry.value.write();
+        let previous = core::mem::replace(&mut *lock, value);
+        entry.generation.fetch_add(1, Ordering::SeqCst);
+        let removed = lock.is_none();
+        drop(lock);
+
+        entry.notify.notify();
+
+        // unwatched entries are dropped when the key is removed
+        if removed && Arc::strong_count(entry) == 1 {
+            entries.remove(&key);
+        }
+
+        self.subscribers
+            .lock()
+            .retain(|updates| match updates.upgrade() {
+                Some(updates) => {
+                    updates.changed.lock().insert(key.clone());
+                    updates.notify.notify();
+                    true
+                }
+                None => false,
+            });
+
+        previous
+    }
+
+    fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.entries
+            .read()
+            .get(key)
+            .and_then(|entry| entry.value.read().clone())
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.entries
+            .read()
+            .get(key)
+            .map(|entry| entry.value.read().is_some())
+            .unwrap_or(false)
+    }
+
+    fn len(&self) -> usize {
+        self.entries
+            .read()
+            .values()
+            .filter(|entry| entry.value.read().is_some())
+            .count()
+    }
+}
+
+struct Entry<V> {
+    value: RwLock<Option<V>>,
+    generation: AtomicUsize,
+    notify: Notifier,
+}
+
+impl<V> Entry<V> {
+    fn new(value: Option<V>) -> Self {
+        Self {
+            value: RwLock::new(value),
+            generation: AtomicUsize::new(0),
+            notify: Notifier::new(),
+        }
+    }
+}
+
+struct Updates<K> {
+    changed: Mutex<BTreeSet<K>>,
+    notify: Notifier,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Context};
+
+    use futures_test::task::new_count_waker;
+
+    use super::map;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        test::noop_context,
+    };
+
+    #[test]
+    fn insert_get_remove() {
+        let (mut map, _rx) = map();
+
+        assert_eq!(None, map.insert(1, "a"));
+        assert_eq!(Some("a"), map.insert(1, "b"));
+        assert_eq!(None, map.insert(2, "c"));
+
+        assert_eq!(Some("b"), map.get(&1));
+        assert!(map.contains_key(&2));
+        assert_eq!(2, map.len());
+
+        assert_eq!(Some("b"), map.remove(&1));
+        assert_eq!(None, map.remove(&1));
+        assert_eq!(None, map.get(&1));
+        assert_eq!(1, map.len());
+    }
+
+    #[test]
+    fn watch_observes_current() {
+        let mut cx = noop_context();
+        let (mut map, _rx) = map();
+        map.insert(1, 10);
+
+        let mut present = map.watch(1);
+        let mut absent = map.watch(2);
+
+        assert_eq!(
+            PollRecv::Ready(Some(10)),
+            Pin::new(&mut present).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut present).poll_recv(&mut cx));
+
+        assert_eq!(
+            PollRecv::Ready(None),
+            Pin::new(&mut absent).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut absent).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn watch_observes_latest() {
+        let mut cx = noop_context();
+        let (mut map, _rx) = map();
+        let mut key = map.watch(1);
+
+        assert_eq!(PollRecv::Ready(None), Pin::new(&mut key).poll_recv(&mut cx));
+
+        map.insert(1, 10);
+        map.insert(1, 20);
+        assert!(key.has_changed());
+        assert_eq!(
+            PollRecv::Ready(Some(20)),
+            Pin::new(&mut key).poll_recv(&mut cx)
+        );
+
+        map.remove(&1);
+        assert_eq!(PollRecv::Ready(None), Pin::new(&mut key).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut key).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn watch_wakes_on_key() {
+        let (mut map, _rx) = map();
+        let mut key = map.watch(1);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker).into();
+        assert_eq!(PollRecv::Ready(None), Pin::new(&mut key).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut key).poll_recv(&mut cx));
+
+        map.insert(2, 20);
+        assert_eq!(0, count.get());
+
+        map.insert(1, 10);
+        assert_eq!(1, count.get());
+        assert_eq!(
+            PollRecv::Ready(Some(10)),
+            Pin::new(&mut key).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn updates_coalesce() {
+        let mut cx = noop_context();
+        let (mut map, mut rx) = map();
+
+        map.insert(2, 20);
+        map.insert(1, 10);
+        map.insert(1, 11);
+        map.remove(&2);
+
+        assert_eq!(
+            PollRecv::Ready((1, Some(11))),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready((2, None)),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn subscribe_observes_entries() {
+        let mut cx = noop_context();
+        let (mut map, _rx) = map();
+        map.insert(1, 10);
+        map.insert(2, 20);
+        map.remove(&2);
+
+        let mut rx = map.subscribe();
+        assert_eq!(
+            PollRecv::Ready((1, Some(10))),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn writer_disconnect() {
+        let mut cx = noop_context();
+        let (mut map, mut rx) = map();
+        let mut key = map.watch(1);
+        let clone = map.clone();
+
+        map.insert(1, 10);
+        drop(map);
+        assert_eq!(
+            PollRecv::Ready((1, Some(10))),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(clone);
+        assert_eq!(
+            PollRecv::Ready(Some(10)),
+            Pin::new(&mut key).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut key).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut rx).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn writer_disconnect_wakes() {
+        let (map, mut rx) = map::<usize, usize>();
+        let mut key = map.watch(1);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker).into();
+        assert_eq!(PollRecv::Ready(None), Pin::new(&mut key).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut key).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(map);
+        assert_eq!(2, count.get());
+    }
+
+    #[test]
+    fn removed_entries_released() {
+        let (mut map, _rx) = map();
+
+        let key = map.watch(1);
+        map.insert(1, 10);
+        map.remove(&1);
+        assert_eq!(1, map.state().entries.read().len());
+
+        drop(key);
+        assert!(map.state().entries.read().is_empty());
+
+        map.insert(2, 20);
+        map.remove(&2);
+        assert!(map.state().entries.read().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::{task::spawn, time::timeout};
+
+    use crate::{
+        stream::Stream,
+        test::{CHANNEL_TEST_ITERATIONS, TEST_TIMEOUT},
+    };
+
+    #[tokio::test]
+    async fn watch_converges() {
+        let (mut map, _rx) = super::map();
+        let mut key = map.watch(0usize);
+
+        let receiver = spawn(async move {
+            let mut latest = None;
+            while let Some(value) = key.recv().await {
+                latest = value;
+            }
+
+            latest
+        });
+
+        for i in 0..CHANNEL_TEST_ITERATIONS {
+            map.insert(i % 4, i);
+        }
+        drop(map);
+
+        let latest = timeout(TEST_TIMEOUT, receiver)
+            .await
+            .expect("test timeout")
+            .expect("receiver panicked");
+        assert_eq!(Some(CHANNEL_TEST_ITERATIONS - 4), latest);
+    }
+}
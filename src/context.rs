@@ -1,4 +1,4 @@
-use std::task::Waker;
+use core::task::{RawWaker, RawWakerVTable, Waker};
 /// The `Context` of an asynchronous task.
 ///
 /// Unlike std::task::Context, this context *optionally* contains a waker.
@@ -9,20 +9,20 @@ pub struct Context<'a> {
     waker: Option<&'a Waker>,
 }
 
-impl<'a> From<std::task::Context<'a>> for Context<'a> {
-    fn from(cx: std::task::Context<'a>) -> Self {
+impl<'a> From<core::task::Context<'a>> for Context<'a> {
+    fn from(cx: core::task::Context<'a>) -> Self {
         Self::from_waker(cx.waker())
     }
 }
 
-impl<'a> From<&std::task::Context<'a>> for Context<'a> {
-    fn from(cx: &std::task::Context<'a>) -> Self {
+impl<'a> From<&core::task::Context<'a>> for Context<'a> {
+    fn from(cx: &core::task::Context<'a>) -> Self {
         Self::from_waker(cx.waker())
     }
 }
 
-impl<'a> From<&mut std::task::Context<'a>> for Context<'a> {
-    fn from(cx: &mut std::task::Context<'a>) -> Self {
+impl<'a> From<&mut core::task::Context<'a>> for Context<'a> {
+    fn from(cx: &mut core::task::Context<'a>) -> Self {
         Self::from_waker(cx.waker())
     }
 }
@@ -44,8 +44,22 @@ impl<'a> Context<'a> {
     }
 }
 
-impl std::fmt::Debug for Context<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// A waker which does nothing when it is woken.  Inner futures are polled with it when the postage context has no waker.
+pub(crate) fn noop_waker() -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+
+    fn noop(_: *const ()) {}
+
+    // the vtable functions never read the data pointer
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
+
+impl core::fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Context")
             .field("waker", &self.waker)
             .finish()
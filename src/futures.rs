@@ -1,4 +1,4 @@
-use std::task::Poll;
+use core::task::Poll;
 
 macro_rules! poll {
     ($self:ident, $cx:ident) => {{
@@ -9,7 +9,7 @@ macro_rules! poll {
         return match $self.poll_recv(&mut cx) {
             crate::stream::PollRecv::Ready(v) => Poll::Ready(Some(v)),
             crate::stream::PollRecv::Pending => Poll::Pending,
-            crate::stream::PollRecv::Closed => Poll::Ready(None),
+            crate::stream::PollRecv::Closed(_) => Poll::Ready(None),
         };
     }};
 }
@@ -18,8 +18,8 @@ impl futures::stream::Stream for crate::barrier::Receiver {
     type Item = ();
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
@@ -29,8 +29,8 @@ impl<T: Clone> futures::stream::Stream for crate::broadcast::Receiver<T> {
     type Item = T;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
@@ -40,8 +40,8 @@ impl<T> futures::stream::Stream for crate::dispatch::Receiver<T> {
     type Item = T;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
@@ -51,8 +51,19 @@ impl<T> futures::stream::Stream for crate::mpsc::Receiver<T> {
     type Item = T;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        poll!(self, cx)
+    }
+}
+
+impl<T> futures::stream::Stream for crate::mpsc::UnboundedReceiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
@@ -62,8 +73,8 @@ impl<T> futures::stream::Stream for crate::oneshot::Receiver<T> {
     type Item = T;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
@@ -73,13 +84,40 @@ impl<T: Clone> futures::stream::Stream for crate::watch::Receiver<T> {
     type Item = T;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         poll!(self, cx)
     }
 }
 
+impl<'s, S> futures::future::FusedFuture for crate::stream::RecvFuture<'s, S>
+where
+    S: crate::stream::Stream + Unpin + ?Sized,
+{
+    fn is_terminated(&self) -> bool {
+        crate::stream::RecvFuture::is_terminated(self)
+    }
+}
+
+impl<S> futures::future::FusedFuture for crate::stream::RecvOwnedFuture<S>
+where
+    S: crate::stream::Stream + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        crate::stream::RecvOwnedFuture::is_terminated(self)
+    }
+}
+
+impl<'s, S> futures::future::FusedFuture for crate::sink::SendFuture<'s, S>
+where
+    S: crate::sink::Sink + Unpin + ?Sized,
+{
+    fn is_terminated(&self) -> bool {
+        crate::sink::SendFuture::is_terminated(self)
+    }
+}
+
 #[cfg(test)]
 mod sink_tests {
     use std::{pin::Pin, task::Poll};
@@ -164,6 +202,11 @@ mod sink_tests {
         test_sink!(mpsc::channel(1), 1usize);
     }
 
+    #[test]
+    fn mpsc_unbounded() {
+        test_sink_ready!(mpsc::unbounded(), 1usize);
+    }
+
     #[test]
     fn oneshot() {
         let mut std_cx = futures_test::task::noop_context();
@@ -267,6 +310,11 @@ mod stream_tests {
         test_stream!(mpsc::channel(4), 1usize);
     }
 
+    #[test]
+    fn mpsc_unbounded() {
+        test_stream!(mpsc::unbounded(), 1usize);
+    }
+
     #[test]
     fn oneshot() {
         test_stream!(oneshot::channel(), 1usize);
@@ -298,3 +346,37 @@ mod stream_tests {
         assert_eq!(Poll::Ready(None), Pin::new(&mut rx).poll_next(&mut std_cx));
     }
 }
+
+#[cfg(test)]
+mod fused_tests {
+    use std::{future::Future, pin::Pin, task::Poll};
+
+    use crate::{mpsc, sink::Sink, stream::Stream};
+    use futures::future::FusedFuture;
+
+    #[test]
+    fn recv_future() {
+        let mut std_cx = futures_test::task::noop_context();
+
+        let (mut tx, mut rx) = mpsc::channel(4);
+        let mut recv = rx.recv();
+        assert!(!FusedFuture::is_terminated(&recv));
+        assert_eq!(Poll::Pending, Pin::new(&mut recv).poll(&mut std_cx));
+        assert!(!FusedFuture::is_terminated(&recv));
+
+        tx.try_send(1usize).unwrap();
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut recv).poll(&mut std_cx));
+        assert!(FusedFuture::is_terminated(&recv));
+    }
+
+    #[test]
+    fn send_future() {
+        let mut std_cx = futures_test::task::noop_context();
+
+        let (mut tx, _rx) = mpsc::channel(4);
+        let mut send = tx.send(1usize);
+        assert!(!FusedFuture::is_terminated(&send));
+        assert_eq!(Poll::Ready(Ok(())), Pin::new(&mut send).poll(&mut std_cx));
+        assert!(FusedFuture::is_terminated(&send));
+    }
+}
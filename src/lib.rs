@@ -3,8 +3,11 @@
 //! # Why use Postage?
 //! - Includes a **rich set of channels.**
 //!   - [barrier](./barrier/index.html), a oneshot channel that transmits when the sender half is dropped.
-//!   - [broadcast](./broadcast/index.html), a lossless multi-producer, multi-consumer broadcast channel with backpressure (no lagging!).
+//!   - [broadcast](./broadcast/index.html), a multi-producer, multi-consumer broadcast channel.  By default it is lossless, with backpressure.
+//!     With `OverflowPolicy::DropOldest`, senders never wait, and slow receivers lag and skip the oldest messages.
 //!   - [dispatch](./dispatch/index.html), a multi-producer, multi-consumer queue.
+//!   - [history](./history/index.html), a state distribution channel which retains the latest values, and never blocks senders.
+//!   - [mpmc](./mpmc/index.html), a multi-producer, multi-consumer work queue with fair wakeups, backed by dispatch.
 //!   - [mpsc](./mpsc/index.html), a multi-producer, single-consumer channel.
 //!   - [oneshot](./oneshot/index.html), a oneshot transfer channel.
 //!   - [watch](./watch/index.html), a state distribution channel with a value that can be borrowed.
@@ -16,23 +19,51 @@
 //! - Comes with **built-in [Sink](./sink/trait.Sink.html) and [Stream](./stream/trait.Stream.html) combinators.**
 //!   - Sinks can be chained, and filtered.
 //!   - Streams can be chained, filtered, mapped, and merged.
+//!   - [select::Biased](./select/struct.Biased.html) checks streams in declaration order, so a shutdown branch always wins over a data branch.
 //!   - With the `logging` feature, Sinks and streams can log their values.  This is really helpful when debugging applications.
+//! - Exports [sync::ref_count](./sync/ref_count/index.html), an atomic counter which can be awaited until it reaches zero.
 //!
 //! See [the readme](https://github.com/austinjones/postage-rs#benchmarks) for benchmarks.
 //!
 //! ## Cargo features:
-//! - `blocking (default)` - enables [Sink::blocking_send](./sink/trait.Sink.html#method.blocking_send) and [Stream::blocking_recv](./stream/trait.Stream.html#method.blocking_recv)
-//! - `debug` - enables _extremely verbose_ internal log statements.
-//! - `futures-traits` - enables `futures::Sink` and `futures::Stream` implementations for the postage channels.  Compatible with `v0.3`.
+//! - `blocking (default)` - enables [Sink::blocking_send](./sink/trait.Sink.html#method.blocking_send), [Stream::blocking_recv](./stream/trait.Stream.html#method.blocking_recv), [Stream::into_blocking_iter](./stream/trait.Stream.html#method.into_blocking_iter), and the std [bridge](./bridge/index.html) adapters.
+//! - `checksum` - stamps each slot of the [broadcast](./broadcast/index.html) buffer with a checksum, which is validated when the slot is read or overwritten.  A mismatch panics, catching use-after-release and double-write bugs in the buffer.  Other channels are not checked.  Intended for development.
+//! - `crossbeam` - enables [bridge](./bridge/index.html) adapters for crossbeam channels.
+//! - `debug` - enables _extremely verbose_ internal log statements, and `waiters()` on the mpsc, dispatch, and broadcast handles, which reports the number of parked senders and receivers.
+//! - `diagnostics` - enables `diagnostics()` on the mpsc, dispatch, and broadcast handles, which returns a [ChannelDiagnostics](./sync/struct.ChannelDiagnostics.html) snapshot of parked tasks, the broadcast slot which blocks writers, and the time since the last write.  Useful for watchdogs.
+//! - `futures-traits` - enables `futures::Sink` and `futures::Stream` implementations for the postage channels, and the [Sink::into_futures_sink](./sink/trait.Sink.html#method.into_futures_sink) and [Stream::into_futures_stream](./stream/trait.Stream.html#method.into_futures_stream) adapters for any sink or stream.  Compatible with `v0.3`.
 //! - `logging (default)` - enables the enables [Sink::log(Level)](./sink/trait.Sink.html#method.log) and [Stream::log(Level)](./stream/trait.Stream.html#method.log) combinators.
+//! - `metrics` - enables the [metrics](./metrics/index.html) module, and `channel_named` constructors for mpsc, dispatch, and broadcast channels.  Named channels report send, receive, blocked-send, and dropped-receiver events to a global [ChannelMetrics](./metrics/trait.ChannelMetrics.html) recorder.
+//! - `test-util` - enables the [test](./test/index.html) module, with `PollCounter`, `noop_context()`, and the `assert_ready!`, `assert_pending!`, and `assert_closed!` macros, for unit testing channel-driven code without an async runtime.
+//! - `thread-priority` - enables [Sink::blocking_send_with](./sink/trait.Sink.html#method.blocking_send_with) and [Stream::blocking_recv_with](./stream/trait.Stream.html#method.blocking_recv_with), which can raise the priority of the parked thread on Windows and macOS.
+//! - `timer` - enables [dispatch::Sender::send_after](./dispatch/struct.Sender.html#method.send_after), for scheduled message delivery, the [Stream::min_batch_latency](./stream/trait.Stream.html#method.min_batch_latency) and [Stream::rate_limit](./stream/trait.Stream.html#method.rate_limit) combinators, and [time::ThreadTimer](./time/struct.ThreadTimer.html).
+//! - `tokio-timer` - enables [time::TokioTimer](./time/struct.TokioTimer.html), for use with [Sink::send_timeout](./sink/trait.Sink.html#method.send_timeout) and [Stream::recv_timeout](./stream/trait.Stream.html#method.recv_timeout).
+//! - `async-std-timer` - enables [time::AsyncStdTimer](./time/struct.AsyncStdTimer.html).
+//! - `std (default)` - links the standard library.  Without it, the crate is `#![no_std]`, and requires `alloc`.
+//!   The channels and the executor-independent combinators are available, and features which need threads or clocks
+//!   (such as `blocking` and `timer`) enable `std`.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "blocking")]
+pub mod bridge;
 mod channels;
 mod context;
 mod logging;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod prelude;
+pub mod select;
+#[cfg(feature = "sim")]
+pub mod sim;
 pub mod sink;
 pub mod stream;
-mod sync;
+pub mod sync;
+pub mod time;
 
 #[cfg(feature = "futures-traits")]
 mod futures;
@@ -40,11 +71,15 @@ mod futures;
 pub use channels::barrier;
 pub use channels::broadcast;
 pub use channels::dispatch;
+pub use channels::history;
+pub use channels::mpmc;
 pub use channels::mpsc;
 pub use channels::oneshot;
+pub use channels::priority;
 pub use channels::watch;
 
 pub use context::Context;
+pub use stream::pipe;
 
-#[cfg(test)]
-mod test;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test;
@@ -0,0 +1,204 @@
+//! Channel instrumentation.  Named channels report send, receive, and backpressure events to a global recorder.
+//!
+//! Channels are named with `channel_named`, which is available on the mpsc, dispatch, and broadcast modules.
+//! Unnamed channels are not reported.
+//!
+//! ```rust
+//! use postage::metrics::{self, ChannelMetrics};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! struct SendCounter(AtomicUsize);
+//!
+//! impl ChannelMetrics for SendCounter {
+//!     fn send(&self, channel: &str) {
+//!         if channel == "ingest" {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! metrics::set_recorder(SendCounter(AtomicUsize::new(0))).expect("recorder already installed");
+//!
+//! let (mut tx, _rx) = postage::mpsc::channel_named("ingest", 16);
+//! tx.try_send(1usize).expect("send failed");
+//! ```
+//!
+//! Requires the `metrics` feature.
+
+use std::sync::{Arc, OnceLock};
+
+use thiserror::Error;
+
+/// Receives events from named channels.  Install with `metrics::set_recorder`.
+///
+/// Methods are called synchronously by channel handles, and should be cheap.  Every method has an empty default implementation.
+pub trait ChannelMetrics: Send + Sync {
+    /// A message was accepted by the channel.
+    fn send(&self, _channel: &str) {}
+
+    /// A message was taken from the channel by a receiver.
+    fn recv(&self, _channel: &str) {}
+
+    /// A sender was suspended because the channel is full.
+    fn send_blocked(&self, _channel: &str) {}
+
+    /// A receiver was dropped.
+    fn receiver_dropped(&self, _channel: &str) {}
+}
+
+/// An error returned by `set_recorder`, if a recorder has already been installed.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("a metrics recorder has already been installed")]
+pub struct SetRecorderError;
+
+static RECORDER: OnceLock<Box<dyn ChannelMetrics>> = OnceLock::new();
+
+/// Installs the global recorder.  The recorder can only be installed once.
+pub fn set_recorder<M>(recorder: M) -> Result<(), SetRecorderError>
+where
+    M: ChannelMetrics + 'static,
+{
+    RECORDER
+        .set(Box::new(recorder))
+        .map_err(|_| SetRecorderError)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Event {
+    Send,
+    Recv,
+    SendBlocked,
+    ReceiverDropped,
+}
+
+/// Reports the event to the global recorder, if the channel is named.
+pub(crate) fn record(name: &Option<Arc<str>>, event: Event) {
+    let (name, recorder) = match (name, RECORDER.get()) {
+        (Some(name), Some(recorder)) => (name, recorder),
+        _ => return,
+    };
+
+    match event {
+        Event::Send => recorder.send(name),
+        Event::Recv => recorder.recv(name),
+        Event::SendBlocked => recorder.send_blocked(name),
+        Event::ReceiverDropped => recorder.receiver_dropped(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Mutex, Once, OnceLock},
+    };
+
+    use super::{set_recorder, ChannelMetrics, SetRecorderError};
+
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    struct Counts {
+        send: usize,
+        recv: usize,
+        send_blocked: usize,
+        receiver_dropped: usize,
+    }
+
+    // the recorder is global, so each test uses a unique channel name
+    static COUNTS: OnceLock<Mutex<HashMap<String, Counts>>> = OnceLock::new();
+
+    struct TestRecorder;
+
+    impl TestRecorder {
+        fn update(channel: &str, update: impl FnOnce(&mut Counts)) {
+            let mut counts = COUNTS.get_or_init(Default::default).lock().unwrap();
+            update(counts.entry(channel.to_string()).or_default());
+        }
+    }
+
+    impl ChannelMetrics for TestRecorder {
+        fn send(&self, channel: &str) {
+            Self::update(channel, |counts| counts.send += 1);
+        }
+
+        fn recv(&self, channel: &str) {
+            Self::update(channel, |counts| counts.recv += 1);
+        }
+
+        fn send_blocked(&self, channel: &str) {
+            Self::update(channel, |counts| counts.send_blocked += 1);
+        }
+
+        fn receiver_dropped(&self, channel: &str) {
+            Self::update(channel, |counts| counts.receiver_dropped += 1);
+        }
+    }
+
+    fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| set_recorder(TestRecorder).expect("recorder already installed"));
+    }
+
+    fn counts(channel: &str) -> Counts {
+        let counts = COUNTS.get_or_init(Default::default).lock().unwrap();
+        counts.get(channel).copied().unwrap_or_default()
+    }
+
+    fn expected(send: usize, recv: usize, send_blocked: usize, receiver_dropped: usize) -> Counts {
+        Counts {
+            send,
+            recv,
+            send_blocked,
+            receiver_dropped,
+        }
+    }
+
+    #[test]
+    fn set_recorder_once() {
+        install();
+
+        assert_eq!(Err(SetRecorderError), set_recorder(TestRecorder));
+    }
+
+    #[test]
+    fn mpsc() {
+        install();
+
+        let (mut tx, mut rx) = crate::mpsc::channel_named("mpsc", 1);
+        assert!(tx.try_send(1usize).is_ok());
+        assert!(tx.try_send(2usize).is_err());
+        assert_eq!(Ok(1), rx.try_recv());
+        drop(rx);
+
+        assert_eq!(expected(1, 1, 1, 1), counts("mpsc"));
+    }
+
+    #[test]
+    fn dispatch() {
+        install();
+
+        let (mut tx, mut rx) = crate::dispatch::channel_named("dispatch", 1);
+        assert!(tx.try_send(1usize).is_ok());
+        assert!(tx.try_send(2usize).is_err());
+        assert_eq!(Ok(1), rx.try_recv());
+        drop(rx);
+
+        assert_eq!(expected(1, 1, 1, 1), counts("dispatch"));
+    }
+
+    #[test]
+    fn broadcast() {
+        install();
+
+        let (mut tx, mut rx) = crate::broadcast::channel_named("broadcast", 2);
+        let mut rx2 = rx.clone();
+        assert!(tx.try_send(1usize).is_ok());
+        assert!(tx.try_send(2usize).is_ok());
+        assert!(tx.try_send(3usize).is_err());
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(1), rx2.try_recv());
+        drop(rx);
+        drop(rx2);
+
+        assert_eq!(expected(2, 2, 1, 2), counts("broadcast"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Selects values from multiple streams.
+//!
+//! Fair selection is provided by `Stream::merge` and `stream::select_all`, which rotate between streams so none are starved.
+//!
+//! `Biased` checks its branches in declaration order, like the `biased;` mode of `tokio::select!`.
+//! This is useful when a shutdown branch must deterministically win over a data branch:
+//!
+//! ```rust
+//! use postage::{mpsc, prelude::*, select};
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Event {
+//!     Shutdown,
+//!     Data(usize),
+//! }
+//!
+//! let (mut shutdown_tx, shutdown_rx) = mpsc::channel(1);
+//! let (mut data_tx, data_rx) = mpsc::channel(4);
+//!
+//! data_tx.try_send(1).ok();
+//! shutdown_tx.try_send(()).ok();
+//!
+//! let mut events = select::biased(shutdown_rx.map(|_| Event::Shutdown), data_rx.map(Event::Data));
+//! assert_eq!(Ok(Event::Shutdown), events.try_recv());
+//! assert_eq!(Ok(Event::Data(1)), events.try_recv());
+//! ```
+//!
+//! More than two branches can be selected by nesting: `select::biased(first, select::biased(second, third))`.
+//!
+//! ## Using `tokio::select!` and `futures::select!`
+//!
+//! The futures returned by `Stream::recv` and `Sink::send` are named types (`RecvFuture` and `SendFuture`), and are `Unpin`,
+//! so they can be used as branches without pinning.  With the `futures-traits` feature they implement `FusedFuture`,
+//! as required by `futures::select!`.  Streams which are selected in a loop can be wrapped with `Stream::fuse`,
+//! so a closed branch is not polled again.
+//!
+//! When a select completes, the futures in the other branches are dropped:
+//! - `recv`, `recv_many`, `latest`, and `closed` are cancel-safe.  If they are dropped, no message is lost.
+//!   A dropped receive future calls `Stream::cancel_recv`, so a dispatch receiver gives up its turn to the other receivers.
+//! - `send` is not cancel-safe.  If it is dropped before it completes, the message is dropped.
+//!   To keep the message, use `Sink::try_send` when the branch is selected, or reserve capacity with `mpsc::Sender::reserve`.
+//!
+//! ```rust
+//! use postage::{mpsc, prelude::*};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut data_tx, mut data_rx) = mpsc::channel(4);
+//!     let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+//!
+//!     data_tx.send(1usize).await.ok();
+//!     drop(shutdown_tx);
+//!
+//!     let mut received = Vec::new();
+//!     loop {
+//!         tokio::select! {
+//!             biased;
+//!             Some(value) = data_rx.recv() => received.push(value),
+//!             _ = shutdown_rx.recv() => break,
+//!         }
+//!     }
+//!
+//!     assert_eq!(vec![1], received);
+//! }
+//! ```
+use core::pin::Pin;
+
+use pin_project::pin_project;
+
+use crate::{
+    stream::{PollRecv, Stream},
+    Context,
+};
+
+/// Returns a stream which receives from `first` whenever it is ready, and from `second` only when `first` is not.
+pub fn biased<First, Second>(first: First, second: Second) -> Biased<First, Second>
+where
+    First: Stream,
+    Second: Stream<Item = First::Item>,
+{
+    Biased::new(first, second)
+}
+
+/// A stream which checks its branches in declaration order.  The first branch always takes priority over the second.
+///
+/// The stream is closed once both branches are closed.
+#[pin_project]
+#[derive(Debug)]
+pub struct Biased<First, Second> {
+    #[pin]
+    first: First,
+    #[pin]
+    second: Second,
+}
+
+impl<First, Second> Biased<First, Second>
+where
+    First: Stream,
+    Second: Stream<Item = First::Item>,
+{
+    /// Constructs a stream which prefers `first` over `second`.
+    pub fn new(first: First, second: Second) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<First, Second> Stream for Biased<First, Second>
+where
+    First: Stream,
+    Second: Stream<Item = First::Item>,
+{
+    type Item = First::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        match this.first.poll_recv(cx) {
+            PollRecv::Ready(value) => PollRecv::Ready(value),
+            PollRecv::Pending => match this.second.poll_recv(cx) {
+                PollRecv::Ready(value) => PollRecv::Ready(value),
+                PollRecv::Pending | PollRecv::Closed(_) => PollRecv::Pending,
+            },
+            PollRecv::Closed(_) => this.second.poll_recv(cx),
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.first.cancel_recv();
+        this.second.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::Biased;
+
+    #[test]
+    fn first_wins() {
+        let mut cx = Context::empty();
+        let mut biased = Biased::new(from_iter(vec![1, 2]), from_iter(vec![3, 4]));
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut biased).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn second_when_first_pending() {
+        let mut cx = Context::empty();
+        let first = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1)]);
+        let mut biased = Biased::new(first, from_iter(vec![2, 3]));
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut biased).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut biased).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn pending_until_both_closed() {
+        let mut cx = Context::empty();
+        let mut biased = Biased::new(pending::<usize>(), closed::<usize>());
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut biased).poll_recv(&mut cx));
+
+        let mut biased = Biased::new(closed::<usize>(), pending::<usize>());
+        assert_eq!(PollRecv::Pending, Pin::new(&mut biased).poll_recv(&mut cx));
+    }
+}
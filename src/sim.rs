@@ -0,0 +1,279 @@
+//! Deterministic virtual time, for testing timer-gated behavior without real sleeps.
+//!
+//! While a `Clock` is alive, timers polled on its thread observe virtual time.  This includes `ThreadTimer`
+//! (and `send_timeout` and `recv_timeout` which use it), `Stream::min_batch_latency`, `Stream::rate_limit`, and
+//! delayed dispatch messages.  Virtual time only moves when the test calls `Clock::advance`, and tasks whose
+//! deadlines have passed are woken during the call.
+//!
+//! ```rust
+//! use postage::{sim::Clock, time::{ThreadTimer, Timer}};
+//! use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+//!
+//! let clock = Clock::new();
+//! let mut sleep = ThreadTimer.sleep(Duration::from_secs(60));
+//! let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+//!
+//! assert_eq!(Poll::Pending, Pin::new(&mut sleep).poll(&mut cx));
+//!
+//! clock.advance(Duration::from_secs(60));
+//! assert_eq!(Poll::Ready(()), Pin::new(&mut sleep).poll(&mut cx));
+//! ```
+//!
+//! Requires the `sim` feature.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    task::Waker,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static CLOCK: RefCell<Option<Rc<RefCell<State>>>> = const { RefCell::new(None) };
+}
+
+struct State {
+    start: Instant,
+    elapsed: Duration,
+    entries: Vec<(Instant, Waker)>,
+}
+
+impl State {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed
+    }
+}
+
+/// A virtual clock, which is installed on the current thread until it is dropped.
+///
+/// The clock is not `Send`.  Tasks which use timers must be polled on the thread which created the clock,
+/// for example with a current-thread executor, or by polling manually.
+pub struct Clock {
+    state: Rc<RefCell<State>>,
+}
+
+impl Clock {
+    /// Installs a virtual clock on the current thread.  Virtual time starts at the current instant.
+    ///
+    /// Panics if a clock is already installed on the thread.
+    pub fn new() -> Self {
+        let state = Rc::new(RefCell::new(State {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+            entries: Vec::new(),
+        }));
+
+        CLOCK.with(|clock| {
+            let mut clock = clock.borrow_mut();
+            assert!(
+                clock.is_none(),
+                "a sim::Clock is already installed on this thread"
+            );
+            *clock = Some(state.clone());
+        });
+
+        Self { state }
+    }
+
+    /// The current virtual instant.
+    pub fn now(&self) -> Instant {
+        self.state.borrow().now()
+    }
+
+    /// The amount of virtual time which has elapsed since the clock was installed.
+    pub fn elapsed(&self) -> Duration {
+        self.state.borrow().elapsed
+    }
+
+    /// Moves virtual time forward, and wakes tasks whose deadlines have passed.
+    pub fn advance(&self, duration: Duration) {
+        let expired: Vec<Waker> = {
+            let mut state = self.state.borrow_mut();
+            state.elapsed += duration;
+
+            let now = state.now();
+            let (expired, pending) = std::mem::take(&mut state.entries)
+                .into_iter()
+                .partition(|(deadline, _)| *deadline <= now);
+            state.entries = pending;
+
+            expired.into_iter().map(|(_, waker)| waker).collect()
+        };
+
+        // woken tasks may register new deadlines, so the state is not borrowed
+        expired.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Clock {
+    fn drop(&mut self) {
+        CLOCK.with(|clock| clock.borrow_mut().take());
+    }
+}
+
+impl std::fmt::Debug for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clock")
+            .field("elapsed", &self.elapsed())
+            .finish()
+    }
+}
+
+fn installed() -> Option<Rc<RefCell<State>>> {
+    CLOCK.with(|clock| clock.borrow().clone())
+}
+
+/// The virtual instant, if a clock is installed on the current thread.
+pub(crate) fn now() -> Option<Instant> {
+    installed().map(|state| state.borrow().now())
+}
+
+/// Registers the waker with the current thread's virtual clock.  Returns the waker if no clock is installed.
+pub(crate) fn wake_at(deadline: Instant, waker: Waker) -> Result<(), Waker> {
+    let state = match installed() {
+        Some(state) => state,
+        None => return Err(waker),
+    };
+
+    let mut state = state.borrow_mut();
+    if deadline <= state.now() {
+        drop(state);
+        waker.wake();
+        return Ok(());
+    }
+
+    if let Some(entry) = state
+        .entries
+        .iter_mut()
+        .find(|(_, existing)| existing.will_wake(&waker))
+    {
+        if deadline < entry.0 {
+            entry.0 = deadline;
+        }
+    } else {
+        state.entries.push((deadline, waker));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+    use futures_test::task::new_count_waker;
+
+    use super::Clock;
+    use crate::{
+        stream::{PollRecv, RateLimitConfig, Stream},
+        test::stream::from_iter,
+        time::{ThreadTimer, Timer},
+        watch,
+    };
+
+    #[test]
+    fn sleep() {
+        let clock = Clock::new();
+        let mut sleep = ThreadTimer.sleep(Duration::from_secs(60));
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut sleep).poll(&mut cx));
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(0, count.get());
+        assert_eq!(Poll::Pending, Pin::new(&mut sleep).poll(&mut cx));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(1, count.get());
+        assert_eq!(Poll::Ready(()), Pin::new(&mut sleep).poll(&mut cx));
+    }
+
+    #[test]
+    fn uninstalled_on_drop() {
+        let clock = Clock::new();
+        clock.advance(Duration::from_secs(60));
+        drop(clock);
+
+        assert_eq!(None, super::now());
+
+        let _clock = Clock::new();
+    }
+
+    #[test]
+    #[should_panic]
+    fn installed_once() {
+        let _clock = Clock::new();
+        let _other = Clock::new();
+    }
+
+    #[test]
+    fn rate_limit() {
+        let clock = Clock::new();
+        let config = RateLimitConfig {
+            burst: 1,
+            interval: Duration::from_secs(10),
+        };
+        let (_tx, rx) = watch::channel_with(config);
+        let mut stream = from_iter(vec![1, 2, 3]).rate_limit(rx);
+
+        let (waker, count) = new_count_waker();
+        let std_cx = std::task::Context::from_waker(&waker);
+        let mut cx: crate::Context<'_> = std_cx.into();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(1, count.get());
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn dispatch_send_after() {
+        let clock = Clock::new();
+        let (mut tx, mut rx) = crate::dispatch::channel(4);
+
+        tx.send_after(1usize, Duration::from_secs(5))
+            .expect("send failed");
+
+        let (waker, count) = new_count_waker();
+        let std_cx = std::task::Context::from_waker(&waker);
+        let mut cx: crate::Context<'_> = std_cx.into();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        clock.advance(Duration::from_secs(5));
+        assert!(count.get() >= 1);
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut rx).poll_recv(&mut cx));
+
+        drop(tx);
+    }
+
+    #[test]
+    fn recv_timeout() {
+        let clock = Clock::new();
+        let (mut tx, mut rx) = crate::mpsc::channel::<usize>(4);
+
+        let future = rx.recv_timeout(Duration::from_secs(30), ThreadTimer);
+        futures::pin_mut!(future);
+
+        let (waker, _count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+
+        clock.advance(Duration::from_secs(30));
+        assert!(matches!(future.as_mut().poll(&mut cx), Poll::Ready(Err(_))));
+
+        assert!(tx.try_send(1).is_ok());
+    }
+}
@@ -50,20 +50,41 @@
 //!     tx.send(true).await.ok();
 //! }
 //! ```
-use std::marker::PhantomPinned;
-use std::{future::Future, ops::DerefMut, pin::Pin, task::Poll};
+use core::{future::Future, ops::DerefMut, pin::Pin, task::Poll, time::Duration};
 
-use crate::Context;
+use crate::{
+    stream::{ForwardFuture, Stream},
+    time::Timer,
+    Context,
+};
 use pin_project::pin_project;
 
+mod alternative;
+mod backoff;
+mod buffer;
 mod chain;
+mod dyn_sender;
 mod errors;
+mod fanout;
 mod filter;
+#[cfg(feature = "futures-traits")]
+mod futures_sink;
+mod map_err;
+mod map_version;
+#[cfg(feature = "blocking")]
+mod send_guard;
+mod with;
 
 #[cfg(feature = "logging")]
 mod sink_log;
 
+pub use alternative::{AlternativePolicy, AlternativeSink, FailoverCounter};
+pub use backoff::{BackoffPolicy, BackoffSink, DroppedCounter};
+pub use buffer::{BufferSink, FlushFuture};
+pub use dyn_sender::DynSender;
 pub use errors::*;
+#[cfg(feature = "blocking")]
+pub use send_guard::SendGuard;
 
 /// A sink which can asynchronously accept messages, and at some point may refuse to accept any further messages.
 ///
@@ -138,6 +159,17 @@ pub trait Sink {
         value: Self::Item,
     ) -> PollSend<Self::Item>;
 
+    /// Called when a task stops waiting to send a message, after `poll_send` returned `Pending`.
+    ///
+    /// Channels which serve waiting senders in order (such as mpmc) use it to give up the sender's turn.
+    /// The default implementation does nothing.
+    ///
+    /// The futures returned by `send` and `send_timeout` call it when they are dropped before completing, or time out.
+    /// Combinators forward it to the sinks they wrap.  Code which polls `poll_send` directly, and then stops polling, should call it.
+    /// This includes combinators which return `Ready` or `Pending` without waiting on a sink which returned `Pending`,
+    /// such as `buffer`, `alternative`, `into_sink_with_backoff`, and `Stream::fanout_ordered`.
+    fn cancel_send(self: Pin<&mut Self>) {}
+
     /// Attempts to send a message into the sink.  
     ///
     /// Returns:
@@ -147,6 +179,40 @@ pub trait Sink {
         SendFuture::new(self, value)
     }
 
+    /// Sends every message from the stream, until the stream is closed.
+    ///
+    /// Messages which are ready are sent within a single poll, so the task is not rescheduled for each message.
+    /// Each message is sent with `poll_send`, so the receiver may still be notified once per message.
+    ///
+    /// Returns:
+    /// - `Ok(())` once the stream is closed.
+    /// - `Err(SendError(value))` if the sink rejected a message.
+    fn send_all<'s, S>(&'s mut self, stream: &'s mut S) -> ForwardFuture<&'s mut S, &'s mut Self>
+    where
+        S: Stream<Item = Self::Item> + Unpin,
+        Self: Unpin,
+    {
+        ForwardFuture::new(stream, self)
+    }
+
+    /// Attempts to send a message into the sink, giving up once `timeout` has elapsed.  The deadline is measured by `timer`.
+    ///
+    /// Returns:
+    /// - `Ok(())` if the value was accepted.
+    /// - `Err(SendTimeoutError::Timeout(value))` if the sink did not accept the message before the deadline.
+    /// - `Err(SendTimeoutError::Rejected(value))` if the sink rejected the message.
+    fn send_timeout<T>(
+        &mut self,
+        value: Self::Item,
+        timeout: Duration,
+        timer: T,
+    ) -> SendTimeoutFuture<'_, Self, T::Sleep>
+    where
+        T: Timer,
+    {
+        SendTimeoutFuture::new(self, value, timer.sleep(timeout))
+    }
+
     /// Attempts to send a message over the sink, without blocking.
     ///
     /// Returns:
@@ -174,7 +240,39 @@ pub trait Sink {
     where
         Self: Unpin,
     {
-        pollster::block_on(self.send(value))
+        crate::blocking::block_on(self.send(value))
+    }
+
+    /// Sends a message over the channel, blocking the current thread until the message is sent.
+    /// With `WakePriority::High`, the thread's scheduling priority is raised while it waits.
+    ///
+    /// Requires the `thread-priority` feature.
+    #[cfg(feature = "thread-priority")]
+    fn blocking_send_with(
+        &mut self,
+        value: Self::Item,
+        priority: crate::blocking::WakePriority,
+    ) -> Result<(), SendError<Self::Item>>
+    where
+        Self: Unpin,
+    {
+        crate::blocking::block_on_with(self.send(value), priority)
+    }
+
+    /// Sends a message with guaranteed delivery.  The returned guard can be awaited like `send`, using a clone of the sink.
+    ///
+    /// If the guard is dropped before the message is accepted (for example, if the task is cancelled),
+    /// the message is sent by a shared background thread instead of being lost.  This is useful for critical messages, such as shutdown notices.
+    /// Messages which are delivered in the background can arrive after messages which were sent later.
+    ///
+    /// Requires the `blocking` feature (enabled by default).
+    #[cfg(feature = "blocking")]
+    fn send_guard(&self, value: Self::Item) -> SendGuard<Self>
+    where
+        Self: Clone + Unpin + Send + 'static,
+        Self::Item: Send + 'static,
+    {
+        SendGuard::new(self.clone(), value)
     }
 
     /// Chains two sink implementations.  Messages will be transmitted to the argument until it rejects a message.
@@ -196,6 +294,99 @@ pub trait Sink {
         filter::FilterSink::new(filter, self)
     }
 
+    /// Transforms messages before they are sent to the sink.
+    ///
+    /// The function borrows the message, so it can be returned to the caller if the sink is full or closed.
+    /// It is called again each time the message is offered to the sink.
+    fn with<From, With>(self, with: With) -> with::WithSink<With, Self, From>
+    where
+        With: FnMut(&From) -> Self::Item,
+        Self: Sized,
+    {
+        with::WithSink::new(with, self)
+    }
+
+    /// Transforms messages which are rejected by the sink.  A rejected message is the sink's error,
+    /// and it is returned to the caller in `PollSend::Rejected` or `SendError`.
+    fn sink_map_err<MapErr>(self, map_err: MapErr) -> map_err::MapErrSink<MapErr, Self>
+    where
+        MapErr: FnMut(Self::Item) -> Self::Item,
+        Self: Sized,
+    {
+        map_err::MapErrSink::new(map_err, self)
+    }
+
+    /// Accepts messages of a newer version, and downgrades them to the sink's message type with `From<&Version>`.
+    ///
+    /// Paired with `Stream::map_version`, this lets producers and consumers migrate to a new message version independently.
+    fn map_version<Version>(self) -> map_version::MapVersionSink<Self, Version>
+    where
+        for<'a> &'a Version: Into<Self::Item>,
+        Self: Sized,
+    {
+        map_version::MapVersionSink::new(self)
+    }
+
+    /// Queues up to `capacity` messages locally when the sink is full, so the sender can run ahead of backpressure.
+    ///
+    /// Queued messages are sent in order before each new message.  Use `BufferSink::feed` to queue a message without
+    /// waiting for the sink, and `BufferSink::flush` to wait until the queue is empty.
+    fn buffer(self, capacity: usize) -> BufferSink<Self>
+    where
+        Self: Sized,
+    {
+        BufferSink::new(self, capacity)
+    }
+
+    /// Duplicates messages into two sinks.  A message is accepted once both sinks accept it, and is rejected if either sink is closed.
+    ///
+    /// If `other` is full, the message has already been sent to `self`.  Retry the same message, and it is only sent to `other`.
+    fn fanout<Other>(self, other: Other) -> fanout::FanoutSink<Self, Other>
+    where
+        Other: Sink<Item = Self::Item>,
+        Self::Item: Clone,
+        Self: Sized,
+    {
+        fanout::FanoutSink::new(self, other)
+    }
+
+    /// Sends messages to `self`, and fails over to `fallback` when `self` is closed.
+    /// With `AlternativePolicy::OnFull`, messages are also sent to `fallback` when `self` is full.
+    ///
+    /// Failovers are counted, and the counters can be read with `AlternativeSink::failovers`.
+    fn alternative<Fallback>(
+        self,
+        fallback: Fallback,
+        policy: AlternativePolicy,
+    ) -> AlternativeSink<Self, Fallback>
+    where
+        Fallback: Sink<Item = Self::Item>,
+        Self: Sized,
+    {
+        AlternativeSink::new(self, fallback, policy)
+    }
+
+    /// Converts the sink into a sink which never rejects a message, for callers which can't handle send errors.
+    ///
+    /// While the sink is full, the message is retried with the exponential backoff in `policy`, measured by `timer`.
+    /// Messages are dropped if the retries are exhausted, or if the sink is closed.  Dropped messages are counted,
+    /// and the counters can be read with `BackoffSink::dropped`.
+    fn into_sink_with_backoff<T>(self, policy: BackoffPolicy, timer: T) -> BackoffSink<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        BackoffSink::new(self, policy, timer)
+    }
+
+    /// Boxes the sink into a `DynSender`, which hides the concrete channel or combinator type.
+    fn into_dyn(self) -> DynSender<Self::Item>
+    where
+        Self: Sized + Send + 'static,
+    {
+        DynSender::new(self)
+    }
+
     /// Logs messages that are accepted by the sink using the Debug trait, at the provided log level.
     ///
     /// Requires the `logging` feature
@@ -203,10 +394,21 @@ pub trait Sink {
     fn log(self, level: log::Level) -> sink_log::SinkLog<Self>
     where
         Self: Sized,
-        Self::Item: std::fmt::Debug,
+        Self::Item: core::fmt::Debug,
     {
         sink_log::SinkLog::new(self, level)
     }
+
+    /// Adapts the sink into a `futures::Sink`, which can be used with the `futures::SinkExt` combinators.
+    ///
+    /// Requires the `futures-traits` feature
+    #[cfg(feature = "futures-traits")]
+    fn into_futures_sink(self) -> futures_sink::FuturesSink<Self>
+    where
+        Self: Sized,
+    {
+        futures_sink::FuturesSink::new(self)
+    }
 }
 
 impl<S> Sink for &mut S
@@ -222,6 +424,10 @@ where
     ) -> PollSend<Self::Item> {
         S::poll_send(Pin::new(&mut **self), cx, value)
     }
+
+    fn cancel_send(mut self: Pin<&mut Self>) {
+        S::cancel_send(Pin::new(&mut **self))
+    }
 }
 
 impl<P, S> Sink for Pin<P>
@@ -238,6 +444,35 @@ where
     ) -> PollSend<Self::Item> {
         Pin::get_mut(self).as_mut().poll_send(cx, value)
     }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        Pin::get_mut(self).as_mut().cancel_send()
+    }
+}
+
+/// An optional sink.  `None` is closed, and rejects every value, which is useful for optional pipeline stages.
+impl<S> Sink for Option<S>
+where
+    S: Sink,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        match self.as_pin_mut() {
+            Some(sink) => sink.poll_send(cx, value),
+            None => PollSend::Rejected(value),
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        if let Some(sink) = self.as_pin_mut() {
+            sink.cancel_send();
+        }
+    }
 }
 
 /// An enum of poll responses that are produced by Sink implementations.
@@ -253,7 +488,10 @@ pub enum PollSend<T> {
 
 /// A future returned by `Sink::send`, which wraps an item.
 /// The item is sent to the sink, or returned if the sink is closed.
-#[pin_project]
+///
+/// The future is `Unpin` if the item is, so it can be stored in a struct, and polled with `Pin::new(&mut future)` without boxing.
+/// If it is dropped before it completes, the item is dropped, and has not been sent.
+#[pin_project(PinnedDrop)]
 #[must_use = "futures do nothing unless polled"]
 pub struct SendFuture<'s, S>
 where
@@ -262,10 +500,11 @@ where
     #[pin]
     send: &'s mut S,
     value: Option<S::Item>,
-    #[pin]
-    _pin: PhantomPinned,
+    cancel: Option<fn(&mut S)>,
 }
 
+static_assertions::assert_impl_all!(SendFuture<'static, crate::broadcast::Sender<usize>>: Unpin, Send);
+
 impl<'s, S> SendFuture<'s, S>
 where
     S: Sink + ?Sized,
@@ -274,9 +513,14 @@ where
         Self {
             send,
             value: Some(value),
-            _pin: PhantomPinned,
+            cancel: None,
         }
     }
+
+    /// Returns true if the future has completed, and should not be polled again.
+    pub fn is_terminated(&self) -> bool {
+        self.value.is_none()
+    }
 }
 
 impl<'s, S> Future for SendFuture<'s, S>
@@ -285,7 +529,7 @@ where
 {
     type Output = Result<(), SendError<S::Item>>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
         if self.value.is_none() {
             return Poll::Ready(Ok(()));
         }
@@ -293,13 +537,125 @@ where
         let this = self.project();
 
         let mut cx: crate::Context<'_> = cx.into();
-        match this.send.poll_send(&mut cx, this.value.take().unwrap()) {
+        let poll = match this.send.poll_send(&mut cx, this.value.take().unwrap()) {
             PollSend::Ready => Poll::Ready(Ok(())),
             PollSend::Pending(value) => {
                 *this.value = Some(value);
                 Poll::Pending
             }
             PollSend::Rejected(value) => Poll::Ready(Err(SendError(value))),
+        };
+
+        *this.cancel = pending_cancel::<S>(poll.is_pending());
+        poll
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S> PinnedDrop for SendFuture<'s, S>
+where
+    S: Sink + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.send.get_mut());
+        }
+    }
+}
+
+// the cancel_send call for a sink which is waiting.  futures store it when the sink returns `Pending`,
+// as the `Unpin` bound which is required to pin the sink is only known when the future is polled
+fn pending_cancel<S>(pending: bool) -> Option<fn(&mut S)>
+where
+    S: Sink + Unpin + ?Sized,
+{
+    fn cancel<S: Sink + Unpin + ?Sized>(sink: &mut S) {
+        Pin::new(sink).cancel_send();
+    }
+
+    pending.then_some(cancel::<S>)
+}
+
+/// A future returned by `Sink::send_timeout`, which wraps an item and a sleep future.
+/// The item is sent to the sink, or returned if the sink is closed or the deadline elapses.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct SendTimeoutFuture<'s, S, Sleep>
+where
+    S: Sink + ?Sized,
+{
+    send: &'s mut S,
+    value: Option<S::Item>,
+    #[pin]
+    sleep: Sleep,
+    cancel: Option<fn(&mut S)>,
+}
+
+impl<'s, S, Sleep> SendTimeoutFuture<'s, S, Sleep>
+where
+    S: Sink + ?Sized,
+{
+    pub fn new(send: &'s mut S, value: S::Item, sleep: Sleep) -> Self {
+        Self {
+            send,
+            value: Some(value),
+            sleep,
+            cancel: None,
+        }
+    }
+}
+
+impl<'s, S, Sleep> Future for SendTimeoutFuture<'s, S, Sleep>
+where
+    S: Sink + Unpin + ?Sized,
+    Sleep: Future<Output = ()>,
+{
+    type Output = Result<(), SendTimeoutError<S::Item>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let value = match this.value.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let mut postage_cx: crate::Context<'_> = cx.into();
+        match Pin::new(&mut **this.send).poll_send(&mut postage_cx, value) {
+            PollSend::Ready => {
+                *this.cancel = None;
+                Poll::Ready(Ok(()))
+            }
+            PollSend::Pending(value) => {
+                if this.sleep.poll(cx).is_ready() {
+                    // the send is abandoned
+                    *this.cancel = None;
+                    Pin::new(&mut **this.send).cancel_send();
+                    return Poll::Ready(Err(SendTimeoutError::Timeout(value)));
+                }
+
+                *this.value = Some(value);
+                *this.cancel = pending_cancel::<S>(true);
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => {
+                *this.cancel = None;
+                Poll::Ready(Err(SendTimeoutError::Rejected(value)))
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S, Sleep> PinnedDrop for SendTimeoutFuture<'s, S, Sleep>
+where
+    S: Sink + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.send);
         }
     }
 }
@@ -315,4 +671,130 @@ mod tests {
         let mut stream = ready();
         assert_eq!(Ok(()), stream.blocking_send(1usize));
     }
+
+    #[test]
+    fn send_all() {
+        use super::Sink;
+        use crate::test::stream::from_iter;
+        use futures_test::task::noop_context;
+        use std::{future::Future, pin::Pin, task::Poll};
+
+        let (mut tx, mut rx) = crate::mpsc::channel(4);
+        let mut source = from_iter(vec![1usize, 2, 3]);
+
+        let mut cx = noop_context();
+        let mut future = tx.send_all(&mut source);
+        assert_eq!(Poll::Ready(Ok(())), Pin::new(&mut future).poll(&mut cx));
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+
+    #[test]
+    fn send_all_resumes_when_full() {
+        use super::Sink;
+        use crate::test::stream::from_iter;
+        use futures_test::task::new_count_waker;
+        use std::{future::Future, pin::Pin, task::Poll};
+
+        let (mut tx, mut rx) = crate::mpsc::channel(2);
+        let mut source = from_iter(vec![1usize, 2, 3, 4]);
+
+        let (waker, count) = new_count_waker();
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut future = tx.send_all(&mut source);
+
+        // the channel accepts two messages, and the third waits for capacity
+        assert_eq!(Poll::Pending, Pin::new(&mut future).poll(&mut cx));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert!(count.get() > 0);
+
+        assert_eq!(Poll::Ready(Ok(())), Pin::new(&mut future).poll(&mut cx));
+        assert_eq!(Ok(3), rx.try_recv());
+        assert_eq!(Ok(4), rx.try_recv());
+    }
+
+    #[test]
+    fn option() {
+        use super::{PollSend, Sink};
+        use crate::test::sink::ready;
+        use std::pin::Pin;
+
+        let mut cx = crate::Context::empty();
+
+        let mut some = Some(ready());
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut some).poll_send(&mut cx, 1usize)
+        );
+
+        let mut none: Option<crate::mpsc::Sender<usize>> = None;
+        assert_eq!(
+            PollSend::Rejected(1usize),
+            Pin::new(&mut none).poll_send(&mut cx, 1usize)
+        );
+    }
+
+    #[test]
+    fn send_future_stored() {
+        use super::{SendError, SendFuture, Sink};
+        use crate::test::sink::{pending, rejected};
+        use futures_test::task::noop_context;
+        use std::{future::Future, pin::Pin, task::Poll};
+
+        let mut cx = noop_context();
+
+        // the named future is Unpin, and can be polled in place
+        let mut sink = pending();
+        let mut future: SendFuture<'_, _> = sink.send(1usize);
+        assert_eq!(Poll::Pending, Pin::new(&mut future).poll(&mut cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut future).poll(&mut cx));
+
+        let mut sink = rejected();
+        let mut future = sink.send(1usize);
+        assert_eq!(
+            Poll::Ready(Err(SendError(1usize))),
+            Pin::new(&mut future).poll(&mut cx)
+        );
+    }
+
+    #[test]
+    fn send_timeout() {
+        use super::{SendTimeoutError, Sink};
+        use crate::test::sink::{pending, ready, rejected};
+        use crate::test::timer::{ExpiredTimer, NeverTimer};
+        use futures_test::task::noop_context;
+        use std::{future::Future, task::Poll, time::Duration};
+
+        let mut cx = noop_context();
+        let timeout = Duration::from_secs(1);
+
+        let mut sink = ready();
+        let future = sink.send_timeout(1usize, timeout, ExpiredTimer);
+        futures::pin_mut!(future);
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut cx));
+
+        let mut sink = pending();
+        let future = sink.send_timeout(1usize, timeout, NeverTimer);
+        futures::pin_mut!(future);
+        assert_eq!(Poll::Pending, future.poll(&mut cx));
+
+        let mut sink = pending();
+        let future = sink.send_timeout(1usize, timeout, ExpiredTimer);
+        futures::pin_mut!(future);
+        assert_eq!(
+            Poll::Ready(Err(SendTimeoutError::Timeout(1usize))),
+            future.poll(&mut cx)
+        );
+
+        let mut sink = rejected();
+        let future = sink.send_timeout(1usize, timeout, NeverTimer);
+        futures::pin_mut!(future);
+        assert_eq!(
+            Poll::Ready(Err(SendTimeoutError::Rejected(1usize))),
+            future.poll(&mut cx)
+        );
+    }
 }
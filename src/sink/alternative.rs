@@ -0,0 +1,302 @@
+use alloc::sync::Arc;
+use core::{
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+/// Determines when `Sink::alternative` routes a message to the fallback sink.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AlternativePolicy {
+    /// Routes messages to the fallback once the primary sink is closed.  Waits if the primary sink is full.
+    OnClosed,
+    /// Also routes a message to the fallback if the primary sink is full.  Waits only if both sinks are full.
+    OnFull,
+}
+
+/// Counts the messages which were routed to the fallback sink.  Created by `AlternativeSink::failovers`.
+///
+/// The counter can be cloned, and read while the sink is used by another task.
+#[derive(Debug, Clone, Default)]
+pub struct FailoverCounter {
+    counts: Arc<Counts>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    closed: AtomicUsize,
+    full: AtomicUsize,
+}
+
+impl FailoverCounter {
+    /// The number of messages which were sent to the fallback because the primary sink was closed.
+    pub fn closed(&self) -> usize {
+        self.counts.closed.load(Ordering::Relaxed)
+    }
+
+    /// The number of messages which were sent to the fallback because the primary sink was full.
+    pub fn full(&self) -> usize {
+        self.counts.full.load(Ordering::Relaxed)
+    }
+
+    /// The total number of messages which were sent to the fallback.
+    pub fn total(&self) -> usize {
+        self.closed() + self.full()
+    }
+}
+
+/// A sink which sends messages to a primary sink, and fails over to a fallback sink.  Created by `Sink::alternative`.
+///
+/// The sink is rejected once both the primary and the fallback are closed.
+#[pin_project]
+pub struct AlternativeSink<Primary, Fallback> {
+    #[pin]
+    primary: Primary,
+    #[pin]
+    fallback: Fallback,
+    policy: AlternativePolicy,
+    primary_closed: bool,
+    failovers: FailoverCounter,
+}
+
+impl<Primary, Fallback> AlternativeSink<Primary, Fallback>
+where
+    Primary: Sink,
+    Fallback: Sink<Item = Primary::Item>,
+{
+    pub fn new(primary: Primary, fallback: Fallback, policy: AlternativePolicy) -> Self {
+        Self {
+            primary,
+            fallback,
+            policy,
+            primary_closed: false,
+            failovers: FailoverCounter::default(),
+        }
+    }
+
+    /// Returns a handle to the failover counters.
+    pub fn failovers(&self) -> FailoverCounter {
+        self.failovers.clone()
+    }
+}
+
+impl<Primary, Fallback> Sink for AlternativeSink<Primary, Fallback>
+where
+    Primary: Sink,
+    Fallback: Sink<Item = Primary::Item>,
+{
+    type Item = Primary::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let mut this = self.project();
+
+        if !*this.primary_closed {
+            match this.primary.as_mut().poll_send(cx, value) {
+                PollSend::Ready => PollSend::Ready,
+                PollSend::Pending(value) => match this.policy {
+                    AlternativePolicy::OnClosed => PollSend::Pending(value),
+                    AlternativePolicy::OnFull => match this.fallback.poll_send(cx, value) {
+                        PollSend::Ready => {
+                            // the message was delivered, so the primary gives up its turn
+                            this.primary.cancel_send();
+                            this.failovers.counts.full.fetch_add(1, Ordering::Relaxed);
+                            PollSend::Ready
+                        }
+                        // the primary sink is open, so the message waits for it
+                        PollSend::Pending(value) | PollSend::Rejected(value) => {
+                            PollSend::Pending(value)
+                        }
+                    },
+                },
+                PollSend::Rejected(value) => {
+                    *this.primary_closed = true;
+                    send_fallback(this.fallback, this.failovers, cx, value)
+                }
+            }
+        } else {
+            send_fallback(this.fallback, this.failovers, cx, value)
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.primary.cancel_send();
+        this.fallback.cancel_send();
+    }
+}
+
+fn send_fallback<Fallback>(
+    fallback: Pin<&mut Fallback>,
+    failovers: &FailoverCounter,
+    cx: &mut Context<'_>,
+    value: Fallback::Item,
+) -> PollSend<Fallback::Item>
+where
+    Fallback: Sink,
+{
+    let poll = fallback.poll_send(cx, value);
+    if let PollSend::Ready = poll {
+        failovers.counts.closed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    poll
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::{AlternativePolicy, AlternativeSink};
+
+    #[test]
+    fn primary() {
+        let mut primary = test_sink(vec![PollSend::Ready]);
+        let mut fallback = test_sink(vec![PollSend::Ready]);
+        let mut sink =
+            AlternativeSink::new(&mut primary, &mut fallback, AlternativePolicy::OnClosed);
+        let failovers = sink.failovers();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(0, failovers.total());
+
+        drop(sink);
+        assert_eq!(&[1], primary.values());
+        assert_eq!(Vec::<usize>::new(), fallback.values());
+    }
+
+    #[test]
+    fn failover_on_closed() {
+        let mut primary = test_sink(vec![PollSend::Ready]);
+        let mut fallback = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut sink =
+            AlternativeSink::new(&mut primary, &mut fallback, AlternativePolicy::OnClosed);
+        let failovers = sink.failovers();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 3));
+        assert_eq!(2, failovers.closed());
+        assert_eq!(0, failovers.full());
+
+        drop(sink);
+        assert_eq!(&[1], primary.values());
+        assert_eq!(&[2, 3], fallback.values());
+    }
+
+    #[test]
+    fn on_closed_waits_when_full() {
+        let mut sink =
+            AlternativeSink::new(pending::<usize>(), ready(), AlternativePolicy::OnClosed);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+        assert_eq!(0, sink.failovers().total());
+    }
+
+    #[test]
+    fn failover_on_full() {
+        let mut sink = AlternativeSink::new(pending::<usize>(), ready(), AlternativePolicy::OnFull);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(1, sink.failovers().full());
+        assert_eq!(0, sink.failovers().closed());
+    }
+
+    #[test]
+    fn on_full_waits_when_fallback_closed() {
+        let mut sink =
+            AlternativeSink::new(pending::<usize>(), rejected(), AlternativePolicy::OnFull);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn rejected_when_both_closed() {
+        let mut sink =
+            AlternativeSink::new(rejected::<usize>(), rejected(), AlternativePolicy::OnClosed);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+        assert_eq!(0, sink.failovers().total());
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use crate::{sink::Sink, stream::Stream};
+
+    use super::AlternativePolicy;
+
+    #[tokio::test]
+    async fn failover_to_backup_worker() {
+        let (primary_tx, primary_rx) = crate::mpsc::channel(4);
+        let (backup_tx, mut backup_rx) = crate::mpsc::channel(4);
+
+        let mut tx = primary_tx.alternative(backup_tx, AlternativePolicy::OnClosed);
+        let failovers = tx.failovers();
+
+        drop(primary_rx);
+        tx.send(1usize).await.expect("send failed");
+
+        assert_eq!(Some(1), backup_rx.recv().await);
+        assert_eq!(1, failovers.closed());
+    }
+
+    #[tokio::test]
+    async fn failover_on_full_releases_primary_turn() {
+        let (mut primary_tx, mut primary_rx) = crate::mpmc::channel(1);
+        let (backup_tx, mut backup_rx) = crate::mpsc::channel(4);
+
+        let mut tx = primary_tx
+            .clone()
+            .alternative(backup_tx, AlternativePolicy::OnFull);
+
+        primary_tx.try_send(0usize).expect("send failed");
+        tx.send(1).await.expect("send failed");
+        assert_eq!(Some(1), backup_rx.recv().await);
+
+        // the primary has a free slot, which other senders can take
+        assert_eq!(Some(0), primary_rx.recv().await);
+        assert_eq!(Ok(()), primary_tx.try_send(2));
+        assert_eq!(Some(2), primary_rx.recv().await);
+    }
+}
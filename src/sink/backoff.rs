@@ -0,0 +1,345 @@
+use alloc::sync::Arc;
+use core::{
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use crate::{
+    sink::{PollSend, Sink},
+    time::{poll_sleep, Timer},
+    Context,
+};
+use pin_project::pin_project;
+
+/// Configures the retries of `Sink::into_sink_with_backoff`.
+///
+/// While the sink is full, the message is retried after `initial`, and the delay doubles after each attempt, up to `max`.
+/// After `retries` failed retries, the message is dropped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// The delay before the first retry
+    pub initial: Duration,
+    /// The maximum delay between retries
+    pub max: Duration,
+    /// The number of retries before a message is dropped
+    pub retries: usize,
+}
+
+impl BackoffPolicy {
+    /// The delay before the given retry, starting at zero.
+    fn delay(&self, attempt: usize) -> Duration {
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        self.initial
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+impl Default for BackoffPolicy {
+    /// Retries 8 times, starting at 1ms, with delays of up to 100ms.
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(100),
+            retries: 8,
+        }
+    }
+}
+
+/// Counts the messages which were dropped by a backoff sink.  Created by `BackoffSink::dropped`.
+///
+/// The counter can be cloned, and read while the sink is used by another task.
+#[derive(Debug, Clone, Default)]
+pub struct DroppedCounter {
+    counts: Arc<Counts>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    closed: AtomicUsize,
+    full: AtomicUsize,
+}
+
+impl DroppedCounter {
+    /// The number of messages which were dropped because the wrapped sink was closed.
+    pub fn closed(&self) -> usize {
+        self.counts.closed.load(Ordering::Relaxed)
+    }
+
+    /// The number of messages which were dropped because the wrapped sink was still full after every retry.
+    pub fn full(&self) -> usize {
+        self.counts.full.load(Ordering::Relaxed)
+    }
+
+    /// The total number of messages which were dropped.
+    pub fn total(&self) -> usize {
+        self.closed() + self.full()
+    }
+}
+
+/// A sink which never rejects a message.  Created by `Sink::into_sink_with_backoff`.
+///
+/// While the wrapped sink is full, the message is retried with an exponential backoff, measured by the timer.
+/// If the retries are exhausted, or the wrapped sink is closed, the message is dropped and counted.
+#[pin_project]
+pub struct BackoffSink<Into, T>
+where
+    T: Timer,
+{
+    #[pin]
+    into: Into,
+    #[pin]
+    sleep: Option<T::Sleep>,
+    timer: T,
+    policy: BackoffPolicy,
+    attempt: usize,
+    dropped: DroppedCounter,
+}
+
+impl<Into, T> BackoffSink<Into, T>
+where
+    Into: Sink,
+    T: Timer,
+{
+    pub fn new(into: Into, policy: BackoffPolicy, timer: T) -> Self {
+        Self {
+            into,
+            sleep: None,
+            timer,
+            policy,
+            attempt: 0,
+            dropped: DroppedCounter::default(),
+        }
+    }
+
+    /// Returns a handle to the counters of dropped messages.
+    pub fn dropped(&self) -> DroppedCounter {
+        self.dropped.clone()
+    }
+}
+
+impl<Into, T> Sink for BackoffSink<Into, T>
+where
+    Into: Sink,
+    T: Timer,
+{
+    type Item = Into::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let mut this = self.project();
+
+        if !poll_sleep(this.sleep.as_mut(), cx) {
+            return PollSend::Pending(value);
+        }
+
+        match this.into.as_mut().poll_send(cx, value) {
+            PollSend::Ready => {
+                *this.attempt = 0;
+                PollSend::Ready
+            }
+            PollSend::Pending(value) => {
+                // the sink does not wait on the wrapped sink while it sleeps, or after it drops the message
+                this.into.cancel_send();
+
+                if *this.attempt >= this.policy.retries {
+                    *this.attempt = 0;
+                    this.dropped.counts.full.fetch_add(1, Ordering::Relaxed);
+                    return PollSend::Ready;
+                }
+
+                let delay = this.policy.delay(*this.attempt);
+                *this.attempt += 1;
+                this.sleep.set(Some(this.timer.sleep(delay)));
+
+                // polling the sleep registers the waker, so the task retries when it elapses
+                if poll_sleep(this.sleep, cx) {
+                    if let Some(waker) = cx.waker() {
+                        waker.wake_by_ref();
+                    }
+                }
+
+                PollSend::Pending(value)
+            }
+            PollSend::Rejected(_value) => {
+                *this.attempt = 0;
+                this.dropped.counts.closed.fetch_add(1, Ordering::Relaxed);
+                PollSend::Ready
+            }
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::test::sink::*;
+    use crate::test::timer::{ExpiredTimer, NeverTimer};
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::{BackoffPolicy, BackoffSink};
+
+    fn policy(retries: usize) -> BackoffPolicy {
+        BackoffPolicy {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(4),
+            retries,
+        }
+    }
+
+    #[test]
+    fn delay() {
+        let policy = policy(0);
+
+        assert_eq!(Duration::from_millis(1), policy.delay(0));
+        assert_eq!(Duration::from_millis(2), policy.delay(1));
+        assert_eq!(Duration::from_millis(4), policy.delay(2));
+        assert_eq!(Duration::from_millis(4), policy.delay(3));
+        assert_eq!(Duration::from_millis(4), policy.delay(64));
+    }
+
+    #[test]
+    fn ready() {
+        let mut into = test_sink(vec![PollSend::Ready]);
+        let mut sink = BackoffSink::new(&mut into, policy(2), NeverTimer);
+        let dropped = sink.dropped();
+
+        let mut cx = Context::empty();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(0, dropped.total());
+
+        drop(sink);
+        assert_eq!(&[1], into.values());
+    }
+
+    #[test]
+    fn retry_after_backoff() {
+        let mut into = test_sink(vec![PollSend::Pending(1usize), PollSend::Ready]);
+        let mut sink = BackoffSink::new(&mut into, policy(2), ExpiredTimer);
+        let dropped = sink.dropped();
+
+        let mut cx = Context::empty();
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(0, dropped.total());
+
+        drop(sink);
+        assert_eq!(&[1], into.values());
+    }
+
+    #[test]
+    fn waits_for_backoff() {
+        let mut into = test_sink(vec![PollSend::Pending(1usize), PollSend::Ready]);
+        let mut sink = BackoffSink::new(&mut into, policy(2), NeverTimer);
+
+        let mut cx = Context::empty();
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+
+        drop(sink);
+        assert_eq!(Vec::<usize>::new(), into.values());
+    }
+
+    #[test]
+    fn drop_after_retries() {
+        let mut into = test_sink(vec![
+            PollSend::Pending(1usize),
+            PollSend::Pending(1),
+            PollSend::Pending(1),
+            PollSend::Ready,
+        ]);
+        let mut sink = BackoffSink::new(&mut into, policy(2), ExpiredTimer);
+        let dropped = sink.dropped();
+
+        let mut cx = Context::empty();
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(1, dropped.full());
+
+        // the next message starts with a fresh set of retries
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 2));
+
+        drop(sink);
+        assert_eq!(&[2], into.values());
+    }
+
+    #[test]
+    fn drop_on_closed() {
+        let mut into = test_sink(vec![PollSend::Rejected(1usize)]);
+        let mut sink = BackoffSink::new(&mut into, policy(2), NeverTimer);
+        let dropped = sink.dropped();
+
+        let mut cx = Context::empty();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut sink).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(1, dropped.closed());
+        assert_eq!(0, dropped.full());
+    }
+
+    #[test]
+    fn backoff_releases_send_turn() {
+        let (mut tx, mut rx) = crate::mpmc::channel(1);
+        assert_eq!(Ok(()), tx.try_send(0usize));
+
+        let mut sink = BackoffSink::new(tx.clone(), policy(2), NeverTimer);
+        let mut cx: Context<'_> = futures_test::task::noop_context().into();
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut sink).poll_send(&mut cx, 1)
+        );
+
+        // the sink is sleeping, and other senders can take the free slot
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+
+    #[test]
+    fn dropped_message_releases_send_turn() {
+        let (mut tx, mut rx) = crate::mpmc::channel(1);
+        assert_eq!(Ok(()), tx.try_send(0usize));
+
+        let mut sink = BackoffSink::new(tx.clone(), policy(0), NeverTimer);
+        let dropped = sink.dropped();
+        let mut cx: Context<'_> = futures_test::task::noop_context().into();
+        assert_eq!(PollSend::Ready, Pin::new(&mut sink).poll_send(&mut cx, 1));
+        assert_eq!(1, dropped.full());
+
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+}
@@ -0,0 +1,361 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::{future::Future, pin::Pin, task::Poll};
+
+use crate::Context;
+
+use crate::sink::{PollSend, SendError, Sink};
+use pin_project::pin_project;
+
+/// A sink which queues up to `capacity` messages locally when the wrapped sink is full.  Created by `Sink::buffer`.
+///
+/// Queued messages are sent before each new message, in order.  Call `flush` to wait until every queued message
+/// has been accepted.  Messages which are still queued when the sink is dropped are lost.
+///
+/// When a message is queued, the pending send on the wrapped sink is cancelled with `Sink::cancel_send`,
+/// so a fair channel such as `mpmc` serves other senders until the queue is sent.
+#[pin_project]
+pub struct BufferSink<Into>
+where
+    Into: Sink,
+{
+    #[pin]
+    into: Into,
+    buffer: VecDeque<Into::Item>,
+    capacity: usize,
+}
+
+impl<Into> BufferSink<Into>
+where
+    Into: Sink,
+{
+    pub fn new(into: Into, capacity: usize) -> Self {
+        Self {
+            into,
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The number of messages which are queued, and have not yet been accepted by the wrapped sink.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Attempts to send every queued message to the wrapped sink, without blocking.
+    ///
+    /// Returns:
+    /// - `Poll::Ready(Ok(()))` if the queue is empty.
+    /// - `Poll::Pending` if the wrapped sink is full.  The waker in `cx` is called when more messages may be accepted.
+    /// - `Poll::Ready(Err(SendError(values)))` with the undelivered messages, if the wrapped sink is closed.
+    pub fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SendError<Vec<Into::Item>>>> {
+        let this = self.project();
+
+        match send_buffered(this.into, this.buffer, cx) {
+            Flush::Empty => Poll::Ready(Ok(())),
+            Flush::Pending => Poll::Pending,
+            Flush::Rejected => Poll::Ready(Err(SendError(this.buffer.drain(..).collect()))),
+        }
+    }
+
+    /// Queues a message, without waiting for the wrapped sink to accept it.
+    ///
+    /// If the queue is full, waits until there is room, like `Sink::send`.  Messages are not sent until the queue is full,
+    /// or `send` or `flush` is called.  If the wrapped sink is closed, the error is returned by `flush`.
+    pub async fn feed(&mut self, value: Into::Item) -> Result<(), SendError<Into::Item>>
+    where
+        Self: Unpin,
+    {
+        if self.buffer.len() < self.capacity {
+            self.buffer.push_back(value);
+            return Ok(());
+        }
+
+        self.send(value).await
+    }
+
+    /// Waits until every queued message has been accepted by the wrapped sink.
+    ///
+    /// Returns `Err(SendError(values))` with the undelivered messages if the wrapped sink is closed.
+    pub fn flush(&mut self) -> FlushFuture<'_, Into>
+    where
+        Self: Unpin,
+    {
+        FlushFuture { sink: self }
+    }
+}
+
+enum Flush {
+    Empty,
+    Pending,
+    Rejected,
+}
+
+fn send_buffered<Into>(
+    mut into: Pin<&mut Into>,
+    buffer: &mut VecDeque<Into::Item>,
+    cx: &mut Context<'_>,
+) -> Flush
+where
+    Into: Sink,
+{
+    while let Some(value) = buffer.pop_front() {
+        match into.as_mut().poll_send(cx, value) {
+            PollSend::Ready => {}
+            PollSend::Pending(value) => {
+                buffer.push_front(value);
+                return Flush::Pending;
+            }
+            PollSend::Rejected(value) => {
+                buffer.push_front(value);
+                return Flush::Rejected;
+            }
+        }
+    }
+
+    Flush::Empty
+}
+
+impl<Into> Sink for BufferSink<Into>
+where
+    Into: Sink,
+{
+    type Item = Into::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let mut this = self.project();
+
+        match send_buffered(this.into.as_mut(), this.buffer, cx) {
+            Flush::Empty => {}
+            Flush::Pending => {
+                // the wrapped sink is full, and has registered the waker
+                if this.buffer.len() < *this.capacity {
+                    // nothing waits on the wrapped sink until the next send or flush, so it gives up its turn
+                    this.buffer.push_back(value);
+                    this.into.cancel_send();
+                    return PollSend::Ready;
+                }
+
+                return PollSend::Pending(value);
+            }
+            Flush::Rejected => return PollSend::Rejected(value),
+        }
+
+        match this.into.as_mut().poll_send(cx, value) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(value) => {
+                if *this.capacity > 0 {
+                    this.buffer.push_back(value);
+                    this.into.cancel_send();
+                    return PollSend::Ready;
+                }
+
+                PollSend::Pending(value)
+            }
+            PollSend::Rejected(value) => PollSend::Rejected(value),
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
+}
+
+/// A future returned by `BufferSink::flush`, which resolves when every queued message has been accepted.
+#[must_use = "futures do nothing unless polled"]
+pub struct FlushFuture<'s, Into>
+where
+    Into: Sink,
+{
+    sink: &'s mut BufferSink<Into>,
+}
+
+impl<'s, Into> Future for FlushFuture<'s, Into>
+where
+    Into: Sink,
+    BufferSink<Into>: Unpin,
+{
+    type Output = Result<(), SendError<Vec<Into::Item>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let mut cx: crate::Context<'_> = cx.into();
+        Pin::new(&mut *self.sink).poll_flush(&mut cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Poll};
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, SendError, Sink},
+        Context,
+    };
+
+    use super::BufferSink;
+
+    #[test]
+    fn buffer_when_full() {
+        let mut test_sink = test_sink(vec![
+            PollSend::Pending(1usize),
+            PollSend::Pending(1usize),
+            PollSend::Pending(1usize),
+            PollSend::Ready,
+            PollSend::Ready,
+            PollSend::Ready,
+        ]);
+        let mut buffer = BufferSink::new(&mut test_sink, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 2));
+        assert_eq!(
+            PollSend::Pending(3),
+            Pin::new(&mut buffer).poll_send(&mut cx, 3)
+        );
+        assert_eq!(2, buffer.buffered());
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 3));
+        assert_eq!(0, buffer.buffered());
+
+        drop(buffer);
+        assert_eq!(&[1, 2, 3], test_sink.values());
+    }
+
+    #[test]
+    fn flush() {
+        let mut test_sink = test_sink(vec![
+            PollSend::Pending(1usize),
+            PollSend::Pending(1usize),
+            PollSend::Ready,
+        ]);
+        let mut buffer = BufferSink::new(&mut test_sink, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 1));
+        assert_eq!(Poll::Pending, Pin::new(&mut buffer).poll_flush(&mut cx));
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            Pin::new(&mut buffer).poll_flush(&mut cx)
+        );
+
+        drop(buffer);
+        assert_eq!(&[1], test_sink.values());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let source = pending::<usize>();
+        let mut buffer = BufferSink::new(source, 0);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut buffer).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn closed_returns_buffered() {
+        let test_sink = test_sink(vec![PollSend::Pending(1usize), PollSend::Rejected(1usize)]);
+        let mut buffer = BufferSink::new(test_sink, 2);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 1));
+        assert_eq!(
+            PollSend::Rejected(2),
+            Pin::new(&mut buffer).poll_send(&mut cx, 2)
+        );
+        assert_eq!(
+            Poll::Ready(Err(SendError(vec![1]))),
+            Pin::new(&mut buffer).poll_flush(&mut cx)
+        );
+    }
+
+    #[test]
+    fn queued_send_releases_turn() {
+        let (mut tx1, mut rx) = crate::mpmc::channel(1);
+        let mut tx2 = tx1.clone();
+
+        assert_eq!(Ok(()), tx1.try_send(0usize));
+
+        // both values are queued in the buffer, which does not wait on the channel
+        let mut buffer = BufferSink::new(tx1.clone(), 4);
+        let mut cx: Context<'_> = futures_test::task::noop_context().into();
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 1));
+        assert_eq!(PollSend::Ready, Pin::new(&mut buffer).poll_send(&mut cx, 2));
+        assert_eq!(2, buffer.buffered());
+
+        assert_eq!(Ok(0), rx.try_recv());
+        assert_eq!(Ok(()), tx2.try_send(3));
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use crate::{sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn buffer_ahead_of_receiver() {
+        let (tx, mut rx) = crate::mpsc::channel(1);
+        let mut tx = tx.buffer(4);
+
+        for i in 0..5usize {
+            tx.try_send(i).expect("send failed");
+        }
+
+        assert!(tx.try_send(5).is_err());
+
+        let receiver = tokio::spawn(async move {
+            let mut values = Vec::new();
+            while let Some(v) = rx.recv().await {
+                values.push(v);
+            }
+            values
+        });
+
+        tx.flush().await.expect("flush failed");
+        drop(tx);
+
+        assert_eq!(vec![0, 1, 2, 3, 4], receiver.await.expect("join failed"));
+    }
+
+    #[tokio::test]
+    async fn feed_then_flush() {
+        let (tx, mut rx) = crate::mpsc::channel(4);
+        let mut tx = tx.buffer(2);
+
+        tx.feed(1usize).await.expect("feed failed");
+        tx.feed(2).await.expect("feed failed");
+        assert_eq!(2, tx.buffered());
+        assert!(rx.is_empty());
+
+        // the queue is full, so the queued messages are sent before the next message
+        tx.feed(3).await.expect("feed failed");
+        assert_eq!(0, tx.buffered());
+        assert_eq!(3, rx.len());
+
+        tx.feed(4).await.expect("feed failed");
+        tx.flush().await.expect("flush failed");
+        drop(tx);
+
+        let mut values = Vec::new();
+        while let Some(v) = rx.recv().await {
+            values.push(v);
+        }
+        assert_eq!(vec![1, 2, 3, 4], values);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::sink::{PollSend, Sink};
 use crate::Context;
 use atomic::{Atomic, Ordering};
+use core::pin::Pin;
 use pin_project::pin_project;
-use std::pin::Pin;
 
 #[derive(Copy, Clone)]
 enum State {
@@ -80,6 +80,12 @@ where
 
         unreachable!();
     }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.left.cancel_send();
+        this.right.cancel_send();
+    }
 }
 
 #[cfg(test)]
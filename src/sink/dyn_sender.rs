@@ -0,0 +1,107 @@
+use alloc::boxed::Box;
+use core::{fmt, pin::Pin};
+
+use crate::sink::{PollSend, Sink};
+use crate::Context;
+
+// Sink is not object-safe, as `send` and the other futures reference `Self`.
+// This trait exposes only the poll and cancel functions, so the sink can be called through a vtable.
+trait PollSink<T>: Send {
+    fn poll_send_dyn(self: Pin<&mut Self>, cx: &mut Context<'_>, value: T) -> PollSend<T>;
+
+    fn cancel_send_dyn(self: Pin<&mut Self>);
+}
+
+impl<S> PollSink<S::Item> for S
+where
+    S: Sink + Send,
+{
+    fn poll_send_dyn(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: S::Item,
+    ) -> PollSend<S::Item> {
+        self.poll_send(cx, value)
+    }
+
+    fn cancel_send_dyn(self: Pin<&mut Self>) {
+        self.cancel_send()
+    }
+}
+
+/// A sender which hides the type of the wrapped sink.  Created by `Sink::into_dyn` or `DynSender::new`.
+///
+/// Any channel sender or sink combinator can be stored as a `DynSender<T>`, so endpoints can be passed between crates
+/// without exposing the channel kind.  The sink is boxed once, when the sender is created.  Each poll is a virtual call,
+/// and does not allocate.
+pub struct DynSender<T> {
+    sink: Pin<Box<dyn PollSink<T>>>,
+}
+
+impl<T> DynSender<T> {
+    pub fn new<S>(sink: S) -> Self
+    where
+        S: Sink<Item = T> + Send + 'static,
+    {
+        Self {
+            sink: Box::pin(sink),
+        }
+    }
+}
+
+impl<T> Sink for DynSender<T> {
+    type Item = T;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        self.get_mut().sink.as_mut().poll_send_dyn(cx, value)
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.get_mut().sink.as_mut().cancel_send_dyn();
+    }
+}
+
+impl<T> fmt::Debug for DynSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynSender").finish_non_exhaustive()
+    }
+}
+
+static_assertions::assert_impl_all!(DynSender<usize>: Send, Unpin);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dispatch, mpsc,
+        sink::{Sink, TrySendError},
+    };
+
+    use super::DynSender;
+
+    #[test]
+    fn channels() {
+        let (mpsc_tx, mut mpsc_rx) = mpsc::channel(4);
+        let (dispatch_tx, mut dispatch_rx) = dispatch::channel(4);
+
+        let mut senders = [DynSender::new(mpsc_tx), DynSender::new(dispatch_tx)];
+        for sender in senders.iter_mut() {
+            sender.try_send(1usize).unwrap();
+        }
+
+        assert_eq!(Ok(1), mpsc_rx.try_recv());
+        assert_eq!(Ok(1), dispatch_rx.try_recv());
+    }
+
+    #[test]
+    fn rejected() {
+        let (tx, rx) = mpsc::channel(4);
+        let mut sender = DynSender::new(tx);
+        drop(rx);
+
+        assert_eq!(Err(TrySendError::Rejected(1usize)), sender.try_send(1));
+    }
+}
@@ -7,32 +7,54 @@ pub enum TrySendError<T> {
     Rejected(T),
 }
 
-impl<T> std::fmt::Display for TrySendError<T>
+impl<T> core::fmt::Display for TrySendError<T>
 where
-    T: std::fmt::Debug,
+    T: core::fmt::Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_fmt(format_args!("{:?}", &self))?;
 
         Ok(())
     }
 }
 
-impl<T> std::error::Error for TrySendError<T> where T: std::fmt::Debug {}
+impl<T> core::error::Error for TrySendError<T> where T: core::fmt::Debug {}
 
 /// An error type returned by `Sink::send`, if the sink is closed while a send is in progress.
 #[derive(Debug, PartialEq, Eq)]
 pub struct SendError<T>(pub T);
 
-impl<T> std::fmt::Display for SendError<T>
+impl<T> core::fmt::Display for SendError<T>
 where
-    T: std::fmt::Debug,
+    T: core::fmt::Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_fmt(format_args!("{:?}", &self))?;
 
         Ok(())
     }
 }
 
-impl<T> std::error::Error for SendError<T> where T: std::fmt::Debug {}
+impl<T> core::error::Error for SendError<T> where T: core::fmt::Debug {}
+
+/// An error type returned by `Sink::send_timeout`, if the message was not accepted before the deadline.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendTimeoutError<T> {
+    /// The deadline elapsed before the sink accepted the item
+    Timeout(T),
+    /// The sink is closed, and will never accept the item
+    Rejected(T),
+}
+
+impl<T> core::fmt::Display for SendTimeoutError<T>
+where
+    T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("{:?}", &self))?;
+
+        Ok(())
+    }
+}
+
+impl<T> core::error::Error for SendTimeoutError<T> where T: core::fmt::Debug {}
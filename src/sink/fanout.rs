@@ -0,0 +1,158 @@
+use core::pin::Pin;
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FanoutSink<Left, Right> {
+    #[pin]
+    left: Left,
+    #[pin]
+    right: Right,
+    left_sent: bool,
+}
+
+impl<Left, Right> FanoutSink<Left, Right>
+where
+    Left: Sink,
+    Left::Item: Clone,
+    Right: Sink<Item = Left::Item>,
+{
+    pub fn new(left: Left, right: Right) -> Self {
+        Self {
+            left,
+            right,
+            left_sent: false,
+        }
+    }
+}
+
+impl<Left, Right> Sink for FanoutSink<Left, Right>
+where
+    Left: Sink,
+    Left::Item: Clone,
+    Right: Sink<Item = Left::Item>,
+{
+    type Item = Left::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        // if the right sink was full, the retried message has already been accepted by the left sink
+        if !*this.left_sent {
+            match this.left.poll_send(cx, value.clone()) {
+                PollSend::Ready => *this.left_sent = true,
+                PollSend::Pending(_) => return PollSend::Pending(value),
+                PollSend::Rejected(_) => return PollSend::Rejected(value),
+            }
+        }
+
+        match this.right.poll_send(cx, value) {
+            PollSend::Ready => {
+                *this.left_sent = false;
+                PollSend::Ready
+            }
+            PollSend::Pending(value) => PollSend::Pending(value),
+            PollSend::Rejected(value) => {
+                *this.left_sent = false;
+                PollSend::Rejected(value)
+            }
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.left.cancel_send();
+        this.right.cancel_send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::FanoutSink;
+
+    #[test]
+    fn duplicate() {
+        let mut left = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut right = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut fanout = FanoutSink::new(&mut left, &mut right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut fanout).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut fanout).poll_send(&mut cx, 2usize)
+        );
+
+        assert_eq!(&[1, 2], left.values());
+        assert_eq!(&[1, 2], right.values());
+    }
+
+    #[test]
+    fn right_pending_sends_left_once() {
+        let mut left = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut right = test_sink(vec![PollSend::Pending(1usize), PollSend::Ready]);
+        let mut fanout = FanoutSink::new(&mut left, &mut right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut fanout).poll_send(&mut cx, 1usize)
+        );
+
+        assert_eq!(&[1], left.values());
+        assert_eq!(&[1], right.values());
+    }
+
+    #[test]
+    fn left_pending() {
+        let mut fanout = FanoutSink::new(pending::<usize>(), ready());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn rejected_if_either_closed() {
+        let mut cx = Context::empty();
+
+        let mut fanout = FanoutSink::new(rejected::<usize>(), ready());
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+
+        let mut fanout = FanoutSink::new(ready(), rejected::<usize>());
+        assert_eq!(
+            PollSend::Rejected(1),
+            Pin::new(&mut fanout).poll_send(&mut cx, 1)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use core::pin::Pin;
 
 use crate::Context;
 
@@ -41,6 +41,10 @@ where
 
         this.into.poll_send(cx, value)
     }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
 }
 
 #[cfg(test)]
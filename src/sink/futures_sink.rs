@@ -0,0 +1,165 @@
+use core::{pin::Pin, task::Poll};
+
+use crate::sink::{PollSend, SendError, Sink};
+use pin_project::pin_project;
+
+/// Adapts a postage sink into a `futures::Sink`.
+///
+/// `start_send` buffers a single message, which is sent to the postage sink by `poll_ready`, `poll_flush`, or `poll_close`.
+/// If the postage sink rejects the buffered message, it is returned as `Err(SendError(message))`.
+#[pin_project]
+pub struct FuturesSink<S>
+where
+    S: Sink,
+{
+    #[pin]
+    sink: S,
+    buffer: Option<S::Item>,
+}
+
+impl<S> FuturesSink<S>
+where
+    S: Sink,
+{
+    pub fn new(sink: S) -> Self {
+        Self { sink, buffer: None }
+    }
+
+    /// Returns the wrapped postage sink.  Messages which have not been flushed are dropped.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S> FuturesSink<S>
+where
+    S: Sink,
+{
+    fn poll_buffer(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), SendError<S::Item>>> {
+        let this = self.project();
+
+        let value = match this.buffer.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let mut cx = cx.into();
+        match this.sink.poll_send(&mut cx, value) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(value) => {
+                *this.buffer = Some(value);
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => Poll::Ready(Err(SendError(value))),
+        }
+    }
+}
+
+impl<S> futures::sink::Sink<S::Item> for FuturesSink<S>
+where
+    S: Sink,
+{
+    type Error = SendError<S::Item>;
+
+    fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_buffer(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: S::Item) -> Result<(), Self::Error> {
+        let this = self.project();
+
+        if this.buffer.is_some() {
+            return Err(SendError(item));
+        }
+
+        *this.buffer = Some(item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_buffer(cx)
+    }
+
+    fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.poll_buffer(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, task::Poll};
+
+    use futures::{Sink as _, SinkExt};
+
+    use crate::test::sink::*;
+    use crate::{
+        mpsc,
+        sink::{SendError, Sink},
+    };
+
+    use super::FuturesSink;
+
+    #[test]
+    fn send_ready() {
+        let mut std_cx = futures_test::task::noop_context();
+        let mut sink = FuturesSink::new(ready::<usize>());
+
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            Pin::new(&mut sink).poll_ready(&mut std_cx)
+        );
+        assert_eq!(Ok(()), Pin::new(&mut sink).start_send(1));
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            Pin::new(&mut sink).poll_flush(&mut std_cx)
+        );
+    }
+
+    #[test]
+    fn send_pending() {
+        let mut std_cx = futures_test::task::noop_context();
+        let mut sink = FuturesSink::new(pending::<usize>());
+
+        assert_eq!(Ok(()), Pin::new(&mut sink).start_send(1));
+        assert_eq!(Poll::Pending, Pin::new(&mut sink).poll_flush(&mut std_cx));
+        assert_eq!(Poll::Pending, Pin::new(&mut sink).poll_ready(&mut std_cx));
+    }
+
+    #[test]
+    fn send_rejected() {
+        let mut std_cx = futures_test::task::noop_context();
+        let mut sink = FuturesSink::new(rejected::<usize>());
+
+        assert_eq!(Ok(()), Pin::new(&mut sink).start_send(1));
+        assert_eq!(
+            Poll::Ready(Err(SendError(1))),
+            Pin::new(&mut sink).poll_flush(&mut std_cx)
+        );
+    }
+
+    #[tokio::test]
+    async fn send_all() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut source = futures::stream::iter(vec![Ok(1usize), Ok(2), Ok(3)]);
+
+        let mut sink = tx.into_futures_sink();
+        sink.send_all(&mut source).await.expect("send_all failed");
+        drop(sink);
+
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+        assert_eq!(Ok(3), rx.try_recv());
+    }
+}
@@ -0,0 +1,102 @@
+use core::pin::Pin;
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct MapErrSink<MapErr, Into> {
+    map_err: MapErr,
+    #[pin]
+    into: Into,
+}
+
+impl<MapErr, Into> MapErrSink<MapErr, Into>
+where
+    Into: Sink,
+    MapErr: FnMut(Into::Item) -> Into::Item,
+{
+    pub fn new(map_err: MapErr, into: Into) -> Self {
+        Self { map_err, into }
+    }
+}
+
+impl<MapErr, Into> Sink for MapErrSink<MapErr, Into>
+where
+    Into: Sink,
+    MapErr: FnMut(Into::Item) -> Into::Item,
+{
+    type Item = Into::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        match this.into.poll_send(cx, value) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(value) => PollSend::Pending(value),
+            PollSend::Rejected(value) => PollSend::Rejected((this.map_err)(value)),
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::MapErrSink;
+
+    #[test]
+    fn map_rejected() {
+        let source = rejected::<usize>();
+        let mut map_err = MapErrSink::new(|i| i + 10, source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(11),
+            Pin::new(&mut map_err).poll_send(&mut cx, 1)
+        );
+    }
+
+    #[test]
+    fn forward_ready() {
+        let mut test_sink = test_sink(vec![PollSend::Ready]);
+        let mut map_err = MapErrSink::new(|i| i + 10, &mut test_sink);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut map_err).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(&[1], test_sink.values());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut map_err = MapErrSink::new(|i| i + 10, source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(1),
+            Pin::new(&mut map_err).poll_send(&mut cx, 1)
+        );
+    }
+}
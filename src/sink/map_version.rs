@@ -0,0 +1,125 @@
+use core::{marker::PhantomData, pin::Pin};
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct MapVersionSink<Into, From> {
+    #[pin]
+    into: Into,
+    from: PhantomData<From>,
+}
+
+impl<Into, From> MapVersionSink<Into, From>
+where
+    Into: Sink,
+    for<'a> &'a From: core::convert::Into<Into::Item>,
+{
+    pub fn new(into: Into) -> Self {
+        Self {
+            into,
+            from: PhantomData,
+        }
+    }
+}
+
+impl<Into, From> Sink for MapVersionSink<Into, From>
+where
+    Into: Sink,
+    for<'a> &'a From: core::convert::Into<Into::Item>,
+{
+    type Item = From;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        // the message is converted by reference, so it can be returned if the sink is full or closed
+        match this.into.poll_send(cx, (&value).into()) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(_) => PollSend::Pending(value),
+            PollSend::Rejected(_) => PollSend::Rejected(value),
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::MapVersionSink;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum V1 {
+        Ping,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum V2 {
+        Ping { id: usize },
+    }
+
+    impl From<&V2> for V1 {
+        fn from(v2: &V2) -> Self {
+            match v2 {
+                V2::Ping { .. } => V1::Ping,
+            }
+        }
+    }
+
+    #[test]
+    fn downgrade() {
+        let mut test_sink = test_sink(vec![PollSend::Ready]);
+        let mut sink = MapVersionSink::<_, V2>::new(&mut test_sink);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut sink).poll_send(&mut cx, V2::Ping { id: 1 })
+        );
+
+        assert_eq!(&[V1::Ping], test_sink.values());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<V1>();
+        let mut sink = MapVersionSink::<_, V2>::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending(V2::Ping { id: 1 }),
+            Pin::new(&mut sink).poll_send(&mut cx, V2::Ping { id: 1 })
+        );
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = rejected::<V1>();
+        let mut sink = MapVersionSink::<_, V2>::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected(V2::Ping { id: 1 }),
+            Pin::new(&mut sink).poll_send(&mut cx, V2::Ping { id: 1 })
+        );
+    }
+}
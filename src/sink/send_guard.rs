@@ -0,0 +1,270 @@
+use core::{future::Future, pin::Pin, task::Poll};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, OnceLock},
+    task::{Wake, Waker},
+};
+
+use crate::sink::{PollSend, SendError, Sink, TrySendError};
+
+/// A send future which guarantees delivery, even if it is dropped.  Created by `Sink::send_guard`.
+///
+/// If the guard is dropped before the message is accepted (for example, if the task is cancelled),
+/// the message is sent by a shared background thread.  Delivery is best-effort: the message is only lost if the sink is closed.
+///
+/// Messages which are delivered in the background can be reordered.  They may arrive after messages which were sent later,
+/// and messages from several dropped guards may arrive in any order.
+///
+/// Requires the `blocking` feature (enabled by default).
+#[must_use = "futures do nothing unless polled"]
+pub struct SendGuard<S>
+where
+    S: Sink + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+    sink: Option<S>,
+    value: Option<S::Item>,
+}
+
+impl<S> SendGuard<S>
+where
+    S: Sink + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+    /// Creates a guard which sends the value to the sink.  `Sink::send_guard` creates one with a clone of the sink.
+    pub fn new(sink: S, value: S::Item) -> Self {
+        Self {
+            sink: Some(sink),
+            value: Some(value),
+        }
+    }
+}
+
+// the value is never pinned
+impl<S> Unpin for SendGuard<S>
+where
+    S: Sink + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+}
+
+impl<S> Future for SendGuard<S>
+where
+    S: Sink + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+    type Output = Result<(), SendError<S::Item>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let value = match this.value.take() {
+            Some(value) => value,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        let sink = this
+            .sink
+            .as_mut()
+            .expect("the sink is present until the guard is dropped");
+
+        let mut cx: crate::Context<'_> = cx.into();
+        match Pin::new(sink).poll_send(&mut cx, value) {
+            PollSend::Ready => Poll::Ready(Ok(())),
+            PollSend::Pending(value) => {
+                this.value = Some(value);
+                Poll::Pending
+            }
+            PollSend::Rejected(value) => Poll::Ready(Err(SendError(value))),
+        }
+    }
+}
+
+impl<S> Drop for SendGuard<S>
+where
+    S: Sink + Unpin + Send + 'static,
+    S::Item: Send + 'static,
+{
+    fn drop(&mut self) {
+        let (mut sink, value) = match (self.sink.take(), self.value.take()) {
+            (Some(sink), Some(value)) => (sink, value),
+            _ => return,
+        };
+
+        let value = match sink.try_send(value) {
+            Err(TrySendError::Pending(value)) => value,
+            Ok(()) | Err(TrySendError::Rejected(_)) => return,
+        };
+
+        if let Some(worker) = worker() {
+            worker.schedule(Arc::new(Delivery {
+                future: Mutex::new(Some(Box::pin(async move {
+                    sink.send(value).await.ok();
+                }))),
+            }));
+        }
+    }
+}
+
+// A single background thread which delivers the messages of dropped guards.
+// The thread is started lazily, the first time a guard is dropped before its message is accepted.
+// Each delivery is polled when its sink wakes it, so a full sink does not delay deliveries to other sinks.
+
+struct Worker {
+    ready: Mutex<VecDeque<Arc<Delivery>>>,
+    condvar: Condvar,
+}
+
+struct Delivery {
+    future: Mutex<Option<Pin<Box<dyn Future<Output = ()> + Send>>>>,
+}
+
+impl Wake for Delivery {
+    fn wake(self: Arc<Self>) {
+        if let Some(worker) = worker() {
+            worker.schedule(self);
+        }
+    }
+}
+
+fn worker() -> Option<&'static Worker> {
+    static WORKER: OnceLock<Option<&'static Worker>> = OnceLock::new();
+
+    *WORKER.get_or_init(|| {
+        let worker: &'static Worker = Box::leak(Box::new(Worker {
+            ready: Mutex::new(VecDeque::new()),
+            condvar: Condvar::new(),
+        }));
+
+        let spawn = std::thread::Builder::new()
+            .name("postage-send-guard".to_string())
+            .spawn(move || worker.run());
+
+        match spawn {
+            Ok(_) => Some(worker),
+            Err(_e) => {
+                #[cfg(feature = "logging")]
+                log::error!("Failed to spawn the SendGuard delivery thread: {}", _e);
+                None
+            }
+        }
+    })
+}
+
+impl Worker {
+    fn schedule(&self, delivery: Arc<Delivery>) {
+        self.ready.lock().unwrap().push_back(delivery);
+        self.condvar.notify_one();
+    }
+
+    fn run(&self) {
+        loop {
+            let delivery = {
+                let mut ready = self.ready.lock().unwrap();
+                loop {
+                    match ready.pop_front() {
+                        Some(delivery) => break delivery,
+                        None => ready = self.condvar.wait(ready).unwrap(),
+                    }
+                }
+            };
+
+            // a delivery which was woken more than once may already be complete
+            let waker = Waker::from(delivery.clone());
+            let mut cx = core::task::Context::from_waker(&waker);
+            let mut future = delivery.future.lock().unwrap();
+            if let Some(poll) = future.as_mut().map(|future| future.as_mut().poll(&mut cx)) {
+                if poll.is_ready() {
+                    *future = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, task::Poll};
+
+    use crate::sink::{SendError, Sink};
+    use futures_test::task::noop_context;
+
+    use super::SendGuard;
+
+    #[test]
+    fn send_ready() {
+        let (tx, mut rx) = crate::mpsc::channel(1);
+
+        let mut guard = SendGuard::new(tx, 1usize);
+        assert_eq!(
+            Poll::Ready(Ok(())),
+            std::pin::Pin::new(&mut guard).poll(&mut noop_context())
+        );
+        drop(guard);
+
+        assert_eq!(Some(1), rx.blocking_recv());
+        assert_eq!(None, rx.blocking_recv());
+    }
+
+    #[test]
+    fn drop_delivers() {
+        let (mut tx, mut rx) = crate::mpsc::channel(1);
+        assert_eq!(Ok(()), tx.try_send(1usize));
+
+        let mut guard = tx.send_guard(2usize);
+        assert_eq!(
+            Poll::Pending,
+            std::pin::Pin::new(&mut guard).poll(&mut noop_context())
+        );
+        drop(guard);
+        drop(tx);
+
+        assert_eq!(Some(1), rx.blocking_recv());
+        assert_eq!(Some(2), rx.blocking_recv());
+        assert_eq!(None, rx.blocking_recv());
+    }
+
+    #[test]
+    fn drop_unpolled_delivers() {
+        let (tx, mut rx) = crate::mpsc::channel(1);
+
+        drop(tx.send_guard(1usize));
+        drop(tx);
+
+        assert_eq!(Some(1), rx.blocking_recv());
+    }
+
+    #[test]
+    fn full_sink_does_not_delay_other_deliveries() {
+        let (mut tx1, mut rx1) = crate::mpsc::channel(1);
+        let (mut tx2, mut rx2) = crate::mpsc::channel(1);
+        assert_eq!(Ok(()), tx1.try_send(1usize));
+        assert_eq!(Ok(()), tx2.try_send(1usize));
+
+        drop(tx1.send_guard(2));
+        drop(tx2.send_guard(2));
+        drop(tx2);
+
+        // the first channel stays full while the second is drained
+        assert_eq!(Some(1), rx2.blocking_recv());
+        assert_eq!(Some(2), rx2.blocking_recv());
+        assert_eq!(None, rx2.blocking_recv());
+
+        drop(tx1);
+        assert_eq!(Some(1), rx1.blocking_recv());
+        assert_eq!(Some(2), rx1.blocking_recv());
+        assert_eq!(None, rx1.blocking_recv());
+    }
+
+    #[test]
+    fn rejected() {
+        let (tx, rx) = crate::mpsc::channel(1);
+        drop(rx);
+
+        let mut guard = tx.send_guard(1usize);
+        assert_eq!(
+            Poll::Ready(Err(SendError(1))),
+            std::pin::Pin::new(&mut guard).poll(&mut noop_context())
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use crate::sink::{PollSend, Sink};
+use alloc::format;
+use core::{fmt::Debug, pin::Pin};
 use log::log_enabled;
 use pin_project::pin_project;
-use std::{fmt::Debug, pin::Pin};
 
 use crate::Context;
 #[pin_project]
@@ -17,7 +18,7 @@ where
     S: Sink,
 {
     pub fn new(sink: S, level: log::Level) -> Self {
-        let type_name = std::any::type_name::<S::Item>();
+        let type_name = core::any::type_name::<S::Item>();
         SinkLog {
             sink,
             type_name,
@@ -58,6 +59,10 @@ where
             PollSend::Rejected(v) => PollSend::Rejected(v),
         }
     }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().sink.cancel_send();
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,110 @@
+use core::{marker::PhantomData, pin::Pin};
+
+use crate::Context;
+
+use crate::sink::{PollSend, Sink};
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct WithSink<With, Into, From> {
+    with: With,
+    #[pin]
+    into: Into,
+    from: PhantomData<From>,
+}
+
+impl<With, Into, From> WithSink<With, Into, From>
+where
+    Into: Sink,
+    With: FnMut(&From) -> Into::Item,
+{
+    pub fn new(with: With, into: Into) -> Self {
+        Self {
+            with,
+            into,
+            from: PhantomData,
+        }
+    }
+}
+
+impl<With, Into, From> Sink for WithSink<With, Into, From>
+where
+    Into: Sink,
+    With: FnMut(&From) -> Into::Item,
+{
+    type Item = From;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+
+        // the original message is kept, so it can be returned if the sink is full or closed
+        match this.into.poll_send(cx, (this.with)(&value)) {
+            PollSend::Ready => PollSend::Ready,
+            PollSend::Pending(_) => PollSend::Pending(value),
+            PollSend::Rejected(_) => PollSend::Rejected(value),
+        }
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().into.cancel_send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::sink::*;
+    use crate::{
+        sink::{PollSend, Sink},
+        Context,
+    };
+
+    use super::WithSink;
+
+    #[test]
+    fn simple() {
+        let mut test_sink = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let mut with = WithSink::new(|s: &&str| s.len(), &mut test_sink);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollSend::Ready, Pin::new(&mut with).poll_send(&mut cx, "a"));
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut with).poll_send(&mut cx, "abc")
+        );
+
+        assert_eq!(&[1, 3], test_sink.values());
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut with = WithSink::new(|s: &&str| s.len(), source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Pending("a"),
+            Pin::new(&mut with).poll_send(&mut cx, "a")
+        );
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = rejected::<usize>();
+        let mut with = WithSink::new(|s: &&str| s.len(), source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollSend::Rejected("a"),
+            Pin::new(&mut with).poll_send(&mut cx, "a")
+        );
+    }
+}
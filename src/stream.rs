@@ -35,30 +35,70 @@
 //!     }
 //! }
 //! ```
-use std::{future::Future, marker::PhantomPinned, ops::DerefMut, pin::Pin};
+use alloc::vec::Vec;
+use core::{future::Future, ops::DerefMut, pin::Pin, time::Duration};
 
-use crate::Context;
+use crate::{sink::Sink, time::Timer, Context};
+use core::task::Poll;
 use pin_project::pin_project;
-use std::task::Poll;
 
 use self::{
-    chain::ChainStream, filter::FilterStream, find::FindStream, map::MapStream, merge::MergeStream,
-    once::OnceStream, repeat::RepeatStream,
+    chain::ChainStream, filter::FilterStream, filter_map::FilterMapStream, find::FindStream,
+    flat_map::FlatMapStream, map::MapStream, map_version::MapVersionStream, merge::MergeStream,
+    once::OnceStream, repeat::RepeatStream, skip_while::SkipWhileStream,
+    take_until::TakeUntilStream,
 };
 
+#[cfg(feature = "timer")]
+mod batch;
+#[cfg(feature = "blocking")]
+mod blocking_iter;
+#[cfg(feature = "std")]
+mod catch_unwind;
 mod chain;
+mod debounce;
+mod dyn_receiver;
 mod errors;
+mod fanout;
 mod filter;
+mod filter_map;
 mod find;
+mod flat_map;
+mod forward;
+mod fuse;
+#[cfg(feature = "futures-traits")]
+mod futures_stream;
 mod map;
+mod map_version;
 mod merge;
+mod merge_all;
 mod once;
+#[cfg(feature = "timer")]
+mod rate_limit;
 mod repeat;
+#[cfg(feature = "std")]
+mod report;
+mod share;
+mod skip_while;
+mod take_until;
+mod throttle;
 
 #[cfg(feature = "logging")]
 mod stream_log;
 
+#[cfg(feature = "timer")]
+pub use batch::BatchConfig;
+pub use dyn_receiver::DynReceiver;
 pub use errors::*;
+pub use fanout::{FanoutFuture, FanoutPolicy};
+pub use forward::ForwardFuture;
+pub use fuse::FuseStream;
+pub use merge_all::{MergeAll, StreamKey};
+#[cfg(feature = "timer")]
+pub use rate_limit::RateLimitConfig;
+#[cfg(feature = "std")]
+pub use report::{ClosedReport, ReportFuture};
+pub use share::SharedStream;
 
 /// An asynchronous stream, which produces a series of messages until closed.
 ///
@@ -116,6 +156,11 @@ pub use errors::*;
 ///     assert_eq!(Err(TryRecvError::Closed), rx.try_recv());
 /// }
 /// ```
+///
+/// # Cancellation
+/// `cancel_recv` tells the stream that a task has stopped waiting for a message.  It has a default implementation,
+/// so existing `Stream` implementations compile unchanged.  Implementations which wrap another stream should forward it,
+/// or a receive which is cancelled through the wrapper keeps its turn on a fair channel, such as `mpmc`.
 #[must_use = "streams do nothing unless polled"]
 pub trait Stream {
     type Item;
@@ -125,9 +170,59 @@ pub trait Stream {
     /// Returns:
     /// - `PollRecv::Ready(value)` if a message is ready
     /// - `PollRecv::Pending` if the stream is open, but no message is currently available.
-    /// - `PollRecv::Closed` if the stream is closed, and no messages are expected.
+    /// - `PollRecv::Closed(reason)` if the stream is closed, and no messages are expected.
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item>;
 
+    /// Attempts to retrieve up to `limit` items from the stream, without blocking.  Items are appended to `buffer`.
+    ///
+    /// Returns:
+    /// - `PollRecv::Ready(count)` if one or more messages were received.  If `limit` is zero, returns `Ready(0)`.
+    /// - `PollRecv::Pending` if the stream is open, but no message is currently available.
+    /// - `PollRecv::Closed(reason)` if the stream is closed, and no messages are expected.
+    ///
+    /// The default implementation calls `poll_recv` repeatedly.  Channels override it, to receive a batch with a single wakeup.
+    fn poll_recv_many(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Self::Item>,
+        limit: usize,
+    ) -> PollRecv<usize> {
+        let mut received = 0;
+
+        while received < limit {
+            match self.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    buffer.push(value);
+                    received += 1;
+                }
+                PollRecv::Pending => break,
+                PollRecv::Closed(reason) => {
+                    if received == 0 {
+                        return PollRecv::Closed(reason);
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        if received == 0 && limit > 0 {
+            return PollRecv::Pending;
+        }
+
+        PollRecv::Ready(received)
+    }
+
+    /// Called when a task stops waiting for a message, after `poll_recv` returned `Pending`.
+    ///
+    /// Channels which assign messages to waiting receivers (such as dispatch with `Strategy::RoundRobin`)
+    /// use it to give up the receiver's turn.  The default implementation does nothing.
+    ///
+    /// The futures returned by `recv`, `recv_many`, `latest`, `recv_timeout`, `into_recv_owned`, and `forward` call it
+    /// when they are dropped before completing, or time out.  Combinators forward it to the streams they wrap.
+    /// Code which polls `poll_recv` directly, and then stops polling, should call it.
+    fn cancel_recv(self: Pin<&mut Self>) {}
+
     /// Retrieves a message from the stream.
     ///
     /// Returns:
@@ -140,6 +235,74 @@ pub trait Stream {
         RecvFuture::new(self)
     }
 
+    /// Retrieves a message from the stream, with a `'static` future which owns the stream.
+    ///
+    /// The future resolves to the message (or `None` if the stream is closed), and returns the stream,
+    /// so it can be spawned as a task, or stored in a `FuturesUnordered`, without borrowing the receiver.
+    fn into_recv_owned(self) -> RecvOwnedFuture<Self>
+    where
+        Self: Sized + Unpin,
+    {
+        RecvOwnedFuture::new(self)
+    }
+
+    /// Retrieves a message from a clone of the stream, with a `'static` future which owns the clone.
+    ///
+    /// On a dispatch channel, each clone takes a different message, so a task can be spawned for each message
+    /// without wrapping the receiver in a Mutex.  A clone of a broadcast receiver observes the same messages as the original.
+    fn recv_owned(&self) -> RecvOwnedFuture<Self>
+    where
+        Self: Clone + Unpin,
+    {
+        RecvOwnedFuture::new(self.clone())
+    }
+
+    /// Waits for at least one message, and then receives all the messages which are ready, up to `limit`.
+    /// Messages are appended to `buffer`.
+    ///
+    /// Returns the number of messages received, or zero if the stream is closed (or `limit` is zero).
+    ///
+    /// This improves throughput, as channels can receive a batch of messages with a single wakeup.
+    fn recv_many<'s>(
+        &'s mut self,
+        buffer: &'s mut Vec<Self::Item>,
+        limit: usize,
+    ) -> RecvManyFuture<'s, Self>
+    where
+        Self: Unpin,
+    {
+        RecvManyFuture::new(self, buffer, limit)
+    }
+
+    /// Waits for at least one message, and then drains every message which is ready, returning only the newest.
+    ///
+    /// This gives 'latest state' semantics over any stream, such as an mpsc receiver, without converting the producer to a watch channel.
+    /// Returns `None` if the stream is closed, and no messages are ready.
+    fn latest(&mut self) -> LatestFuture<'_, Self>
+    where
+        Self: Unpin,
+    {
+        LatestFuture::new(self)
+    }
+
+    /// Retrieves a message from the stream, giving up once `timeout` has elapsed.  The deadline is measured by `timer`.
+    ///
+    /// Returns:
+    /// - `Ok(value)` if a message was received.
+    /// - `Err(RecvTimeoutError::Timeout)` if no message was received before the deadline.
+    /// - `Err(RecvTimeoutError::Closed)` if the stream is closed.
+    fn recv_timeout<T>(
+        &mut self,
+        timeout: Duration,
+        timer: T,
+    ) -> RecvTimeoutFuture<'_, Self, T::Sleep>
+    where
+        Self: Unpin,
+        T: Timer,
+    {
+        RecvTimeoutFuture::new(self, timer.sleep(timeout))
+    }
+
     /// Attempts to retrive a message from the stream, without blocking.
     ///
     /// Returns:
@@ -155,7 +318,7 @@ pub trait Stream {
         match pin.poll_recv(&mut Context::empty()) {
             PollRecv::Ready(value) => Ok(value),
             PollRecv::Pending => Err(TryRecvError::Pending),
-            PollRecv::Closed => Err(TryRecvError::Closed),
+            PollRecv::Closed(_) => Err(TryRecvError::Closed),
         }
     }
 
@@ -169,7 +332,31 @@ pub trait Stream {
     where
         Self: Unpin,
     {
-        pollster::block_on(self.recv())
+        crate::blocking::block_on(self.recv())
+    }
+
+    /// Retrieves a message from the stream, blocking the current thread until one is available.
+    /// With `WakePriority::High`, the thread's scheduling priority is raised while it waits.
+    ///
+    /// Requires the `thread-priority` feature.
+    #[cfg(feature = "thread-priority")]
+    fn blocking_recv_with(&mut self, priority: crate::blocking::WakePriority) -> Option<Self::Item>
+    where
+        Self: Unpin,
+    {
+        crate::blocking::block_on_with(self.recv(), priority)
+    }
+
+    /// Converts the stream into an iterator, which blocks the current thread until each item is received.
+    /// The iterator ends when the stream is closed.
+    ///
+    /// This is useful for handing a stream to synchronous, iterator-based code.
+    #[cfg(feature = "blocking")]
+    fn into_blocking_iter(self) -> blocking_iter::BlockingIter<Self>
+    where
+        Self: Sized + Unpin,
+    {
+        blocking_iter::BlockingIter::new(self)
     }
 
     /// Transforms the stream with a map function.
@@ -181,6 +368,17 @@ pub trait Stream {
         MapStream::new(self, map)
     }
 
+    /// Upgrades messages to a newer version with `From`, as they are received.
+    ///
+    /// Paired with `Sink::map_version`, this lets producers and consumers migrate to a new message version independently.
+    fn map_version<Version>(self) -> MapVersionStream<Self, Version>
+    where
+        Self::Item: Into<Version>,
+        Self: Sized,
+    {
+        MapVersionStream::new(self)
+    }
+
     /// Filters messages returned by the stream, ignoring messages where `filter` returns false.
     fn filter<Filter>(self, filter: Filter) -> FilterStream<Self, Filter>
     where
@@ -218,6 +416,193 @@ pub trait Stream {
         FindStream::new(self, condition)
     }
 
+    /// Transforms and filters messages in one step, ignoring messages where `filter_map` returns `None`.
+    fn filter_map<FilterMap, Into>(self, filter_map: FilterMap) -> FilterMapStream<Self, FilterMap>
+    where
+        FilterMap: FnMut(Self::Item) -> Option<Into>,
+        Self: Sized,
+    {
+        FilterMapStream::new(self, filter_map)
+    }
+
+    /// Maps each message to a stream, and returns the values of each stream in turn.
+    /// The next message is received once the current stream is closed.
+    fn flat_map<FlatMap, Into>(self, flat_map: FlatMap) -> FlatMapStream<Self, FlatMap, Into>
+    where
+        FlatMap: FnMut(Self::Item) -> Into,
+        Into: Stream,
+        Self: Sized,
+    {
+        FlatMapStream::new(self, flat_map)
+    }
+
+    /// Returns messages until `signal` resolves, and then closes the stream.
+    /// This ties the lifetime of a receiver to a shutdown signal.
+    ///
+    /// The signal is checked before each message, so it takes priority over messages which are ready.
+    fn take_until<Signal>(self, signal: Signal) -> TakeUntilStream<Self, Signal>
+    where
+        Signal: Future,
+        Self: Sized,
+    {
+        TakeUntilStream::new(self, signal)
+    }
+
+    /// Returns a stream which stays closed once the wrapped stream has closed.
+    ///
+    /// The wrapped stream is not polled again after it returns `PollRecv::Closed`, which is useful when a stream is
+    /// selected from in a loop, and some branches may finish before others.  `FuseStream::is_terminated` reports when it has closed.
+    fn fuse(self) -> FuseStream<Self>
+    where
+        Self: Sized,
+    {
+        FuseStream::new(self)
+    }
+
+    /// Ignores messages while `condition` returns true.  Once it returns false, all following messages are returned.
+    fn skip_while<Condition>(self, condition: Condition) -> SkipWhileStream<Self, Condition>
+    where
+        Condition: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        SkipWhileStream::new(self, condition)
+    }
+
+    /// Catches panics raised while polling the stream, such as panics in `map` or `filter` closures.
+    ///
+    /// Messages are produced as `Ok(value)`, and panics are produced as `Err(payload)`.
+    /// The stream remains open after a panic, and the next item can be received.
+    ///
+    /// Note: the stream is polled with `AssertUnwindSafe`.  Combinators which panic while holding state
+    /// may observe that state on the next poll.
+    ///
+    /// Requires the `std` feature
+    #[cfg(feature = "std")]
+    fn catch_unwind(self) -> catch_unwind::CatchUnwindStream<Self>
+    where
+        Self: Sized,
+    {
+        catch_unwind::CatchUnwindStream::new(self)
+    }
+
+    /// Forwards messages from the stream into the sink, waiting for the sink to accept each message.
+    ///
+    /// Resolves to `Ok(())` when the stream is closed, or `Err(SendError(value))` with the undelivered message if the sink is closed.
+    fn forward<K>(self, sink: K) -> ForwardFuture<Self, K>
+    where
+        K: Sink<Item = Self::Item>,
+        Self: Sized,
+    {
+        ForwardFuture::new(self, sink)
+    }
+
+    /// Drains the stream, and resolves to a `ClosedReport` when it closes.
+    ///
+    /// The report includes the number of items, the time since the future was first polled, and the close reason.
+    /// Items are discarded, so work is done in an earlier stage such as `map`.  The report's `Display` impl is
+    /// a one-line summary, which gives consumer loops a standard way to log their lifetime.
+    ///
+    /// Requires the `std` feature
+    #[cfg(feature = "std")]
+    fn until_closed_report(self) -> ReportFuture<Self>
+    where
+        Self: Sized,
+    {
+        ReportFuture::new(self)
+    }
+
+    /// Distributes messages from the stream across the sinks, round-robin.  This shards a single stream across worker channels.
+    ///
+    /// With `FanoutPolicy::Block`, each message waits for the next sink in the rotation.
+    /// With `FanoutPolicy::Skip`, a full sink is passed over, and the message goes to the next sink with capacity.
+    ///
+    /// Closed sinks are removed from the rotation.
+    /// Resolves to `Ok(())` when the stream is closed, or `Err(SendError(value))` with the undelivered message once every sink is closed.
+    fn fanout_ordered<K>(self, sinks: Vec<K>, policy: FanoutPolicy) -> FanoutFuture<Self, K>
+    where
+        K: Sink<Item = Self::Item> + Unpin,
+        Self: Sized,
+    {
+        FanoutFuture::new(self, sinks, policy)
+    }
+
+    /// Wraps the stream, so it can be cloned and received from concurrently by multiple tasks.
+    /// Each item is delivered to exactly one clone, which turns any stream into a shared work queue.
+    fn share(self) -> SharedStream<Self>
+    where
+        Self: Sized + Unpin,
+    {
+        SharedStream::new(self)
+    }
+
+    /// Groups messages into batches.  A batch is produced when it contains `max_items` messages,
+    /// or when `max_latency` has elapsed since its first message was received.
+    ///
+    /// The limits are read from the watch channel, and changes take effect on the next poll.
+    /// When the stream is closed, any buffered messages are produced as a final batch.
+    ///
+    /// Requires the `timer` feature
+    #[cfg(feature = "timer")]
+    fn min_batch_latency(
+        self,
+        config: crate::watch::Receiver<BatchConfig>,
+    ) -> batch::BatchStream<Self>
+    where
+        Self: Sized,
+    {
+        batch::BatchStream::new(self, config)
+    }
+
+    /// Limits the rate of the stream with a token bucket.  Up to `burst` items are produced at once,
+    /// and one more item is allowed each time `interval` elapses.
+    ///
+    /// The limits are read from the watch channel, and changes take effect on the next poll.
+    /// The stream is woken when the config changes, so a raised limit releases a waiting stream immediately.
+    ///
+    /// Requires the `timer` feature
+    #[cfg(feature = "timer")]
+    fn rate_limit(
+        self,
+        config: crate::watch::Receiver<RateLimitConfig>,
+    ) -> rate_limit::RateLimitStream<Self>
+    where
+        Self: Sized,
+    {
+        rate_limit::RateLimitStream::new(self, config)
+    }
+
+    /// Produces at most one message per `interval`, measured by `timer`.
+    ///
+    /// A message is produced immediately if the previous message was at least `interval` ago.  Messages which arrive
+    /// within the interval are drained, and the latest is produced when the interval elapses.  Intermediate messages are discarded.
+    fn throttle<T>(self, interval: Duration, timer: T) -> throttle::ThrottleStream<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        throttle::ThrottleStream::new(self, interval, timer)
+    }
+
+    /// Waits until the stream has been quiet for `duration`, measured by `timer`, and then produces the latest message.
+    ///
+    /// Each message restarts the quiet period, so a burst of messages (such as config changes on a watch channel)
+    /// produces only the last value of the burst.  If the stream closes during a burst, the last value is produced immediately.
+    fn debounce<T>(self, duration: Duration, timer: T) -> debounce::DebounceStream<Self, T>
+    where
+        T: Timer,
+        Self: Sized,
+    {
+        debounce::DebounceStream::new(self, duration, timer)
+    }
+
+    /// Boxes the stream into a `DynReceiver`, which hides the concrete channel or combinator type.
+    fn into_dyn(self) -> DynReceiver<Self::Item>
+    where
+        Self: Sized + Send + 'static,
+    {
+        DynReceiver::new(self)
+    }
+
     /// Logs messages that are produced by the stream using the Debug trait, at the provided log level.
     ///
     /// Requires the `logging` feature
@@ -225,10 +610,21 @@ pub trait Stream {
     fn log(self, level: log::Level) -> stream_log::StreamLog<Self>
     where
         Self: Sized,
-        Self::Item: std::fmt::Debug,
+        Self::Item: core::fmt::Debug,
     {
         stream_log::StreamLog::new(self, level)
     }
+
+    /// Adapts the stream into a `futures::Stream`, which can be used with the `futures::StreamExt` combinators.
+    ///
+    /// Requires the `futures-traits` feature
+    #[cfg(feature = "futures-traits")]
+    fn into_futures_stream(self) -> futures_stream::FuturesStream<Self>
+    where
+        Self: Sized,
+    {
+        futures_stream::FuturesStream::new(self)
+    }
 }
 
 impl<S> Stream for &mut S
@@ -240,6 +636,19 @@ where
     fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
         S::poll_recv(Pin::new(&mut **self), cx)
     }
+
+    fn poll_recv_many(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Self::Item>,
+        limit: usize,
+    ) -> PollRecv<usize> {
+        S::poll_recv_many(Pin::new(&mut **self), cx, buffer, limit)
+    }
+
+    fn cancel_recv(mut self: Pin<&mut Self>) {
+        S::cancel_recv(Pin::new(&mut **self))
+    }
 }
 
 impl<P, S> Stream for Pin<P>
@@ -252,6 +661,54 @@ where
     fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
         Pin::get_mut(self).as_mut().poll_recv(cx)
     }
+
+    fn poll_recv_many(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Self::Item>,
+        limit: usize,
+    ) -> PollRecv<usize> {
+        Pin::get_mut(self)
+            .as_mut()
+            .poll_recv_many(cx, buffer, limit)
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        Pin::get_mut(self).as_mut().cancel_recv()
+    }
+}
+
+/// An optional stream.  `None` is closed, which is useful for optional pipeline stages.
+impl<S> Stream for Option<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        match self.as_pin_mut() {
+            Some(stream) => stream.poll_recv(cx),
+            None => PollRecv::Closed(ClosedReason::Closed),
+        }
+    }
+
+    fn poll_recv_many(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Self::Item>,
+        limit: usize,
+    ) -> PollRecv<usize> {
+        match self.as_pin_mut() {
+            Some(stream) => stream.poll_recv_many(cx, buffer, limit),
+            None => PollRecv::Closed(ClosedReason::Closed),
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        if let Some(stream) = self.as_pin_mut() {
+            stream.cancel_recv();
+        }
+    }
 }
 
 /// Returns a stream which produces a single value, and then is closed.
@@ -259,6 +716,26 @@ pub fn once<T>(item: T) -> OnceStream<T> {
     OnceStream::new(item)
 }
 
+/// Forwards messages from `rx` into `tx`, until either side is closed.  Equivalent to `rx.forward(tx)`.
+///
+/// This is useful when connecting channels, such as a mpsc receiver to a broadcast sender.
+pub fn pipe<S, K>(rx: S, tx: K) -> ForwardFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item>,
+{
+    ForwardFuture::new(rx, tx)
+}
+
+/// Returns a stream which merges a dynamic set of streams.  Streams can be added with `MergeAll::insert`, and removed with `MergeAll::remove`.
+pub fn select_all<I>(streams: I) -> MergeAll<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Stream + Unpin,
+{
+    streams.into_iter().collect()
+}
+
 /// Returns a stream which infiniately produces a clonable value.
 pub fn repeat<T>(item: T) -> RepeatStream<T>
 where
@@ -275,21 +752,37 @@ pub enum PollRecv<T> {
     /// The channel is open, but no messages are ready and the receiver has registered with the waker context
     Pending,
     /// The channel is closed, and no messages will ever be delivered
+    Closed(ClosedReason),
+}
+
+/// The reason a stream was closed, carried by `PollRecv::Closed`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClosedReason {
+    /// All the senders were dropped
+    SendersDropped,
+    /// The stream was closed explicitly, or completed on its own (such as `stream::once` after producing its item)
     Closed,
+    /// The last sender was dropped while its thread was panicking
+    Poisoned,
 }
 
 /// A future returned by `Stream::recv`.
-#[pin_project]
+///
+/// The future is `Unpin`, so it can be stored in a struct, and polled with `Pin::new(&mut future)` without boxing.
+/// It is cancel-safe: if it is dropped before it completes, no message is lost.
+#[pin_project(PinnedDrop)]
 #[must_use = "futures do nothing unless polled"]
 pub struct RecvFuture<'s, S>
 where
     S: Stream + ?Sized,
 {
     recv: &'s mut S,
-    #[pin]
-    _pin: PhantomPinned,
+    terminated: bool,
+    cancel: Option<fn(&mut S)>,
 }
 
+static_assertions::assert_impl_all!(RecvFuture<'static, crate::broadcast::Receiver<usize>>: Unpin, Send);
+
 impl<'s, S: Stream> RecvFuture<'s, S>
 where
     S: ?Sized,
@@ -297,9 +790,15 @@ where
     pub fn new(recv: &'s mut S) -> RecvFuture<'s, S> {
         Self {
             recv,
-            _pin: PhantomPinned,
+            terminated: false,
+            cancel: None,
         }
     }
+
+    /// Returns true if the future has completed, and should not be polled again.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
 }
 
 impl<'s, S> Future for RecvFuture<'s, S>
@@ -308,20 +807,520 @@ where
 {
     type Output = Option<S::Item>;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
         let this = self.project();
 
         let mut cx: crate::Context<'_> = cx.into();
-        match Pin::new(this.recv).poll_recv(&mut cx) {
+        let poll = match Pin::new(&mut **this.recv).poll_recv(&mut cx) {
             PollRecv::Ready(v) => Poll::Ready(Some(v)),
             PollRecv::Pending => Poll::Pending,
-            PollRecv::Closed => Poll::Ready(None),
+            PollRecv::Closed(_) => Poll::Ready(None),
+        };
+
+        *this.terminated = poll.is_ready();
+        *this.cancel = pending_cancel::<S>(poll.is_pending());
+        poll
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S> PinnedDrop for RecvFuture<'s, S>
+where
+    S: Stream + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.recv);
+        }
+    }
+}
+
+// the cancel_recv call for a stream which is waiting.  futures store it when the stream returns `Pending`,
+// as the `Unpin` bound which is required to pin the stream is only known when the future is polled
+fn pending_cancel<S>(pending: bool) -> Option<fn(&mut S)>
+where
+    S: Stream + Unpin + ?Sized,
+{
+    fn cancel<S: Stream + Unpin + ?Sized>(stream: &mut S) {
+        Pin::new(stream).cancel_recv();
+    }
+
+    pending.then_some(cancel::<S>)
+}
+
+/// A future returned by `Stream::into_recv_owned` and `Stream::recv_owned`.
+///
+/// The future owns the stream, and returns it with the message, so it can be spawned or stored without borrowing.
+/// Like `RecvFuture`, it is `Unpin`, and cancel-safe.  Panics if polled after it has completed.
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvOwnedFuture<S> {
+    recv: Option<S>,
+    cancel: Option<fn(&mut S)>,
+}
+
+static_assertions::assert_impl_all!(RecvOwnedFuture<crate::broadcast::Receiver<usize>>: Unpin, Send);
+
+impl<S: Stream> RecvOwnedFuture<S> {
+    pub fn new(recv: S) -> RecvOwnedFuture<S> {
+        Self {
+            recv: Some(recv),
+            cancel: None,
+        }
+    }
+
+    /// Returns true if the future has completed, and should not be polled again.
+    pub fn is_terminated(&self) -> bool {
+        self.recv.is_none()
+    }
+}
+
+impl<S> Future for RecvOwnedFuture<S>
+where
+    S: Stream + Unpin,
+{
+    type Output = (Option<S::Item>, S);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let recv = this
+            .recv
+            .as_mut()
+            .expect("RecvOwnedFuture polled after completion");
+
+        let mut cx: crate::Context<'_> = cx.into();
+        let value = match Pin::new(recv).poll_recv(&mut cx) {
+            PollRecv::Ready(v) => Some(v),
+            PollRecv::Pending => {
+                this.cancel = pending_cancel::<S>(true);
+                return Poll::Pending;
+            }
+            PollRecv::Closed(_) => None,
+        };
+
+        this.cancel = None;
+        Poll::Ready((value, this.recv.take().unwrap()))
+    }
+}
+
+impl<S> Drop for RecvOwnedFuture<S> {
+    fn drop(&mut self) {
+        if let (Some(cancel), Some(recv)) = (self.cancel.take(), self.recv.as_mut()) {
+            cancel(recv);
+        }
+    }
+}
+
+/// A future returned by `Stream::recv_many`.  Like `RecvFuture`, it is `Unpin`, and cancel-safe.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvManyFuture<'s, S>
+where
+    S: Stream + ?Sized,
+{
+    recv: &'s mut S,
+    buffer: &'s mut Vec<S::Item>,
+    limit: usize,
+    cancel: Option<fn(&mut S)>,
+}
+
+impl<'s, S: Stream> RecvManyFuture<'s, S>
+where
+    S: ?Sized,
+{
+    pub fn new(recv: &'s mut S, buffer: &'s mut Vec<S::Item>, limit: usize) -> Self {
+        Self {
+            recv,
+            buffer,
+            limit,
+            cancel: None,
+        }
+    }
+}
+
+impl<'s, S> Future for RecvManyFuture<'s, S>
+where
+    S: Stream + Unpin + ?Sized,
+{
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut cx: crate::Context<'_> = cx.into();
+        let poll =
+            match Pin::new(&mut **this.recv).poll_recv_many(&mut cx, this.buffer, *this.limit) {
+                PollRecv::Ready(count) => Poll::Ready(count),
+                PollRecv::Pending => Poll::Pending,
+                PollRecv::Closed(_) => Poll::Ready(0),
+            };
+
+        *this.cancel = pending_cancel::<S>(poll.is_pending());
+        poll
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S> PinnedDrop for RecvManyFuture<'s, S>
+where
+    S: Stream + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.recv);
+        }
+    }
+}
+
+/// A future returned by `Stream::latest`.  Like `RecvFuture`, it is `Unpin`, and cancel-safe.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct LatestFuture<'s, S>
+where
+    S: Stream + ?Sized,
+{
+    recv: &'s mut S,
+    cancel: Option<fn(&mut S)>,
+}
+
+impl<'s, S: Stream> LatestFuture<'s, S>
+where
+    S: ?Sized,
+{
+    pub fn new(recv: &'s mut S) -> Self {
+        Self { recv, cancel: None }
+    }
+}
+
+impl<'s, S> Future for LatestFuture<'s, S>
+where
+    S: Stream + Unpin + ?Sized,
+{
+    type Output = Option<S::Item>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut cx: crate::Context<'_> = cx.into();
+        let mut latest = match Pin::new(&mut **this.recv).poll_recv(&mut cx) {
+            PollRecv::Ready(value) => value,
+            PollRecv::Pending => {
+                *this.cancel = pending_cancel::<S>(true);
+                return Poll::Pending;
+            }
+            PollRecv::Closed(_) => return Poll::Ready(None),
+        };
+
+        *this.cancel = None;
+
+        // the remaining messages are drained without a waker, as the future completes with the newest
+        let mut empty = crate::Context::empty();
+        while let PollRecv::Ready(value) = Pin::new(&mut **this.recv).poll_recv(&mut empty) {
+            latest = value;
+        }
+
+        Poll::Ready(Some(latest))
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S> PinnedDrop for LatestFuture<'s, S>
+where
+    S: Stream + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.recv);
+        }
+    }
+}
+
+/// A future returned by `Stream::recv_timeout`.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvTimeoutFuture<'s, S, Sleep>
+where
+    S: Stream + ?Sized,
+{
+    recv: &'s mut S,
+    #[pin]
+    sleep: Sleep,
+    cancel: Option<fn(&mut S)>,
+}
+
+impl<'s, S, Sleep> RecvTimeoutFuture<'s, S, Sleep>
+where
+    S: Stream + ?Sized,
+{
+    pub fn new(recv: &'s mut S, sleep: Sleep) -> Self {
+        Self {
+            recv,
+            sleep,
+            cancel: None,
+        }
+    }
+}
+
+impl<'s, S, Sleep> Future for RecvTimeoutFuture<'s, S, Sleep>
+where
+    S: Stream + Unpin + ?Sized,
+    Sleep: Future<Output = ()>,
+{
+    type Output = Result<S::Item, RecvTimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let mut postage_cx: crate::Context<'_> = cx.into();
+        match Pin::new(&mut **this.recv).poll_recv(&mut postage_cx) {
+            PollRecv::Ready(value) => {
+                *this.cancel = None;
+                Poll::Ready(Ok(value))
+            }
+            PollRecv::Pending => {
+                if this.sleep.poll(cx).is_ready() {
+                    // the receive is abandoned
+                    *this.cancel = None;
+                    Pin::new(&mut **this.recv).cancel_recv();
+                    return Poll::Ready(Err(RecvTimeoutError::Timeout));
+                }
+
+                *this.cancel = pending_cancel::<S>(true);
+                Poll::Pending
+            }
+            PollRecv::Closed(_) => {
+                *this.cancel = None;
+                Poll::Ready(Err(RecvTimeoutError::Closed))
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<'s, S, Sleep> PinnedDrop for RecvTimeoutFuture<'s, S, Sleep>
+where
+    S: Stream + ?Sized,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some(cancel) = this.cancel.take() {
+            cancel(this.recv);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::pin::Pin;
+
+    use super::{PollRecv, Stream};
+    use crate::test::stream::*;
+    use crate::Context;
+
+    #[test]
+    fn option() {
+        use super::ClosedReason;
+
+        let mut cx = Context::empty();
+
+        let mut some = Some(from_iter(vec![1]));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut some).poll_recv(&mut cx));
+
+        let mut none: Option<crate::mpsc::Receiver<usize>> = None;
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut none).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut none).poll_recv_many(&mut cx, &mut Vec::new(), 1)
+        );
+    }
+
+    #[test]
+    fn poll_recv_many() {
+        let mut stream = from_iter(vec![1, 2, 3]);
+        let mut buffer = Vec::new();
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(2),
+            Pin::new(&mut stream).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut stream).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+        assert_eq!(vec![1, 2, 3], buffer);
+        assert_eq!(
+            PollRecv::Closed(crate::stream::ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+    }
+
+    #[test]
+    fn recv_timeout() {
+        use super::RecvTimeoutError;
+        use crate::test::timer::{ExpiredTimer, NeverTimer};
+        use std::{future::Future, task::Poll, time::Duration};
+
+        let mut cx = futures_test::task::noop_context();
+        let timeout = Duration::from_secs(1);
+
+        let mut stream = from_iter(vec![1usize]);
+        {
+            let future = stream.recv_timeout(timeout, ExpiredTimer);
+            futures::pin_mut!(future);
+            assert_eq!(Poll::Ready(Ok(1)), future.poll(&mut cx));
+        }
+
+        {
+            let future = stream.recv_timeout(timeout, NeverTimer);
+            futures::pin_mut!(future);
+            assert_eq!(
+                Poll::Ready(Err(RecvTimeoutError::Closed)),
+                future.poll(&mut cx)
+            );
+        }
+
+        let mut stream = pending::<usize>();
+        {
+            let future = stream.recv_timeout(timeout, NeverTimer);
+            futures::pin_mut!(future);
+            assert_eq!(Poll::Pending, future.poll(&mut cx));
+        }
+
+        {
+            let future = stream.recv_timeout(timeout, ExpiredTimer);
+            futures::pin_mut!(future);
+            assert_eq!(
+                Poll::Ready(Err(RecvTimeoutError::Timeout)),
+                future.poll(&mut cx)
+            );
+        }
+    }
+
+    #[test]
+    fn poll_recv_many_pending() {
+        let mut stream = pending::<usize>();
+        let mut buffer = Vec::new();
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut stream).poll_recv_many(&mut cx, &mut buffer, 2)
+        );
+        assert_eq!(
+            PollRecv::Ready(0),
+            Pin::new(&mut stream).poll_recv_many(&mut cx, &mut buffer, 0)
+        );
+    }
+
+    #[test]
+    fn latest() {
+        use std::{future::Future, task::Poll};
+
+        let mut cx = futures_test::task::noop_context();
+
+        let mut stream = from_iter(vec![1, 2, 3]);
+        let mut future = stream.latest();
+        assert_eq!(Poll::Ready(Some(3)), Pin::new(&mut future).poll(&mut cx));
+        drop(future);
+
+        let mut future = stream.latest();
+        assert_eq!(Poll::Ready(None), Pin::new(&mut future).poll(&mut cx));
+
+        let mut stream = pending::<usize>();
+        let mut future = stream.latest();
+        assert_eq!(Poll::Pending, Pin::new(&mut future).poll(&mut cx));
+    }
+
+    #[test]
+    fn latest_channel() {
+        use std::{future::Future, task::Poll};
+
+        let mut cx = futures_test::task::noop_context();
+        let (mut tx, mut rx) = crate::mpsc::channel(4);
+
+        assert_eq!(Poll::Pending, Pin::new(&mut rx.latest()).poll(&mut cx));
+
+        for i in 1..=3usize {
+            assert_eq!(Ok(()), tx.try_send(i));
+        }
+        assert_eq!(
+            Poll::Ready(Some(3)),
+            Pin::new(&mut rx.latest()).poll(&mut cx)
+        );
+        assert_eq!(Poll::Pending, Pin::new(&mut rx.latest()).poll(&mut cx));
+
+        // messages which arrive after the drain are observed by the next call
+        assert_eq!(Ok(()), tx.try_send(4));
+        drop(tx);
+        assert_eq!(
+            Poll::Ready(Some(4)),
+            Pin::new(&mut rx.latest()).poll(&mut cx)
+        );
+        assert_eq!(Poll::Ready(None), Pin::new(&mut rx.latest()).poll(&mut cx));
+    }
+
+    #[test]
+    fn recv_future_stored() {
+        use super::RecvFuture;
+        use std::{future::Future, task::Poll};
+
+        // a manual state machine, which stores the named future without boxing or pinning
+        struct Next<'s> {
+            recv: RecvFuture<'s, crate::broadcast::Receiver<usize>>,
+        }
+
+        impl<'s> Future for Next<'s> {
+            type Output = Option<usize>;
+
+            fn poll(
+                mut self: Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> Poll<Self::Output> {
+                Pin::new(&mut self.recv).poll(cx)
+            }
+        }
+
+        let mut cx = futures_test::task::noop_context();
+        let (mut tx, mut rx) = crate::broadcast::channel(4);
+
+        let mut next = Next { recv: rx.recv() };
+        assert_eq!(Poll::Pending, Pin::new(&mut next).poll(&mut cx));
+
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert_eq!(Poll::Ready(Some(1)), Pin::new(&mut next).poll(&mut cx));
+    }
+
+    #[test]
+    fn into_recv_owned() {
+        use std::{future::Future, task::Poll};
+
+        let mut cx = futures_test::task::noop_context();
+        let (mut tx, rx) = crate::mpsc::channel(4);
+
+        let mut recv = rx.into_recv_owned();
+        assert!(Pin::new(&mut recv).poll(&mut cx).is_pending());
+        assert!(!recv.is_terminated());
+
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        let rx = match Pin::new(&mut recv).poll(&mut cx) {
+            Poll::Ready((Some(1), rx)) => rx,
+            _ => panic!("expected the message and the receiver"),
+        };
+        assert!(recv.is_terminated());
+
+        drop(tx);
+        let mut recv = rx.into_recv_owned();
+        assert!(matches!(
+            Pin::new(&mut recv).poll(&mut cx),
+            Poll::Ready((None, _))
+        ));
+    }
 
     #[cfg(feature = "blocking")]
     #[test]
@@ -333,3 +1332,29 @@ mod tests {
         assert_eq!(Some(1usize), stream.blocking_recv());
     }
 }
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::task::spawn;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn recv_owned_spawn() {
+        let (mut tx, rx) = crate::dispatch::channel(4);
+
+        let tasks: Vec<_> = (0..3).map(|_| spawn(rx.recv_owned())).collect();
+        for i in 0..3usize {
+            tx.send(i).await.expect("send failed");
+        }
+
+        let mut received = Vec::new();
+        for task in tasks {
+            let (value, _rx) = task.await.expect("task failed");
+            received.push(value.expect("closed"));
+        }
+
+        received.sort_unstable();
+        assert_eq!(vec![0, 1, 2], received);
+    }
+}
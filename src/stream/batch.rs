@@ -0,0 +1,240 @@
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::stream::{ClosedReason, PollRecv, Stream};
+use crate::watch;
+use crate::Context;
+use pin_project::pin_project;
+
+/// Configures the batches produced by `Stream::min_batch_latency`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// A batch is produced as soon as it contains this many items.  A value of zero is treated as one.
+    pub max_items: usize,
+    /// A batch is produced once this much time has elapsed since its first item was received.
+    pub max_latency: Duration,
+}
+
+#[pin_project]
+pub struct BatchStream<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    config: watch::Receiver<BatchConfig>,
+    current: BatchConfig,
+    buffer: Vec<S::Item>,
+    first: Option<Instant>,
+    closed: Option<ClosedReason>,
+}
+
+impl<S> BatchStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S, config: watch::Receiver<BatchConfig>) -> Self {
+        let current = *config.borrow();
+
+        Self {
+            stream,
+            config,
+            current,
+            buffer: Vec::new(),
+            first: None,
+            closed: None,
+        }
+    }
+}
+
+impl<S> Stream for BatchStream<S>
+where
+    S: Stream,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // config changes are observed at the start of each poll.  the waker is registered,
+        // so a shorter latency can release a batch which is already buffered.
+        while let PollRecv::Ready(config) = Pin::new(&mut *this.config).poll_recv(cx) {
+            *this.current = config;
+        }
+
+        let max_items = this.current.max_items.max(1);
+        let mut stream = this.stream;
+
+        if this.closed.is_none() {
+            while this.buffer.len() < max_items {
+                match stream.as_mut().poll_recv(cx) {
+                    PollRecv::Ready(value) => {
+                        if this.buffer.is_empty() {
+                            *this.first = Some(crate::sync::timer::now());
+                        }
+
+                        this.buffer.push(value);
+                    }
+                    PollRecv::Pending => break,
+                    PollRecv::Closed(reason) => {
+                        *this.closed = Some(reason);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if this.buffer.is_empty() {
+            return match this.closed {
+                Some(reason) => PollRecv::Closed(*reason),
+                None => PollRecv::Pending,
+            };
+        }
+
+        let deadline =
+            this.first.expect("a buffered batch has a start time") + this.current.max_latency;
+
+        if this.buffer.len() >= max_items
+            || this.closed.is_some()
+            || deadline <= crate::sync::timer::now()
+        {
+            *this.first = None;
+            return PollRecv::Ready(std::mem::take(this.buffer));
+        }
+
+        if let Some(waker) = cx.waker() {
+            crate::sync::timer::wake_at(deadline, waker.clone());
+        }
+
+        PollRecv::Pending
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().stream.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        watch, Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{BatchConfig, BatchStream};
+
+    fn config(max_items: usize, max_latency: Duration) -> BatchConfig {
+        BatchConfig {
+            max_items,
+            max_latency,
+        }
+    }
+
+    #[test]
+    fn batch_by_size() {
+        let (_tx, rx) = watch::channel_with(config(2, Duration::from_secs(60)));
+        let mut stream = BatchStream::new(from_iter(vec![1, 2, 3]), rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2]),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Ready(vec![3]),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn batch_by_latency() {
+        let (_config_tx, config_rx) = watch::channel_with(config(10, Duration::from_millis(20)));
+        let (mut tx, rx) = crate::mpsc::channel(10);
+        let mut stream = BatchStream::new(rx, config_rx);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut cx: Context<'_> = w1_context.into();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(w1_count.get() >= 1);
+
+        assert_eq!(
+            PollRecv::Ready(vec![1]),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn config_update() {
+        let (mut config_tx, config_rx) = watch::channel_with(config(10, Duration::from_secs(60)));
+        let (mut tx, rx) = crate::mpsc::channel(10);
+        let mut stream = BatchStream::new(rx, config_rx);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut cx: Context<'_> = w1_context.into();
+
+        assert_eq!(Ok(()), tx.try_send(1));
+        assert_eq!(Ok(()), tx.try_send(2));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        let woken = w1_count.get();
+        assert_eq!(
+            Ok(()),
+            config_tx.try_send(config(2, Duration::from_secs(60)))
+        );
+        assert!(w1_count.get() > woken);
+
+        assert_eq!(
+            PollRecv::Ready(vec![1, 2]),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let (_tx, rx) = watch::channel_with(config(2, Duration::from_secs(60)));
+        let mut stream = BatchStream::new(pending::<usize>(), rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed_reason() {
+        let (_tx, rx) = watch::channel_with(config(2, Duration::from_secs(60)));
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Closed(ClosedReason::Poisoned),
+        ]);
+        let mut stream = BatchStream::new(source, rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(vec![1]),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
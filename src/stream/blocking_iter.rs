@@ -0,0 +1,91 @@
+use std::iter::FusedIterator;
+
+use crate::stream::Stream;
+
+/// An iterator which blocks the current thread until each item is received.  Ends when the stream is closed.
+///
+/// Created by `Stream::into_blocking_iter`.
+pub struct BlockingIter<S> {
+    stream: S,
+    closed: bool,
+}
+
+impl<S> BlockingIter<S>
+where
+    S: Stream + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            closed: false,
+        }
+    }
+}
+
+impl<S> Iterator for BlockingIter<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.closed {
+            return None;
+        }
+
+        let item = self.stream.blocking_recv();
+        self.closed = item.is_none();
+        item
+    }
+}
+
+impl<S> FusedIterator for BlockingIter<S> where S: Stream + Unpin {}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::test::stream::*;
+    use crate::{mpsc, stream::PollRecv};
+
+    use super::BlockingIter;
+
+    #[test]
+    fn collect() {
+        let iter = BlockingIter::new(from_iter(vec![1, 2, 3]));
+
+        assert_eq!(vec![1, 2, 3], iter.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn fused() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1),
+            PollRecv::Closed(crate::stream::ClosedReason::Closed),
+            PollRecv::Ready(2),
+        ]);
+        let mut iter = BlockingIter::new(source);
+
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn blocks_between_messages() {
+        let (mut tx, rx) = mpsc::channel(1);
+
+        let join = thread::spawn(move || {
+            for i in 0..10usize {
+                thread::sleep(Duration::from_millis(1));
+                tx.blocking_send(i).expect("send failed");
+            }
+        });
+
+        assert_eq!(
+            (0..10).collect::<Vec<_>>(),
+            BlockingIter::new(rx).collect::<Vec<_>>()
+        );
+        join.join().expect("sender thread panicked");
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct CatchUnwindStream<S> {
+    #[pin]
+    stream: S,
+}
+
+impl<S> CatchUnwindStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+}
+
+impl<S> Stream for CatchUnwindStream<S>
+where
+    S: Stream,
+{
+    type Item = Result<S::Item, Box<dyn Any + Send>>;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+        let stream = this.stream;
+
+        match catch_unwind(AssertUnwindSafe(move || stream.poll_recv(cx))) {
+            Ok(PollRecv::Ready(value)) => PollRecv::Ready(Ok(value)),
+            Ok(PollRecv::Pending) => PollRecv::Pending,
+            Ok(PollRecv::Closed(reason)) => PollRecv::Closed(reason),
+            Err(panic) => PollRecv::Ready(Err(panic)),
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().stream.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::CatchUnwindStream;
+
+    #[test]
+    fn catches_panic() {
+        let source = from_iter(vec![1, 2, 3]).map(|i| {
+            if i == 2 {
+                panic!("bad item");
+            }
+
+            i
+        });
+        let mut stream = CatchUnwindStream::new(source);
+
+        let mut cx = Context::empty();
+
+        match Pin::new(&mut stream).poll_recv(&mut cx) {
+            PollRecv::Ready(Ok(1)) => {}
+            _ => panic!("expected Ready(Ok(1))"),
+        }
+
+        match Pin::new(&mut stream).poll_recv(&mut cx) {
+            PollRecv::Ready(Err(panic)) => {
+                assert_eq!(Some(&"bad item"), panic.downcast_ref::<&str>())
+            }
+            _ => panic!("expected Ready(Err(_))"),
+        }
+
+        match Pin::new(&mut stream).poll_recv(&mut cx) {
+            PollRecv::Ready(Ok(3)) => {}
+            _ => panic!("expected Ready(Ok(3))"),
+        }
+
+        assert!(matches!(
+            Pin::new(&mut stream).poll_recv(&mut cx),
+            PollRecv::Closed(ClosedReason::Closed)
+        ));
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut stream = CatchUnwindStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert!(matches!(
+            Pin::new(&mut stream).poll_recv(&mut cx),
+            PollRecv::Pending
+        ));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut stream = CatchUnwindStream::new(source);
+
+        let mut cx = Context::empty();
+
+        assert!(matches!(
+            Pin::new(&mut stream).poll_recv(&mut cx),
+            PollRecv::Closed(ClosedReason::Closed)
+        ));
+    }
+}
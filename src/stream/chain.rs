@@ -1,8 +1,8 @@
-use std::pin::Pin;
+use core::pin::Pin;
 
 use atomic::{Atomic, Ordering};
 
-use crate::stream::{PollRecv, Stream};
+use crate::stream::{ClosedReason, PollRecv, Stream};
 use crate::Context;
 use pin_project::pin_project;
 
@@ -10,7 +10,7 @@ use pin_project::pin_project;
 enum State {
     Left,
     Right,
-    Closed,
+    Closed(ClosedReason),
 }
 
 #[pin_project]
@@ -51,7 +51,7 @@ where
             match this.left.poll_recv(cx) {
                 PollRecv::Ready(v) => return PollRecv::Ready(v),
                 PollRecv::Pending => return PollRecv::Pending,
-                PollRecv::Closed => {
+                PollRecv::Closed(_) => {
                     this.state.store(State::Right, Ordering::Release);
                     state = State::Right;
                 }
@@ -62,19 +62,25 @@ where
             match this.right.poll_recv(cx) {
                 PollRecv::Ready(v) => return PollRecv::Ready(v),
                 PollRecv::Pending => return PollRecv::Pending,
-                PollRecv::Closed => {
-                    this.state.store(State::Closed, Ordering::Release);
-                    return PollRecv::Closed;
+                PollRecv::Closed(reason) => {
+                    this.state.store(State::Closed(reason), Ordering::Release);
+                    return PollRecv::Closed(reason);
                 }
             }
         }
 
-        if let State::Closed = state {
-            return PollRecv::Closed;
+        if let State::Closed(reason) = state {
+            return PollRecv::Closed(reason);
         }
 
         unreachable!();
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.left.cancel_recv();
+        this.right.cancel_recv();
+    }
 }
 
 #[cfg(test)]
@@ -83,7 +89,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -100,7 +106,10 @@ mod tests {
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(3), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -113,19 +122,57 @@ mod tests {
 
         assert_eq!(PollRecv::Pending, Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forwards_right_reason() {
+        let left = from_poll_iter(vec![PollRecv::<usize>::Closed(
+            ClosedReason::SendersDropped,
+        )]);
+        let right = from_poll_iter(vec![PollRecv::Closed(ClosedReason::Poisoned)]);
+        let mut find = ChainStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
     fn ignores_after_close() {
-        let left = from_poll_iter(vec![PollRecv::Closed, PollRecv::Ready(1)]);
-        let right = from_poll_iter(vec![PollRecv::Closed, PollRecv::Ready(2)]);
+        let left = from_poll_iter(vec![
+            PollRecv::Closed(ClosedReason::Closed),
+            PollRecv::Ready(1),
+        ]);
+        let right = from_poll_iter(vec![
+            PollRecv::Closed(ClosedReason::Closed),
+            PollRecv::Ready(2),
+        ]);
         let mut find = ChainStream::new(left, right);
 
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,145 @@
+use core::{pin::Pin, time::Duration};
+
+use crate::stream::{PollRecv, Stream};
+use crate::time::{poll_sleep, Timer};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct DebounceStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    #[pin]
+    from: From,
+    #[pin]
+    sleep: Option<T::Sleep>,
+    timer: T,
+    duration: Duration,
+    latest: Option<From::Item>,
+}
+
+impl<From, T> DebounceStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    pub fn new(from: From, duration: Duration, timer: T) -> Self {
+        Self {
+            from,
+            sleep: None,
+            timer,
+            duration,
+            latest: None,
+        }
+    }
+}
+
+impl<From, T> Stream for DebounceStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    type Item = From::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        // each message restarts the quiet period
+        loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    *this.latest = Some(value);
+                    this.sleep.set(Some(this.timer.sleep(*this.duration)));
+                }
+                PollRecv::Pending => break,
+                PollRecv::Closed(reason) => {
+                    // the last message of the final burst is returned without waiting
+                    this.sleep.set(None);
+                    return match this.latest.take() {
+                        Some(value) => PollRecv::Ready(value),
+                        None => PollRecv::Closed(reason),
+                    };
+                }
+            }
+        }
+
+        if this.latest.is_some() && poll_sleep(this.sleep, cx) {
+            return PollRecv::Ready(this.latest.take().unwrap());
+        }
+
+        PollRecv::Pending
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::test::stream::*;
+    use crate::test::timer::{ExpiredTimer, NeverTimer};
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::DebounceStream;
+
+    #[test]
+    fn returns_latest_after_quiet_period() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+            PollRecv::Pending,
+        ]);
+        let mut stream = DebounceStream::new(source, Duration::from_millis(10), ExpiredTimer);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn waits_for_quiet_period() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Pending,
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+        ]);
+        let mut stream = DebounceStream::new(source, Duration::from_millis(10), NeverTimer);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // the pending message is flushed when the stream closes
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn pending_without_messages() {
+        let mut stream =
+            DebounceStream::new(pending::<usize>(), Duration::from_millis(10), ExpiredTimer);
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::boxed::Box;
+use core::{fmt, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+
+// Stream is not object-safe, as `recv` and the other futures reference `Self`.
+// This trait exposes only the poll and cancel functions, so the stream can be called through a vtable.
+trait PollStream<T>: Send {
+    fn poll_recv_dyn(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<T>;
+
+    fn cancel_recv_dyn(self: Pin<&mut Self>);
+}
+
+impl<S> PollStream<S::Item> for S
+where
+    S: Stream + Send,
+{
+    fn poll_recv_dyn(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<S::Item> {
+        self.poll_recv(cx)
+    }
+
+    fn cancel_recv_dyn(self: Pin<&mut Self>) {
+        self.cancel_recv()
+    }
+}
+
+/// A receiver which hides the type of the wrapped stream.  Created by `Stream::into_dyn` or `DynReceiver::new`.
+///
+/// Any channel receiver or stream combinator can be stored as a `DynReceiver<T>`, so endpoints can be passed between crates
+/// without exposing the channel kind.  The stream is boxed once, when the receiver is created.  Each poll is a virtual call,
+/// and does not allocate.
+pub struct DynReceiver<T> {
+    stream: Pin<Box<dyn PollStream<T>>>,
+}
+
+impl<T> DynReceiver<T> {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+    {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl<T> Stream for DynReceiver<T> {
+    type Item = T;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        self.get_mut().stream.as_mut().poll_recv_dyn(cx)
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.get_mut().stream.as_mut().cancel_recv_dyn();
+    }
+}
+
+impl<T> fmt::Debug for DynReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynReceiver").finish_non_exhaustive()
+    }
+}
+
+static_assertions::assert_impl_all!(DynReceiver<usize>: Send, Unpin);
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        broadcast, mpsc,
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::DynReceiver;
+
+    #[test]
+    fn channels() {
+        let (mut mpsc_tx, mpsc_rx) = mpsc::channel(4);
+        let (mut broadcast_tx, broadcast_rx) = broadcast::channel(4);
+
+        let mut receivers = [
+            DynReceiver::new(mpsc_rx),
+            DynReceiver::new(broadcast_rx.map(|value: usize| value * 10)),
+        ];
+
+        mpsc_tx.try_send(1usize).unwrap();
+        broadcast_tx.try_send(2usize).unwrap();
+
+        assert_eq!(Ok(1), receivers[0].try_recv());
+        assert_eq!(Ok(20), receivers[1].try_recv());
+    }
+
+    #[test]
+    fn closed() {
+        let mut stream = DynReceiver::new(from_iter(vec![1usize]));
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
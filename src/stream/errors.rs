@@ -10,3 +10,14 @@ pub enum TryRecvError {
     #[error("TryRecvError::Closed")]
     Closed,
 }
+
+/// An error type returned by `Stream::recv_timeout`, when the deadline elapses, or the stream is closed.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// The deadline elapsed before the stream produced an item
+    #[error("RecvTimeoutError::Timeout")]
+    Timeout,
+    /// The stream is closed, and will never produce an item
+    #[error("RecvTimeoutError::Closed")]
+    Closed,
+}
@@ -0,0 +1,362 @@
+use alloc::vec::Vec;
+use core::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, SendError, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// Determines what `Stream::fanout_ordered` does when the next sink in the rotation is full.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FanoutPolicy {
+    /// Waits for the next sink to accept the message.  Messages are distributed in strict rotation.
+    Block,
+    /// Offers the message to the following sinks, in order.  Waits only if every sink is full.
+    ///
+    /// Skipped sinks give up their send turns with `Sink::cancel_send`, so a fair channel such as `mpmc` serves its other senders.
+    Skip,
+}
+
+/// A future which distributes messages from a stream across a list of sinks, round-robin.  Created by `Stream::fanout_ordered`.
+///
+/// Sinks which are closed are removed from the rotation.
+/// Resolves to `Ok(())` when the stream is closed, or `Err(SendError(value))` once every sink has been closed.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct FanoutFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item> + Unpin,
+{
+    #[pin]
+    stream: S,
+    sinks: Vec<K>,
+    policy: FanoutPolicy,
+    next: usize,
+    buffered: Option<S::Item>,
+    // true while the stream is pending, so it can be cancelled if the future is dropped
+    receiving: bool,
+}
+
+impl<S, K> FanoutFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item> + Unpin,
+{
+    pub fn new(stream: S, sinks: Vec<K>, policy: FanoutPolicy) -> Self {
+        Self {
+            stream,
+            sinks,
+            policy,
+            next: 0,
+            buffered: None,
+            receiving: false,
+        }
+    }
+}
+
+impl<S, K> Future for FanoutFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item> + Unpin,
+{
+    type Output = Result<(), SendError<S::Item>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            // a message which no sink could yet accept is retried before the stream is polled
+            if let Some(mut value) = this.buffered.take() {
+                let start = *this.next;
+                let mut attempt = 0;
+                // sinks which were skipped while full.  they give up their send turns once the attempt is complete
+                let mut skipped = Vec::new();
+                let unsent = loop {
+                    if this.sinks.is_empty() {
+                        return Poll::Ready(Err(SendError(value)));
+                    }
+
+                    let attempts = match this.policy {
+                        FanoutPolicy::Block => 1,
+                        FanoutPolicy::Skip => this.sinks.len(),
+                    };
+
+                    if attempt >= attempts {
+                        break Some(value);
+                    }
+
+                    let index = *this.next % this.sinks.len();
+                    match Pin::new(&mut this.sinks[index]).poll_send(&mut cx, value) {
+                        PollSend::Ready => {
+                            *this.next = index + 1;
+                            break None;
+                        }
+                        PollSend::Pending(v) => {
+                            value = v;
+                            *this.next = index + 1;
+                            attempt += 1;
+
+                            if *this.policy == FanoutPolicy::Skip {
+                                skipped.push(index);
+                            }
+                        }
+                        PollSend::Rejected(v) => {
+                            // the rotation continues at the sink which took this one's place
+                            value = v;
+                            this.sinks.remove(index);
+                            *this.next = index;
+
+                            skipped.retain(|skipped| *skipped != index);
+                            skipped
+                                .iter_mut()
+                                .filter(|skipped| **skipped > index)
+                                .for_each(|skipped| *skipped -= 1);
+                        }
+                    }
+                };
+
+                for index in skipped {
+                    Pin::new(&mut this.sinks[index]).cancel_send();
+                }
+
+                if let Some(value) = unsent {
+                    // keep the message at the sink which was tried first
+                    *this.next = start;
+                    *this.buffered = Some(value);
+                    return Poll::Pending;
+                }
+            }
+
+            let poll = this.stream.as_mut().poll_recv(&mut cx);
+            *this.receiving = matches!(poll, PollRecv::Pending);
+            match poll {
+                PollRecv::Ready(value) => *this.buffered = Some(value),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed(_) => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<S, K> PinnedDrop for FanoutFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item> + Unpin,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if *this.receiving {
+            this.stream.cancel_recv();
+        }
+
+        // a buffered message is waiting for a sink
+        if this.buffered.is_some() {
+            for sink in this.sinks.iter_mut() {
+                Pin::new(sink).cancel_send();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, task::Poll};
+
+    use crate::{
+        sink::{PollSend, SendError},
+        stream::Stream,
+        test::{sink::test_sink, stream::*},
+    };
+    use futures_test::task::noop_context;
+
+    use super::{FanoutFuture, FanoutPolicy};
+
+    #[test]
+    fn round_robin() {
+        let (tx1, mut rx1) = crate::mpsc::channel(4);
+        let (tx2, mut rx2) = crate::mpsc::channel(4);
+        let future = FanoutFuture::new(
+            from_iter(vec![1usize, 2, 3, 4, 5]),
+            vec![tx1, tx2],
+            FanoutPolicy::Block,
+        );
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx1.try_recv());
+        assert_eq!(Ok(3), rx1.try_recv());
+        assert_eq!(Ok(5), rx1.try_recv());
+        assert_eq!(Ok(2), rx2.try_recv());
+        assert_eq!(Ok(4), rx2.try_recv());
+    }
+
+    #[test]
+    fn block_waits_for_next_sink() {
+        let first = test_sink(vec![PollSend::Ready, PollSend::Ready]);
+        let second = test_sink(vec![PollSend::Pending(2usize), PollSend::Ready]);
+        let future = FanoutFuture::new(
+            from_iter(vec![1usize, 2, 3]),
+            vec![first, second],
+            FanoutPolicy::Block,
+        );
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut noop_context()));
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+    }
+
+    #[test]
+    fn skip_full_sink() {
+        let (tx1, mut rx1) = crate::mpsc::channel(1);
+        let (tx2, mut rx2) = crate::mpsc::channel(4);
+        let future = FanoutFuture::new(
+            from_iter(vec![1usize, 2, 3, 4]),
+            vec![tx1, tx2],
+            FanoutPolicy::Skip,
+        );
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx1.try_recv());
+        assert_eq!(Ok(2), rx2.try_recv());
+        assert_eq!(Ok(3), rx2.try_recv());
+        assert_eq!(Ok(4), rx2.try_recv());
+    }
+
+    #[test]
+    fn skip_pending_when_all_full() {
+        let (tx1, mut rx1) = crate::mpsc::channel(1);
+        let (tx2, _rx2) = crate::mpsc::channel(1);
+        let future = FanoutFuture::new(
+            from_iter(vec![1usize, 2, 3]),
+            vec![tx1, tx2],
+            FanoutPolicy::Skip,
+        );
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut noop_context()));
+
+        assert_eq!(Ok(1), rx1.try_recv());
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(3), rx1.try_recv());
+    }
+
+    #[test]
+    fn skip_releases_send_turn() {
+        let (tx1, mut rx1) = crate::mpmc::channel(1);
+        let (tx2, mut rx2) = crate::mpmc::channel(4);
+        let mut other = tx1.clone();
+
+        assert_eq!(Ok(()), other.try_send(0usize));
+
+        let future = FanoutFuture::new(from_iter(vec![1usize]), vec![tx1, tx2], FanoutPolicy::Skip);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx2.try_recv());
+
+        // the skipped channel has a free slot, which other senders can take
+        assert_eq!(Ok(0), rx1.try_recv());
+        assert_eq!(Ok(()), other.try_send(2));
+        assert_eq!(Ok(2), rx1.try_recv());
+    }
+
+    #[test]
+    fn skip_pending_releases_send_turns() {
+        let (tx1, mut rx1) = crate::mpmc::channel(1);
+        let (tx2, mut rx2) = crate::mpmc::channel(1);
+        let mut other1 = tx1.clone();
+        let mut other2 = tx2.clone();
+
+        assert_eq!(Ok(()), other1.try_send(0usize));
+        assert_eq!(Ok(()), other2.try_send(0usize));
+
+        let future = FanoutFuture::new(from_iter(vec![1usize]), vec![tx1, tx2], FanoutPolicy::Skip);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut noop_context()));
+
+        // every channel is full, and neither holds a turn for the fanout
+        assert_eq!(Ok(0), rx2.try_recv());
+        assert_eq!(Ok(()), other2.try_send(2));
+        assert_eq!(Ok(2), rx2.try_recv());
+
+        assert_eq!(Ok(0), rx1.try_recv());
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx1.try_recv());
+    }
+
+    #[test]
+    fn closed_sink_removed() {
+        let (tx1, rx1) = crate::mpsc::channel(4);
+        let (tx2, mut rx2) = crate::mpsc::channel(4);
+        drop(rx1);
+
+        let future = FanoutFuture::new(
+            from_iter(vec![1usize, 2]),
+            vec![tx1, tx2],
+            FanoutPolicy::Block,
+        );
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx2.try_recv());
+        assert_eq!(Ok(2), rx2.try_recv());
+    }
+
+    #[test]
+    fn all_sinks_closed() {
+        let (tx, rx) = crate::mpsc::channel(4);
+        drop(rx);
+
+        let future = FanoutFuture::new(from_iter(vec![1usize]), vec![tx], FanoutPolicy::Skip);
+        futures::pin_mut!(future);
+
+        assert_eq!(
+            Poll::Ready(Err(SendError(1))),
+            future.poll(&mut noop_context())
+        );
+    }
+
+    #[test]
+    fn stream_pending() {
+        let (tx, _rx) = crate::mpsc::channel(4);
+        let future = pending::<usize>().fanout_ordered(vec![tx], FanoutPolicy::Block);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.poll(&mut noop_context()));
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::task::spawn;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    use super::FanoutPolicy;
+
+    #[tokio::test]
+    async fn shard_workers() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let (tx1, mut rx1) = crate::mpsc::channel(4);
+        let (tx2, mut rx2) = crate::mpsc::channel(4);
+
+        let fanout = spawn(rx.fanout_ordered(vec![tx1, tx2], FanoutPolicy::Block));
+
+        for i in 0..100usize {
+            tx.send(i).await.expect("send failed");
+
+            let worker = if i % 2 == 0 { &mut rx1 } else { &mut rx2 };
+            assert_eq!(Some(i), worker.recv().await);
+        }
+
+        drop(tx);
+        assert_eq!(Ok(()), fanout.await.expect("join failed"));
+    }
+}
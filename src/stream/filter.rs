@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use core::pin::Pin;
 
 use crate::stream::{PollRecv, Stream};
 use crate::Context;
@@ -36,10 +36,14 @@ where
                     }
                 }
                 PollRecv::Pending => return PollRecv::Pending,
-                PollRecv::Closed => return PollRecv::Closed,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
             }
         }
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        Pin::new(&mut self.get_mut().from).cancel_recv();
+    }
 }
 
 #[cfg(test)]
@@ -48,7 +52,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -63,7 +67,10 @@ mod tests {
 
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(4), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -83,6 +90,9 @@ mod tests {
 
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,100 @@
+use core::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FilterMapStream<From, FilterMap> {
+    #[pin]
+    from: From,
+    filter_map: FilterMap,
+}
+
+impl<From, FilterMap, Into> FilterMapStream<From, FilterMap>
+where
+    From: Stream,
+    FilterMap: FnMut(From::Item) -> Option<Into>,
+{
+    pub fn new(from: From, filter_map: FilterMap) -> Self {
+        Self { from, filter_map }
+    }
+}
+
+impl<From, FilterMap, Into> Stream for FilterMapStream<From, FilterMap>
+where
+    From: Stream,
+    FilterMap: FnMut(From::Item) -> Option<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+        loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if let Some(value) = (this.filter_map)(value) {
+                        return PollRecv::Ready(value);
+                    }
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
+            }
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::FilterMapStream;
+
+    #[test]
+    fn filter_map() {
+        let source = from_iter(vec!["1", "a", "3"]);
+        let mut stream = FilterMapStream::new(source, |s: &str| s.parse::<usize>().ok());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut stream = FilterMapStream::new(source, Some);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut stream = FilterMapStream::new(source, Some);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
@@ -1,9 +1,9 @@
-use std::pin::Pin;
+use core::pin::Pin;
 
 use crate::Context;
 use atomic::{Atomic, Ordering};
 
-use crate::stream::{PollRecv, Stream};
+use crate::stream::{ClosedReason, PollRecv, Stream};
 
 #[derive(Copy, Clone)]
 enum State {
@@ -42,7 +42,7 @@ where
         let this = self.get_mut();
 
         if let State::Closed = this.state.load(Ordering::Acquire) {
-            return PollRecv::Closed;
+            return PollRecv::Closed(ClosedReason::Closed);
         }
 
         loop {
@@ -56,10 +56,14 @@ where
                     }
                 }
                 PollRecv::Pending => return PollRecv::Pending,
-                PollRecv::Closed => return PollRecv::Closed,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
             }
         }
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        Pin::new(&mut self.get_mut().from).cancel_recv();
+    }
 }
 
 #[cfg(test)]
@@ -68,7 +72,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -82,7 +86,10 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -92,7 +99,10 @@ mod tests {
 
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -103,7 +113,10 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -123,6 +136,9 @@ mod tests {
 
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,117 @@
+use core::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct FlatMapStream<From, FlatMap, Into> {
+    #[pin]
+    from: From,
+    flat_map: FlatMap,
+    #[pin]
+    current: Option<Into>,
+}
+
+impl<From, FlatMap, Into> FlatMapStream<From, FlatMap, Into>
+where
+    From: Stream,
+    FlatMap: FnMut(From::Item) -> Into,
+    Into: Stream,
+{
+    pub fn new(from: From, flat_map: FlatMap) -> Self {
+        Self {
+            from,
+            flat_map,
+            current: None,
+        }
+    }
+}
+
+impl<From, FlatMap, Into> Stream for FlatMapStream<From, FlatMap, Into>
+where
+    From: Stream,
+    FlatMap: FnMut(From::Item) -> Into,
+    Into: Stream,
+{
+    type Item = Into::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+        loop {
+            // the inner stream is drained before the next message is taken from the outer stream
+            if let Some(current) = this.current.as_mut().as_pin_mut() {
+                match current.poll_recv(cx) {
+                    PollRecv::Ready(value) => return PollRecv::Ready(value),
+                    PollRecv::Pending => return PollRecv::Pending,
+                    PollRecv::Closed(_) => this.current.set(None),
+                }
+            }
+
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => this.current.set(Some((this.flat_map)(value))),
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
+            }
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.from.cancel_recv();
+        this.current.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::FlatMapStream;
+
+    #[test]
+    fn flat_map() {
+        let source = from_iter(vec![1usize, 0, 2]);
+        let mut stream = FlatMapStream::new(source, |n| from_iter(vec![n; n]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn inner_pending() {
+        let source = from_iter(vec![1usize, 2]);
+        let mut stream = FlatMapStream::new(source, |_| pending::<usize>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut stream = FlatMapStream::new(source, |n| from_iter(vec![n]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use core::{future::Future, pin::Pin, task::Poll};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, SendError, Sink},
+    stream::{PollRecv, Stream},
+};
+
+/// A future which forwards messages from a stream into a sink.  Created by `Stream::forward` or `pipe`.
+///
+/// Resolves to `Ok(())` when the stream is closed, or `Err(SendError(value))` if the sink rejects a message.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ForwardFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item>,
+{
+    #[pin]
+    stream: S,
+    #[pin]
+    sink: K,
+    buffered: Option<S::Item>,
+    // true while the stream is pending, so it can be cancelled if the future is dropped
+    receiving: bool,
+}
+
+impl<S, K> ForwardFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item>,
+{
+    pub fn new(stream: S, sink: K) -> Self {
+        Self {
+            stream,
+            sink,
+            buffered: None,
+            receiving: false,
+        }
+    }
+}
+
+impl<S, K> Future for ForwardFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item>,
+{
+    type Output = Result<(), SendError<S::Item>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut cx: crate::Context<'_> = cx.into();
+
+        loop {
+            // a message which the sink could not yet accept is retried before the stream is polled
+            if let Some(value) = this.buffered.take() {
+                match this.sink.as_mut().poll_send(&mut cx, value) {
+                    PollSend::Ready => {}
+                    PollSend::Pending(value) => {
+                        *this.buffered = Some(value);
+                        return Poll::Pending;
+                    }
+                    PollSend::Rejected(value) => return Poll::Ready(Err(SendError(value))),
+                }
+            }
+
+            let poll = this.stream.as_mut().poll_recv(&mut cx);
+            *this.receiving = matches!(poll, PollRecv::Pending);
+            match poll {
+                PollRecv::Ready(value) => *this.buffered = Some(value),
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed(_) => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<S, K> PinnedDrop for ForwardFuture<S, K>
+where
+    S: Stream,
+    K: Sink<Item = S::Item>,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if *this.receiving {
+            this.stream.cancel_recv();
+        }
+
+        // a buffered message is waiting for a sink
+        if this.buffered.is_some() {
+            this.sink.cancel_send();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, task::Poll};
+
+    use crate::{
+        sink::{PollSend, SendError},
+        test::{sink::test_sink, stream::*},
+    };
+    use futures_test::task::noop_context;
+
+    use super::ForwardFuture;
+
+    #[test]
+    fn forward_until_stream_closed() {
+        let (tx, mut rx) = crate::mpsc::channel(4);
+        let future = ForwardFuture::new(from_iter(vec![1usize, 2]), tx);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+        assert_eq!(Ok(1), rx.try_recv());
+        assert_eq!(Ok(2), rx.try_recv());
+    }
+
+    #[test]
+    fn forward_backpressure() {
+        let sink = test_sink(vec![PollSend::Pending(1usize), PollSend::Ready]);
+        let future = ForwardFuture::new(from_iter(vec![1usize]), sink);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut noop_context()));
+        assert_eq!(Poll::Ready(Ok(())), future.poll(&mut noop_context()));
+    }
+
+    #[test]
+    fn forward_pending() {
+        let (tx, _rx) = crate::mpsc::channel(4);
+        let future = ForwardFuture::new(pending::<usize>(), tx);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.poll(&mut noop_context()));
+    }
+
+    #[test]
+    fn forward_rejected() {
+        let (tx, rx) = crate::mpsc::channel(4);
+        drop(rx);
+
+        let future = ForwardFuture::new(from_iter(vec![1usize, 2]), tx);
+        futures::pin_mut!(future);
+
+        assert_eq!(
+            Poll::Ready(Err(SendError(1))),
+            future.poll(&mut noop_context())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use tokio::task::spawn;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn pipe_mpsc_to_broadcast() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let (broadcast_tx, mut broadcast_rx) = crate::broadcast::channel(4);
+
+        let pipe = spawn(crate::pipe(rx, broadcast_tx));
+
+        for i in 0..100usize {
+            tx.send(i).await.expect("send failed");
+            assert_eq!(Some(i), broadcast_rx.recv().await);
+        }
+
+        drop(tx);
+        assert_eq!(Ok(()), pipe.await.expect("join failed"));
+        assert_eq!(None, broadcast_rx.recv().await);
+    }
+}
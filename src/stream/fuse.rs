@@ -0,0 +1,90 @@
+use core::pin::Pin;
+
+use crate::stream::{ClosedReason, PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+/// A stream which stays closed once it has returned `PollRecv::Closed`.  Created by `Stream::fuse`.
+#[pin_project]
+pub struct FuseStream<From> {
+    #[pin]
+    from: From,
+    closed: Option<ClosedReason>,
+}
+
+impl<From> FuseStream<From>
+where
+    From: Stream,
+{
+    pub fn new(from: From) -> Self {
+        Self { from, closed: None }
+    }
+
+    /// Returns true if the wrapped stream has been closed.  A terminated stream is not polled again.
+    pub fn is_terminated(&self) -> bool {
+        self.closed.is_some()
+    }
+}
+
+impl<From> Stream for FuseStream<From>
+where
+    From: Stream,
+{
+    type Item = From::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if let Some(reason) = this.closed {
+            return PollRecv::Closed(*reason);
+        }
+
+        let poll = this.from.poll_recv(cx);
+        if let PollRecv::Closed(reason) = poll {
+            *this.closed = Some(reason);
+        }
+
+        poll
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::FuseStream;
+
+    #[test]
+    fn stays_closed() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            PollRecv::Ready(2),
+        ]);
+        let mut stream = FuseStream::new(source);
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert!(!stream.is_terminated());
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert!(stream.is_terminated());
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
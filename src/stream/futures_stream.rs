@@ -0,0 +1,92 @@
+use core::{pin::Pin, task::Poll};
+
+use crate::stream::{PollRecv, Stream};
+use pin_project::pin_project;
+
+/// Adapts a postage stream into a `futures::Stream`.  Closed streams produce `None`.
+///
+/// `futures::Stream` has no notion of a cancelled poll, so a `next()` future which is dropped does not call `Stream::cancel_recv`.
+/// A dispatch receiver keeps its turn until the adapter is polled again or dropped.
+#[pin_project]
+pub struct FuturesStream<S> {
+    #[pin]
+    stream: S,
+}
+
+impl<S> FuturesStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+
+    /// Returns the wrapped postage stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> futures::stream::Stream for FuturesStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let mut cx = cx.into();
+
+        match this.stream.poll_recv(&mut cx) {
+            PollRecv::Ready(value) => Poll::Ready(Some(value)),
+            PollRecv::Pending => Poll::Pending,
+            PollRecv::Closed(_) => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use crate::test::stream::*;
+    use crate::{mpsc, stream::Stream};
+
+    use super::FuturesStream;
+
+    #[tokio::test]
+    async fn collect() {
+        let source = from_iter(vec![1, 2, 3]);
+        let stream = FuturesStream::new(source);
+
+        let values: Vec<usize> = stream.collect().await;
+        assert_eq!(vec![1, 2, 3], values);
+    }
+
+    #[tokio::test]
+    async fn combinators() {
+        let source = from_iter(vec![1usize, 2, 3]).map(|i| i * 2);
+
+        let values: Vec<usize> = source
+            .into_futures_stream()
+            .filter(|i| futures::future::ready(*i > 2))
+            .collect()
+            .await;
+        assert_eq!(vec![4, 6], values);
+    }
+
+    #[tokio::test]
+    async fn channel() {
+        let (mut tx, rx) = mpsc::channel(4);
+
+        tx.try_send(1usize).unwrap();
+        tx.try_send(2usize).unwrap();
+        drop(tx);
+
+        let values: Vec<usize> = rx.into_futures_stream().collect().await;
+        assert_eq!(vec![1, 2], values);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, pin::Pin};
+use core::{marker::PhantomData, pin::Pin};
 
 use crate::stream::{PollRecv, Stream};
 use crate::Context;
@@ -40,9 +40,13 @@ where
         match this.from.poll_recv(cx) {
             PollRecv::Ready(v) => PollRecv::Ready((this.map)(v)),
             PollRecv::Pending => PollRecv::Pending,
-            PollRecv::Closed => PollRecv::Closed,
+            PollRecv::Closed(reason) => PollRecv::Closed(reason),
         }
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
 }
 
 #[cfg(test)]
@@ -51,7 +55,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
     use std::convert::identity;
@@ -68,7 +72,10 @@ mod tests {
         assert_eq!(PollRecv::Ready(11), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(12), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(13), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -81,6 +88,19 @@ mod tests {
         assert_eq!(PollRecv::Pending, Pin::new(&mut find).poll_recv(&mut cx));
     }
 
+    #[test]
+    fn forward_closed_reason() {
+        let source = from_poll_iter(vec![PollRecv::Closed(ClosedReason::Poisoned)]);
+        let mut find = MapStream::new(source, |i: usize| i);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+    }
+
     #[test]
     fn forward_closed() {
         let source = closed::<usize>();
@@ -88,6 +108,9 @@ mod tests {
 
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,158 @@
+use core::{marker::PhantomData, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct MapVersionStream<From, Into> {
+    #[pin]
+    from: From,
+    into: PhantomData<Into>,
+}
+
+impl<From, Into> MapVersionStream<From, Into>
+where
+    From: Stream,
+    From::Item: core::convert::Into<Into>,
+{
+    pub fn new(from: From) -> Self {
+        Self {
+            from,
+            into: PhantomData,
+        }
+    }
+}
+
+impl<From, Into> Stream for MapVersionStream<From, Into>
+where
+    From: Stream,
+    From::Item: core::convert::Into<Into>,
+{
+    type Item = Into;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        match this.from.poll_recv(cx) {
+            PollRecv::Ready(v) => PollRecv::Ready(v.into()),
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed(reason) => PollRecv::Closed(reason),
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::MapVersionStream;
+
+    #[derive(Debug, PartialEq)]
+    enum V1 {
+        Ping,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum V2 {
+        Ping { id: usize },
+    }
+
+    impl From<V1> for V2 {
+        fn from(v1: V1) -> Self {
+            match v1 {
+                V1::Ping => V2::Ping { id: 0 },
+            }
+        }
+    }
+
+    #[test]
+    fn upgrade() {
+        let source = from_iter(vec![V1::Ping]);
+        let mut stream = MapVersionStream::<_, V2>::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Ready(V2::Ping { id: 0 }),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<V1>();
+        let mut stream = MapVersionStream::<_, V2>::new(source);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use crate::{sink::Sink, stream::Stream};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum V1 {
+        Start,
+        Stop,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum V2 {
+        Start { delay_ms: u64 },
+        Stop,
+    }
+
+    impl From<V1> for V2 {
+        fn from(v1: V1) -> Self {
+            match v1 {
+                V1::Start => V2::Start { delay_ms: 0 },
+                V1::Stop => V2::Stop,
+            }
+        }
+    }
+
+    impl From<&V2> for V1 {
+        fn from(v2: &V2) -> Self {
+            match v2 {
+                V2::Start { .. } => V1::Start,
+                V2::Stop => V1::Stop,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn migrate_independently() {
+        // the channel still carries V1, while the producer and the consumer have moved to V2
+        let (tx, rx) = crate::mpsc::channel::<V1>(4);
+        let mut tx = tx.map_version::<V2>();
+        let mut rx = rx.map_version::<V2>();
+
+        tx.send(V2::Start { delay_ms: 10 })
+            .await
+            .expect("send failed");
+        tx.send(V2::Stop).await.expect("send failed");
+        drop(tx);
+
+        assert_eq!(Some(V2::Start { delay_ms: 0 }), rx.recv().await);
+        assert_eq!(Some(V2::Stop), rx.recv().await);
+        assert_eq!(None, rx.recv().await);
+    }
+}
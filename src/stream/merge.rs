@@ -1,6 +1,6 @@
 use crate::stream::{PollRecv, Stream};
+use core::pin::Pin;
 use pin_project::pin_project;
-use std::pin::Pin;
 
 use crate::Context;
 #[derive(Copy, Clone)]
@@ -62,6 +62,12 @@ where
 
         poll.into_recv()
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        let this = self.project();
+        this.left.cancel_recv();
+        this.right.cancel_recv();
+    }
 }
 
 enum MergePoll<T> {
@@ -82,7 +88,7 @@ impl<T> MergePoll<T> {
             MergePoll::First(_) => true,
             MergePoll::Second(PollRecv::Ready(_)) => true,
             MergePoll::Second(PollRecv::Pending) => true,
-            MergePoll::Second(PollRecv::Closed) => false,
+            MergePoll::Second(PollRecv::Closed(_)) => false,
         }
     }
 }
@@ -95,7 +101,7 @@ where
     match first.poll_recv(cx) {
         PollRecv::Ready(v) => MergePoll::First(PollRecv::Ready(v)),
         PollRecv::Pending => MergePoll::Second(second.poll_recv(cx)),
-        PollRecv::Closed => MergePoll::Second(second.poll_recv(cx)),
+        PollRecv::Closed(_) => MergePoll::Second(second.poll_recv(cx)),
     }
 }
 
@@ -105,7 +111,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -123,8 +129,14 @@ mod tests {
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(3), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(4), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -138,7 +150,10 @@ mod tests {
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(3), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -151,12 +166,18 @@ mod tests {
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
     fn swap_closed() {
-        let left = from_poll_iter(vec![PollRecv::Closed, PollRecv::Closed]);
+        let left = from_poll_iter(vec![
+            PollRecv::Closed(ClosedReason::Closed),
+            PollRecv::Closed(ClosedReason::Closed),
+        ]);
         let right = from_poll_iter(vec![PollRecv::Ready(1), PollRecv::Ready(2)]);
         let mut find = MergeStream::new(left, right);
 
@@ -164,7 +185,26 @@ mod tests {
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
         assert_eq!(PollRecv::Ready(2), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forwards_last_reason() {
+        let left = from_poll_iter(vec![PollRecv::<usize>::Closed(
+            ClosedReason::SendersDropped,
+        )]);
+        let right = from_poll_iter(vec![PollRecv::Closed(ClosedReason::Poisoned)]);
+        let mut find = MergeStream::new(left, right);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Poisoned),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -176,7 +216,10 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 
     #[test]
@@ -188,6 +231,9 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut find).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut find).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut find).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,224 @@
+use alloc::vec::Vec;
+use core::{fmt, iter::FromIterator, pin::Pin};
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+
+/// Identifies a stream which was inserted into a `MergeAll`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StreamKey(usize);
+
+/// Merges a dynamic set of streams, which can be inserted and removed at runtime.
+///
+/// Streams are polled fairly, starting after the stream which produced the previous item.
+/// Closed streams are removed from the set.
+///
+/// While the set is empty, the stream is pending rather than closed, so new streams can be inserted later.
+///
+/// Created with `MergeAll::new()` or `stream::select_all(streams)`.
+pub struct MergeAll<S> {
+    streams: Vec<(StreamKey, S)>,
+    next_key: usize,
+    next_index: usize,
+}
+
+impl<S> MergeAll<S>
+where
+    S: Stream + Unpin,
+{
+    /// Creates an empty set of streams.
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+            next_key: 0,
+            next_index: 0,
+        }
+    }
+
+    /// Inserts a stream into the set, returning a key which can be used to remove it.
+    pub fn insert(&mut self, stream: S) -> StreamKey {
+        let key = StreamKey(self.next_key);
+        self.next_key += 1;
+        self.streams.push((key, stream));
+
+        key
+    }
+
+    /// Removes a stream from the set.  Returns `None` if the stream was already removed, or has closed.
+    pub fn remove(&mut self, key: StreamKey) -> Option<S> {
+        let index = self.streams.iter().position(|(k, _)| *k == key)?;
+        Some(self.streams.remove(index).1)
+    }
+
+    /// The number of streams in the set.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// True if the set contains no streams.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+}
+
+impl<S> Default for MergeAll<S>
+where
+    S: Stream + Unpin,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Extend<S> for MergeAll<S>
+where
+    S: Stream + Unpin,
+{
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for stream in iter {
+            self.insert(stream);
+        }
+    }
+}
+
+impl<S> FromIterator<S> for MergeAll<S>
+where
+    S: Stream + Unpin,
+{
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut merge = Self::new();
+        merge.extend(iter);
+        merge
+    }
+}
+
+impl<S> Stream for MergeAll<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+
+        let len = this.streams.len();
+        let start = if len > 0 { this.next_index % len } else { 0 };
+        let mut closed = Vec::new();
+        let mut ready = None;
+
+        for offset in 0..len {
+            let index = (start + offset) % len;
+
+            match Pin::new(&mut this.streams[index].1).poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    this.next_index = index + 1;
+                    ready = Some(value);
+                    break;
+                }
+                PollRecv::Pending => {}
+                PollRecv::Closed(_) => closed.push(index),
+            }
+        }
+
+        closed.sort_unstable();
+        for index in closed.into_iter().rev() {
+            this.streams.remove(index);
+        }
+
+        match ready {
+            Some(value) => PollRecv::Ready(value),
+            None => PollRecv::Pending,
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        for (_, stream) in self.get_mut().streams.iter_mut() {
+            Pin::new(stream).cancel_recv();
+        }
+    }
+}
+
+impl<S> fmt::Debug for MergeAll<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeAll")
+            .field("len", &self.streams.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{PollRecv, Stream},
+        Context,
+    };
+
+    use super::MergeAll;
+
+    #[test]
+    fn fair_merge() {
+        let mut merge = MergeAll::new();
+        merge.insert(from_iter(vec![1, 3]));
+        merge.insert(from_iter(vec![2, 4]));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(4), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert!(merge.is_empty());
+    }
+
+    #[test]
+    fn insert_remove() {
+        let (mut tx1, rx1) = crate::mpsc::channel(4);
+        let (mut tx2, rx2) = crate::mpsc::channel(4);
+
+        let mut merge = MergeAll::new();
+        let key1 = merge.insert(rx1);
+        let key2 = merge.insert(rx2);
+        assert_eq!(2, merge.len());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(Ok(()), tx1.try_send(1));
+        assert_eq!(Ok(()), tx2.try_send(2));
+
+        assert!(merge.remove(key1).is_some());
+        assert!(merge.remove(key1).is_none());
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+
+        drop(tx2);
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert!(merge.remove(key2).is_none());
+        assert!(merge.is_empty());
+    }
+
+    #[test]
+    fn pending_when_empty() {
+        let mut merge = MergeAll::<crate::mpsc::Receiver<usize>>::new();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_pending() {
+        let mut merge: MergeAll<_> = vec![pending::<usize>(), pending::<usize>()]
+            .into_iter()
+            .collect();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut merge).poll_recv(&mut cx));
+        assert_eq!(2, merge.len());
+    }
+}
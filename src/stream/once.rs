@@ -1,8 +1,8 @@
-use std::{cell::UnsafeCell, pin::Pin};
+use core::{cell::UnsafeCell, pin::Pin};
 
 use atomic::{Atomic, Ordering};
 
-use crate::stream::{PollRecv, Stream};
+use crate::stream::{ClosedReason, PollRecv, Stream};
 
 use crate::Context;
 #[derive(Copy, Clone)]
@@ -47,7 +47,7 @@ impl<T> Stream for OnceStream<T> {
             return PollRecv::Ready(value);
         }
 
-        PollRecv::Closed
+        PollRecv::Closed(ClosedReason::Closed)
     }
 }
 
@@ -56,7 +56,7 @@ mod tests {
     use std::pin::Pin;
 
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -66,6 +66,9 @@ mod tests {
         let mut cx = Context::empty();
 
         assert_eq!(PollRecv::Ready(1), Pin::new(&mut repeat).poll_recv(&mut cx));
-        assert_eq!(PollRecv::Closed, Pin::new(&mut repeat).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut repeat).poll_recv(&mut cx)
+        );
     }
 }
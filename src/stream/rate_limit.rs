@@ -0,0 +1,181 @@
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use crate::stream::{PollRecv, Stream};
+use crate::watch;
+use crate::Context;
+use pin_project::pin_project;
+
+/// Configures the token bucket used by `Stream::rate_limit`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The number of items which can be produced at once, after the stream has been idle.  A value of zero is treated as one.
+    pub burst: usize,
+    /// One item is allowed each time this much time elapses.  A zero interval disables the limit.
+    pub interval: Duration,
+}
+
+#[pin_project]
+pub struct RateLimitStream<S> {
+    #[pin]
+    stream: S,
+    config: watch::Receiver<RateLimitConfig>,
+    current: RateLimitConfig,
+    tokens: usize,
+    refilled: Instant,
+}
+
+impl<S> RateLimitStream<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S, config: watch::Receiver<RateLimitConfig>) -> Self {
+        let current = *config.borrow();
+
+        Self {
+            stream,
+            config,
+            current,
+            tokens: current.burst.max(1),
+            refilled: crate::sync::timer::now(),
+        }
+    }
+}
+
+impl<S> Stream for RateLimitStream<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        // config changes are observed at the start of each poll.  the waker is registered,
+        // so a higher limit can release a stream which is waiting for a token.
+        while let PollRecv::Ready(config) = Pin::new(&mut *this.config).poll_recv(cx) {
+            *this.current = config;
+        }
+
+        let burst = this.current.burst.max(1);
+        let interval = this.current.interval;
+
+        if interval.is_zero() {
+            return this.stream.poll_recv(cx);
+        }
+
+        let now = crate::sync::timer::now();
+        let elapsed = now.saturating_duration_since(*this.refilled);
+        let added = (elapsed.as_nanos() / interval.as_nanos()) as usize;
+
+        *this.tokens = this.tokens.saturating_add(added).min(burst);
+        if *this.tokens >= burst {
+            *this.refilled = now;
+        } else {
+            *this.refilled += interval * added as u32;
+        }
+
+        if *this.tokens == 0 {
+            if let Some(waker) = cx.waker() {
+                crate::sync::timer::wake_at(*this.refilled + interval, waker.clone());
+            }
+
+            return PollRecv::Pending;
+        }
+
+        match this.stream.poll_recv(cx) {
+            PollRecv::Ready(value) => {
+                *this.tokens -= 1;
+                PollRecv::Ready(value)
+            }
+            PollRecv::Pending => PollRecv::Pending,
+            PollRecv::Closed(reason) => PollRecv::Closed(reason),
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().stream.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        watch, Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::{RateLimitConfig, RateLimitStream};
+
+    fn config(burst: usize, interval: Duration) -> RateLimitConfig {
+        RateLimitConfig { burst, interval }
+    }
+
+    #[test]
+    fn burst() {
+        let (_tx, rx) = watch::channel_with(config(2, Duration::from_secs(60)));
+        let mut stream = RateLimitStream::new(from_iter(vec![1, 2, 3]), rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn refill() {
+        let (_tx, rx) = watch::channel_with(config(1, Duration::from_millis(20)));
+        let mut stream = RateLimitStream::new(from_iter(vec![1, 2]), rx);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut cx: Context<'_> = w1_context.into();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(w1_count.get() >= 1);
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn config_update() {
+        let (mut config_tx, config_rx) = watch::channel_with(config(1, Duration::from_secs(60)));
+        let mut stream = RateLimitStream::new(from_iter(vec![1, 2]), config_rx);
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut cx: Context<'_> = w1_context.into();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        let woken = w1_count.get();
+        assert_eq!(Ok(()), config_tx.try_send(config(1, Duration::ZERO)));
+        assert!(w1_count.get() > woken);
+
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed_reason() {
+        let (_tx, rx) = watch::channel_with(config(2, Duration::from_secs(60)));
+        let mut stream = RateLimitStream::new(closed::<usize>(), rx);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use core::{fmt, future::Future, pin::Pin, task::Poll};
+use std::time::{Duration, Instant};
+
+use pin_project::pin_project;
+
+use crate::stream::{ClosedReason, PollRecv, Stream};
+
+/// A summary of a stream's lifetime, produced by `Stream::until_closed_report`.
+///
+/// The `Display` impl formats a single line, suitable for logging when a consumer loop exits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClosedReport {
+    /// The number of items the stream produced
+    pub items: usize,
+    /// The time between the first poll, and the stream closing
+    pub elapsed: Duration,
+    /// The reason the stream was closed
+    pub reason: ClosedReason,
+}
+
+impl fmt::Display for ClosedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stream closed after {} items in {:?} ({:?})",
+            self.items, self.elapsed, self.reason
+        )
+    }
+}
+
+/// A future which drains a stream, and resolves to a `ClosedReport` when it closes.  Created by `Stream::until_closed_report`.
+#[pin_project(PinnedDrop)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReportFuture<S>
+where
+    S: Stream,
+{
+    #[pin]
+    stream: S,
+    items: usize,
+    started: Option<Instant>,
+    // true while the stream is pending, so it can be cancelled if the future is dropped
+    receiving: bool,
+}
+
+impl<S> ReportFuture<S>
+where
+    S: Stream,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            items: 0,
+            started: None,
+            receiving: false,
+        }
+    }
+}
+
+impl<S> Future for ReportFuture<S>
+where
+    S: Stream,
+{
+    type Output = ClosedReport;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let mut cx: crate::Context<'_> = cx.into();
+        let started = *this.started.get_or_insert_with(Instant::now);
+
+        loop {
+            let poll = this.stream.as_mut().poll_recv(&mut cx);
+            *this.receiving = matches!(poll, PollRecv::Pending);
+            match poll {
+                PollRecv::Ready(_) => *this.items += 1,
+                PollRecv::Pending => return Poll::Pending,
+                PollRecv::Closed(reason) => {
+                    return Poll::Ready(ClosedReport {
+                        items: *this.items,
+                        elapsed: started.elapsed(),
+                        reason,
+                    })
+                }
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl<S> PinnedDrop for ReportFuture<S>
+where
+    S: Stream,
+{
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if *this.receiving {
+            this.stream.cancel_recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, future::Future, task::Poll};
+
+    use crate::{
+        stream::{ClosedReason, Stream},
+        test::stream::*,
+    };
+    use futures_test::task::noop_context;
+
+    use super::ReportFuture;
+
+    #[test]
+    fn counts_items() {
+        let future = ReportFuture::new(from_iter(vec![1usize, 2, 3]));
+        futures::pin_mut!(future);
+
+        match future.poll(&mut noop_context()) {
+            Poll::Ready(report) => {
+                assert_eq!(3, report.items);
+                assert_eq!(ClosedReason::Closed, report.reason);
+            }
+            Poll::Pending => panic!("expected the report"),
+        }
+    }
+
+    #[test]
+    fn stream_pending() {
+        let future = ReportFuture::new(pending::<usize>());
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.poll(&mut noop_context()));
+    }
+
+    #[test]
+    fn senders_dropped() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        tx.try_send(1usize).unwrap();
+
+        let future = ReportFuture::new(rx);
+        futures::pin_mut!(future);
+
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut noop_context()));
+        drop(tx);
+
+        match future.poll(&mut noop_context()) {
+            Poll::Ready(report) => {
+                assert_eq!(1, report.items);
+                assert_eq!(ClosedReason::SendersDropped, report.reason);
+            }
+            Poll::Pending => panic!("expected the report"),
+        }
+    }
+
+    #[test]
+    fn display() {
+        let report = super::ClosedReport {
+            items: 2,
+            elapsed: std::time::Duration::from_millis(5),
+            reason: ClosedReason::SendersDropped,
+        };
+
+        assert_eq!(
+            "stream closed after 2 items in 5ms (SendersDropped)",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn after_map() {
+        let seen = Cell::new(0);
+        let future =
+            ReportFuture::new(from_iter(vec![1usize, 2]).map(|i| seen.set(seen.get() + i)));
+        futures::pin_mut!(future);
+
+        assert!(future.poll(&mut noop_context()).is_ready());
+        assert_eq!(3, seen.get());
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use tokio::task::spawn;
+
+    use crate::{
+        sink::Sink,
+        stream::{ClosedReason, Stream},
+    };
+
+    #[tokio::test]
+    async fn consumer_loop_lifetime() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let consumer = spawn(rx.until_closed_report());
+
+        for i in 0..10usize {
+            tx.send(i).await.expect("send failed");
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(tx);
+
+        let report = consumer.await.expect("join failed");
+        assert_eq!(10, report.items);
+        assert_eq!(ClosedReason::SendersDropped, report.reason);
+        assert!(report.elapsed >= Duration::from_millis(10));
+    }
+}
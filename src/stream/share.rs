@@ -0,0 +1,292 @@
+use alloc::sync::Arc;
+use core::{fmt, pin::Pin};
+
+use crate::sync::lock::Mutex;
+
+use crate::stream::{PollRecv, Stream};
+use crate::sync::notifier::Notifier;
+use crate::Context;
+
+/// A stream which can be cloned, and polled concurrently by multiple tasks.  Created by `Stream::share`.
+///
+/// Each item is delivered to exactly one clone.  Tasks which are waiting for an item are queued, and are
+/// woken when another clone receives an item, stops waiting, or the stream closes.
+pub struct SharedStream<S> {
+    inner: Arc<Inner<S>>,
+    // true if the last poll of this clone returned pending
+    pending: bool,
+}
+
+struct Inner<S> {
+    stream: Mutex<S>,
+    waiting: Notifier,
+    // calls `cancel_recv` on the stream.  stored so a clone can be cancelled when it is dropped
+    cancel_recv: fn(&mut S),
+}
+
+impl<S> SharedStream<S>
+where
+    S: Stream + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                stream: Mutex::new(stream),
+                waiting: Notifier::new(),
+                cancel_recv: |stream| Pin::new(stream).cancel_recv(),
+            }),
+            pending: false,
+        }
+    }
+}
+
+impl<S> Inner<S> {
+    /// Called when a waiting clone stops polling.  Its waker may be the only one the inner stream retains,
+    /// so waiting tasks poll again, and one of them re-registers for the next item.
+    fn cancel(&self) {
+        (self.cancel_recv)(&mut *self.stream.lock());
+        self.waiting.notify();
+    }
+}
+
+impl<S> Clone for SharedStream<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            pending: false,
+        }
+    }
+}
+
+impl<S> Stream for SharedStream<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.get_mut();
+        let inner = &this.inner;
+        this.pending = false;
+
+        loop {
+            let guard = inner.waiting.guard();
+
+            let poll = Pin::new(&mut *inner.stream.lock()).poll_recv(cx);
+
+            match poll {
+                PollRecv::Ready(value) => {
+                    // the inner stream may only retain the most recent waker.  waiting tasks poll again,
+                    // so one of them re-registers for the next item.
+                    inner.waiting.notify();
+                    return PollRecv::Ready(value);
+                }
+                PollRecv::Pending => {
+                    inner.waiting.subscribe(cx);
+
+                    if guard.is_expired() {
+                        continue;
+                    }
+
+                    this.pending = true;
+                    return PollRecv::Pending;
+                }
+                PollRecv::Closed(reason) => {
+                    inner.waiting.notify();
+                    return PollRecv::Closed(reason);
+                }
+            }
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        let this = self.get_mut();
+        if core::mem::take(&mut this.pending) {
+            this.inner.cancel();
+        }
+    }
+}
+
+impl<S> Drop for SharedStream<S> {
+    fn drop(&mut self) {
+        if self.pending {
+            self.inner.cancel();
+        }
+    }
+}
+
+impl<S> fmt::Debug for SharedStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedStream").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+    use futures_test::task::new_count_waker;
+
+    use super::SharedStream;
+
+    #[test]
+    fn each_item_once() {
+        let mut a = SharedStream::new(from_iter(vec![1, 2, 3]));
+        let mut b = a.clone();
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut a).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(2), Pin::new(&mut b).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut a).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut b).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn ready_wakes_waiters() {
+        let source = from_poll_iter(vec![PollRecv::Pending, PollRecv::Ready(1usize)]);
+        let mut a = SharedStream::new(source);
+        let mut b = a.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut w1_context: Context<'_> = w1_context.into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut a).poll_recv(&mut w1_context)
+        );
+        assert_eq!(0, w1_count.get());
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut b).poll_recv(&mut cx));
+        assert_eq!(1, w1_count.get());
+    }
+
+    #[test]
+    fn cancelled_recv_wakes_waiters() {
+        use std::{future::Future, task::Poll};
+
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut a = rx.share();
+        let mut b = a.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let mut w1_context = std::task::Context::from_waker(&w1);
+        let mut recv_a = a.recv();
+        assert_eq!(Poll::Pending, Pin::new(&mut recv_a).poll(&mut w1_context));
+
+        // b replaces a's waker in the channel, and then is dropped before a message arrives
+        {
+            let mut recv_b = b.recv();
+            let mut cx = futures_test::task::noop_context();
+            assert_eq!(Poll::Pending, Pin::new(&mut recv_b).poll(&mut cx));
+        }
+
+        let woken = w1_count.get();
+        assert!(woken > 0);
+        assert_eq!(Poll::Pending, Pin::new(&mut recv_a).poll(&mut w1_context));
+
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert!(w1_count.get() > woken);
+        assert_eq!(
+            Poll::Ready(Some(1)),
+            Pin::new(&mut recv_a).poll(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn dropped_clone_wakes_waiters() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let mut a = rx.share();
+        let mut b = a.clone();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = std::task::Context::from_waker(&w1);
+        let mut w1_context: Context<'_> = w1_context.into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut a).poll_recv(&mut w1_context)
+        );
+
+        let (w2, _w2_count) = new_count_waker();
+        let w2_context = std::task::Context::from_waker(&w2);
+        let mut w2_context: Context<'_> = w2_context.into();
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut b).poll_recv(&mut w2_context)
+        );
+        drop(b);
+
+        assert!(w1_count.get() > 0);
+        assert_eq!(
+            PollRecv::Pending,
+            Pin::new(&mut a).poll_recv(&mut w1_context)
+        );
+
+        let woken = w1_count.get();
+        assert_eq!(Ok(()), tx.try_send(1usize));
+        assert!(w1_count.get() > woken);
+        assert_eq!(
+            PollRecv::Ready(1),
+            Pin::new(&mut a).poll_recv(&mut w1_context)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let mut stream = SharedStream::new(pending::<usize>());
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tokio::task::spawn;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_receivers() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let shared = rx.share();
+        let received = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            let mut rx = shared.clone();
+            let received = received.clone();
+            handles.push(spawn(async move {
+                while let Some(_value) = rx.recv().await {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            }));
+        }
+        drop(shared);
+
+        for i in 0..1000usize {
+            tx.send(i).await.expect("send failed");
+        }
+        drop(tx);
+
+        for handle in handles {
+            handle.await.expect("join failed");
+        }
+
+        assert_eq!(1000, received.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,108 @@
+use core::pin::Pin;
+
+use crate::stream::{PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct SkipWhileStream<From, Condition> {
+    #[pin]
+    from: From,
+    condition: Condition,
+    skipping: bool,
+}
+
+impl<From, Condition> SkipWhileStream<From, Condition>
+where
+    From: Stream,
+    Condition: FnMut(&From::Item) -> bool,
+{
+    pub fn new(from: From, condition: Condition) -> Self {
+        Self {
+            from,
+            condition,
+            skipping: true,
+        }
+    }
+}
+
+impl<From, Condition> Stream for SkipWhileStream<From, Condition>
+where
+    From: Stream,
+    Condition: FnMut(&From::Item) -> bool,
+{
+    type Item = From::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+        loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    if *this.skipping && (this.condition)(&value) {
+                        continue;
+                    }
+
+                    *this.skipping = false;
+                    return PollRecv::Ready(value);
+                }
+                PollRecv::Pending => return PollRecv::Pending,
+                PollRecv::Closed(reason) => return PollRecv::Closed(reason),
+            }
+        }
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::SkipWhileStream;
+
+    #[test]
+    fn skip_while() {
+        let source = from_iter(vec![1, 2, 3, 1]);
+        let mut stream = SkipWhileStream::new(source, |i| *i < 3);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut stream = SkipWhileStream::new(source, |_| true);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = closed::<usize>();
+        let mut stream = SkipWhileStream::new(source, |_| true);
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::Context;
+use core::{fmt::Debug, pin::Pin};
 use pin_project::pin_project;
-use std::{fmt::Debug, pin::Pin};
 
 use log::log;
 
@@ -18,7 +18,7 @@ where
     S: Stream,
 {
     pub fn new(stream: S, level: log::Level) -> Self {
-        let type_name = std::any::type_name::<S::Item>();
+        let type_name = core::any::type_name::<S::Item>();
         StreamLog {
             stream,
             type_name,
@@ -42,9 +42,13 @@ where
                 PollRecv::Ready(value)
             }
             PollRecv::Pending => PollRecv::Pending,
-            PollRecv::Closed => PollRecv::Closed,
+            PollRecv::Closed(reason) => PollRecv::Closed(reason),
         }
     }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().stream.cancel_recv();
+    }
 }
 
 #[cfg(test)]
@@ -55,7 +59,7 @@ mod tests {
 
     use crate::test::stream::*;
     use crate::{
-        stream::{PollRecv, Stream},
+        stream::{ClosedReason, PollRecv, Stream},
         Context,
     };
 
@@ -108,6 +112,9 @@ mod tests {
         let mut repeat = StreamLog::new(closed::<usize>(), Level::Info);
         let mut cx = Context::empty();
 
-        assert_eq!(PollRecv::Closed, Pin::new(&mut repeat).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut repeat).poll_recv(&mut cx)
+        );
     }
 }
@@ -0,0 +1,164 @@
+use core::{future::Future, pin::Pin};
+
+use crate::stream::{ClosedReason, PollRecv, Stream};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct TakeUntilStream<From, Signal> {
+    #[pin]
+    from: From,
+    #[pin]
+    signal: Signal,
+    stopped: bool,
+}
+
+impl<From, Signal> TakeUntilStream<From, Signal>
+where
+    From: Stream,
+    Signal: Future,
+{
+    pub fn new(from: From, signal: Signal) -> Self {
+        Self {
+            from,
+            signal,
+            stopped: false,
+        }
+    }
+}
+
+impl<From, Signal> Stream for TakeUntilStream<From, Signal>
+where
+    From: Stream,
+    Signal: Future,
+{
+    type Item = From::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+
+        if *this.stopped {
+            return PollRecv::Closed(ClosedReason::Closed);
+        }
+
+        // try_recv polls without a waker, and the signal must not store one
+        let noop = crate::context::noop_waker();
+        let waker = cx.waker().unwrap_or(&noop);
+        let mut signal_cx = core::task::Context::from_waker(waker);
+        if this.signal.poll(&mut signal_cx).is_ready() {
+            *this.stopped = true;
+            return PollRecv::Closed(ClosedReason::Closed);
+        }
+
+        this.from.poll_recv(cx)
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::test::stream::*;
+    use crate::{
+        sink::Sink,
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::TakeUntilStream;
+
+    #[test]
+    fn take_until_signal() {
+        let (mut tx, rx) = crate::oneshot::channel::<()>();
+        let signal = async move {
+            let mut rx = rx;
+            rx.recv().await;
+        };
+
+        let source = from_iter(vec![1, 2, 3]);
+        let mut stream = TakeUntilStream::new(source, Box::pin(signal));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+
+        assert!(tx.try_send(()).is_ok());
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn forward_pending() {
+        let source = pending::<usize>();
+        let mut stream = TakeUntilStream::new(source, std::future::pending::<()>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn forward_closed() {
+        let source = from_poll_iter(vec![PollRecv::<usize>::Closed(
+            ClosedReason::SendersDropped,
+        )]);
+        let mut stream = TakeUntilStream::new(source, std::future::pending::<()>());
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::SendersDropped),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn ready_signal() {
+        let source = from_iter(vec![1]);
+        let mut stream = TakeUntilStream::new(source, std::future::ready(()));
+
+        let mut cx = Context::empty();
+
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tokio_tests {
+    use std::time::Duration;
+
+    use crate::{sink::Sink, stream::Stream};
+
+    #[tokio::test]
+    async fn shutdown_signal() {
+        let (mut tx, rx) = crate::mpsc::channel(4);
+        let (mut shutdown_tx, mut shutdown_rx) = crate::oneshot::channel::<()>();
+
+        let mut rx = rx.take_until(Box::pin(async move {
+            shutdown_rx.recv().await;
+        }));
+
+        tx.send(1usize).await.expect("send failed");
+        assert_eq!(Some(1), rx.recv().await);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            shutdown_tx.send(()).await.expect("send failed");
+        });
+
+        assert_eq!(None, rx.recv().await);
+    }
+}
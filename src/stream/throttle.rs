@@ -0,0 +1,159 @@
+use core::{pin::Pin, time::Duration};
+
+use crate::stream::{PollRecv, Stream};
+use crate::time::{poll_sleep, Timer};
+use crate::Context;
+use pin_project::pin_project;
+
+#[pin_project]
+pub struct ThrottleStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    #[pin]
+    from: From,
+    #[pin]
+    sleep: Option<T::Sleep>,
+    timer: T,
+    interval: Duration,
+    latest: Option<From::Item>,
+}
+
+impl<From, T> ThrottleStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    pub fn new(from: From, interval: Duration, timer: T) -> Self {
+        Self {
+            from,
+            sleep: None,
+            timer,
+            interval,
+            latest: None,
+        }
+    }
+}
+
+impl<From, T> Stream for ThrottleStream<From, T>
+where
+    From: Stream,
+    T: Timer,
+{
+    type Item = From::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let mut this = self.project();
+
+        if this.sleep.is_none() {
+            // outside of an interval, a message is returned immediately, and starts the interval
+            return match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => {
+                    this.sleep.set(Some(this.timer.sleep(*this.interval)));
+                    PollRecv::Ready(value)
+                }
+                poll => poll,
+            };
+        }
+
+        // within the interval, messages are drained, and only the latest is retained
+        let closed = loop {
+            match this.from.as_mut().poll_recv(cx) {
+                PollRecv::Ready(value) => *this.latest = Some(value),
+                PollRecv::Pending => break None,
+                PollRecv::Closed(reason) => break Some(reason),
+            }
+        };
+
+        if let Some(reason) = closed {
+            this.sleep.set(None);
+            return match this.latest.take() {
+                Some(value) => PollRecv::Ready(value),
+                None => PollRecv::Closed(reason),
+            };
+        }
+
+        if poll_sleep(this.sleep.as_mut(), cx) {
+            if let Some(value) = this.latest.take() {
+                this.sleep.set(Some(this.timer.sleep(*this.interval)));
+                return PollRecv::Ready(value);
+            }
+        }
+
+        PollRecv::Pending
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().from.cancel_recv();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::Pin, time::Duration};
+
+    use crate::test::stream::*;
+    use crate::test::timer::{ExpiredTimer, NeverTimer};
+    use crate::{
+        stream::{ClosedReason, PollRecv, Stream},
+        Context,
+    };
+
+    use super::ThrottleStream;
+
+    #[test]
+    fn first_message_immediate() {
+        let source = from_poll_iter(vec![PollRecv::Ready(1usize), PollRecv::Pending]);
+        let mut stream = ThrottleStream::new(source, Duration::from_millis(10), NeverTimer);
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn latest_after_interval() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Ready(2),
+            PollRecv::Ready(3),
+            PollRecv::Pending,
+            PollRecv::Pending,
+        ]);
+        let mut stream = ThrottleStream::new(source, Duration::from_millis(10), ExpiredTimer);
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+
+    #[test]
+    fn holds_latest_within_interval() {
+        let source = from_poll_iter(vec![
+            PollRecv::Ready(1usize),
+            PollRecv::Ready(2),
+            PollRecv::Pending,
+            PollRecv::Ready(3),
+            PollRecv::Pending,
+        ]);
+        let mut stream = ThrottleStream::new(source, Duration::from_millis(10), NeverTimer);
+
+        let mut cx = Context::empty();
+        assert_eq!(PollRecv::Ready(1), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut stream).poll_recv(&mut cx));
+
+        // the latest message is flushed when the stream closes
+        assert_eq!(PollRecv::Ready(3), Pin::new(&mut stream).poll_recv(&mut cx));
+        assert_eq!(
+            PollRecv::Closed(ClosedReason::Closed),
+            Pin::new(&mut stream).poll_recv(&mut cx)
+        );
+    }
+}
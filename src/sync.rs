@@ -1,23 +1,90 @@
-use std::sync::Arc;
+//! Synchronization primitives.  Channel internals are private, and `ref_count` is exported as a general-purpose utility.
 
+use alloc::{sync::Arc, sync::Weak};
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    task::Waker,
+};
+
+use core::fmt::Debug;
 use notifier::Notifier;
 use ref_count::RefCount;
-use std::fmt::Debug;
 
-use crate::Context;
+use crate::{stream::ClosedReason, Context};
 
 use self::{notifier::NotificationGuard, ref_count::TryDecrement};
 
-pub mod mpmc_circular_buffer;
-pub mod notifier;
+pub(crate) mod lock;
+pub(crate) mod mpmc_circular_buffer;
+pub(crate) mod notifier;
 mod oneshot_cell;
-mod ref_count;
+pub(crate) mod primitive;
+pub mod ref_count;
 // mod rr_lock;
 mod state_cell;
+#[cfg(feature = "timer")]
+pub(crate) mod timer;
+#[cfg(feature = "timer")]
+pub(crate) mod timing_wheel;
 pub(crate) mod transfer;
 
+/// The number of tasks which are parked on a channel, waiting to send or receive.  Returned by `waiters()` on channel handles.
+///
+/// Counts are taken from the registered wakers.  A task which polls repeatedly without being woken may be counted more than once.
+///
+/// Requires the `debug` feature.
+#[cfg(feature = "debug")]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Waiters {
+    /// Tasks waiting for capacity, or for a receiver to take a message
+    pub senders: usize,
+    /// Tasks waiting for a message
+    pub receivers: usize,
+}
+
+/// A snapshot of the state of a channel, for watchdogs which detect stalls.  Returned by `diagnostics()` on channel handles.
+///
+/// If senders are waiting, the receivers are not keeping up.  On broadcast channels, `blocking_slot` identifies the slot which a receiver has not read.
+/// If receivers are waiting, and `since_last_write` keeps growing, the senders have stopped sending.
+///
+/// Requires the `diagnostics` feature.
+#[cfg(feature = "diagnostics")]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ChannelDiagnostics {
+    /// Tasks waiting for capacity, or for a receiver to take a message
+    pub waiting_senders: usize,
+    /// Tasks waiting for a message
+    pub waiting_receivers: usize,
+    /// For broadcast channels, the buffer index of the slot which blocks the next write, because a receiver has not read it
+    pub blocking_slot: Option<usize>,
+    /// The time since a message was last accepted by the channel, or `None` if no message has been sent
+    pub since_last_write: Option<std::time::Duration>,
+}
+
+/// True if the current thread is unwinding.  Without `std`, panics can't be observed, and this is always false.
+pub(crate) fn panicking() -> bool {
+    #[cfg(feature = "std")]
+    return std::thread::panicking();
+
+    #[cfg(not(feature = "std"))]
+    false
+}
+
 pub(crate) fn shared<E>(extension: E) -> (SenderShared<E>, ReceiverShared<E>) {
-    let inner = Arc::new(Shared::new(extension));
+    pair(Shared::new(extension))
+}
+
+/// Constructs a channel which reports events to the metrics recorder under the given name.
+#[cfg(feature = "metrics")]
+pub(crate) fn shared_named<E>(name: &str, extension: E) -> (SenderShared<E>, ReceiverShared<E>) {
+    let mut shared = Shared::new(extension);
+    shared.name = Some(name.into());
+
+    pair(shared)
+}
+
+fn pair<E>(shared: Shared<E>) -> (SenderShared<E>, ReceiverShared<E>) {
+    let inner = Arc::new(shared);
 
     let sender = SenderShared {
         inner: inner.clone(),
@@ -29,11 +96,17 @@ pub(crate) fn shared<E>(extension: E) -> (SenderShared<E>, ReceiverShared<E>) {
 }
 
 #[derive(Debug)]
-pub struct Shared<E> {
+pub(crate) struct Shared<E> {
     sender_notify: Notifier,
     sender_count: RefCount,
     receiver_notify: Notifier,
     receiver_count: RefCount,
+    poisoned: AtomicBool,
+    closed: AtomicBool,
+    #[cfg(feature = "metrics")]
+    name: Option<Arc<str>>,
+    #[cfg(feature = "diagnostics")]
+    last_write: lock::Mutex<Option<std::time::Instant>>,
     pub(crate) extension: E,
 }
 
@@ -44,12 +117,37 @@ impl<E> Shared<E> {
             sender_count: RefCount::new(1),
             receiver_notify: Notifier::new(),
             receiver_count: RefCount::new(1),
+            poisoned: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            #[cfg(feature = "metrics")]
+            name: None,
+            #[cfg(feature = "diagnostics")]
+            last_write: lock::Mutex::new(None),
             extension,
         }
     }
+
+    #[cfg(feature = "debug")]
+    fn waiters(&self) -> Waiters {
+        // senders wait to be notified by receivers, and receivers wait to be notified by senders
+        Waiters {
+            senders: self.sender_notify.waiting(),
+            receivers: self.receiver_notify.waiting(),
+        }
+    }
+
+    #[cfg(feature = "diagnostics")]
+    fn diagnostics(&self) -> ChannelDiagnostics {
+        ChannelDiagnostics {
+            waiting_senders: self.sender_notify.waiting(),
+            waiting_receivers: self.receiver_notify.waiting(),
+            blocking_slot: None,
+            since_last_write: self.last_write.lock().map(|instant| instant.elapsed()),
+        }
+    }
 }
 
-pub struct SenderShared<E> {
+pub(crate) struct SenderShared<E> {
     inner: Arc<Shared<E>>,
 }
 
@@ -58,6 +156,17 @@ impl<E> SenderShared<E> {
         &self.inner.extension
     }
 
+    #[cfg(feature = "metrics")]
+    pub fn record(&self, event: crate::metrics::Event) {
+        crate::metrics::record(&self.inner.name, event);
+    }
+
+    /// Records that a message was accepted by the channel.
+    #[cfg(feature = "diagnostics")]
+    pub fn record_write(&self) {
+        *self.inner.last_write.lock() = Some(std::time::Instant::now());
+    }
+
     pub fn notify_receivers(&self) {
         self.inner.receiver_notify.notify();
     }
@@ -75,7 +184,7 @@ impl<E> SenderShared<E> {
     }
 
     pub fn is_alive(&self) -> bool {
-        self.inner.receiver_count.is_alive()
+        self.inner.receiver_count.is_alive() && !self.inner.closed.load(Ordering::SeqCst)
     }
 
     pub fn clone_receiver(&self) -> ReceiverShared<E> {
@@ -89,13 +198,42 @@ impl<E> SenderShared<E> {
     pub fn is_closed(&self) -> bool {
         !self.is_alive()
     }
+
+    pub fn sender_count(&self) -> usize {
+        self.inner.sender_count.get()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.inner.receiver_count.get()
+    }
+
+    /// True if any receivers are alive.  Unlike `is_alive`, this ignores `ReceiverShared::close`.
+    pub fn has_receivers(&self) -> bool {
+        self.inner.receiver_count.is_alive()
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> Waiters {
+        self.inner.waiters()
+    }
+
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> ChannelDiagnostics {
+        self.inner.diagnostics()
+    }
+
+    pub fn downgrade(&self) -> WeakSenderShared<E> {
+        WeakSenderShared {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
 }
 
 impl<E> Debug for SenderShared<E>
 where
     E: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.inner.fmt(f)
     }
 }
@@ -114,13 +252,44 @@ impl<E> Drop for SenderShared<E> {
         match self.inner.sender_count.decrement() {
             TryDecrement::Alive(_) => {}
             TryDecrement::Dead => {
+                // a panic only poisons the channel if it drops the last sender.  other senders may still be sending
+                if panicking() {
+                    self.inner.poisoned.store(true, Ordering::Release);
+                }
+
                 self.notify_receivers();
             }
         }
     }
 }
 
-pub struct ReceiverShared<E> {
+/// A sender reference which does not contribute to the sender count.
+pub(crate) struct WeakSenderShared<E> {
+    inner: Weak<Shared<E>>,
+}
+
+impl<E> WeakSenderShared<E> {
+    /// Produces a sender, if any strong senders are still alive.
+    pub fn upgrade(&self) -> Option<SenderShared<E>> {
+        let inner = self.inner.upgrade()?;
+
+        if !inner.sender_count.try_increment() {
+            return None;
+        }
+
+        Some(SenderShared { inner })
+    }
+}
+
+impl<E> Clone for WeakSenderShared<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+pub(crate) struct ReceiverShared<E> {
     pub(crate) inner: Arc<Shared<E>>,
 }
 
@@ -129,6 +298,15 @@ impl<E> ReceiverShared<E> {
         &self.inner.extension
     }
 
+    #[cfg(feature = "metrics")]
+    pub fn record(&self, event: crate::metrics::Event) {
+        crate::metrics::record(&self.inner.name, event);
+    }
+
+    pub fn notify_receivers(&self) {
+        self.inner.receiver_notify.notify();
+    }
+
     pub fn notify_senders(&self) {
         self.inner.sender_notify.notify();
     }
@@ -137,17 +315,55 @@ impl<E> ReceiverShared<E> {
         self.inner.receiver_notify.subscribe(cx);
     }
 
+    pub fn unsubscribe_send(&self, waker: &Waker) {
+        self.inner.receiver_notify.unsubscribe(waker);
+    }
+
     pub fn send_guard(&self) -> NotificationGuard {
         self.inner.receiver_notify.guard()
     }
 
     pub fn is_alive(&self) -> bool {
-        self.inner.sender_count.is_alive()
+        self.inner.sender_count.is_alive() && !self.inner.closed.load(Ordering::SeqCst)
     }
 
     pub fn is_closed(&self) -> bool {
         !self.is_alive()
     }
+
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> Waiters {
+        self.inner.waiters()
+    }
+
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> ChannelDiagnostics {
+        self.inner.diagnostics()
+    }
+
+    pub fn sender_count(&self) -> usize {
+        self.inner.sender_count.get()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.inner.receiver_count.get()
+    }
+
+    /// Closes the channel from the receiving side.  Senders are rejected, and woken if they are waiting.
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.notify_senders();
+    }
+
+    pub fn closed_reason(&self) -> ClosedReason {
+        if self.inner.poisoned.load(Ordering::Acquire) {
+            ClosedReason::Poisoned
+        } else if self.inner.closed.load(Ordering::SeqCst) {
+            ClosedReason::Closed
+        } else {
+            ClosedReason::SendersDropped
+        }
+    }
 }
 
 impl<E> Clone for ReceiverShared<E> {
@@ -161,6 +377,9 @@ impl<E> Clone for ReceiverShared<E> {
 
 impl<E> Drop for ReceiverShared<E> {
     fn drop(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.record(crate::metrics::Event::ReceiverDropped);
+
         match self.inner.receiver_count.decrement() {
             TryDecrement::Alive(_) => {}
             TryDecrement::Dead => {
@@ -169,3 +388,46 @@ impl<E> Drop for ReceiverShared<E> {
         }
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::stream::ClosedReason;
+
+    use super::shared;
+
+    #[test]
+    fn last_sender_panic_poisons() {
+        let (tx, rx) = shared(());
+        let tx2 = tx.clone();
+
+        drop(tx);
+        crate::test::drop_panicking(tx2);
+
+        assert!(rx.is_closed());
+        assert_eq!(ClosedReason::Poisoned, rx.closed_reason());
+    }
+
+    #[test]
+    fn surviving_sender_not_poisoned() {
+        let (tx, rx) = shared(());
+        let tx2 = tx.clone();
+
+        crate::test::drop_panicking(tx2);
+        assert!(rx.is_alive());
+
+        drop(tx);
+        assert_eq!(ClosedReason::SendersDropped, rx.closed_reason());
+    }
+
+    #[test]
+    fn closed_after_surviving_sender() {
+        let (tx, rx) = shared(());
+        let tx2 = tx.clone();
+
+        crate::test::drop_panicking(tx2);
+        rx.close();
+        drop(tx);
+
+        assert_eq!(ClosedReason::Closed, rx.closed_reason());
+    }
+}
@@ -0,0 +1,26 @@
+//! Locks used by the channel state.  With the `std` feature these are the `parking_lot` locks,
+//! and without it they spin.  Critical sections are short and never await, so spinning is acceptable
+//! on the single-threaded executors used by `no_std` targets.
+
+#[cfg(feature = "std")]
+pub(crate) use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(feature = "std")]
+pub(crate) use std::sync::OnceLock;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A cell which is initialized once.  Matches the subset of the `std::sync::OnceLock` API used by the channels.
+#[cfg(not(feature = "std"))]
+pub(crate) struct OnceLock<T>(spin::Once<T>);
+
+#[cfg(not(feature = "std"))]
+impl<T> OnceLock<T> {
+    pub const fn new() -> Self {
+        Self(spin::Once::new())
+    }
+
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        self.0.call_once(init)
+    }
+}
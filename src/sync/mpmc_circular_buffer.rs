@@ -1,29 +1,62 @@
-use std::{cmp::max, sync::atomic::AtomicUsize};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc, sync::Weak};
+use core::{cmp::max, marker::PhantomData, task::Poll};
 
 use crate::Context;
 use atomic::Ordering;
-use parking_lot::{Mutex, RwLock};
 
 use super::notifier::Notifier;
-use std::fmt::Debug;
+use super::primitive::{spin_loop, AtomicBool, AtomicPtr, AtomicUsize, Mutex, RwLock};
+use core::fmt::Debug;
 
-// A lock-free multi-producer, multi-consumer circular buffer
+// A multi-producer, multi-consumer circular buffer
 // Each reader will see each value created exactly once.
 // Cloned readers inherit the read location of the reader that was cloned.
+// If the buffer is created with `overwrite`, writers never wait for readers.
+//   Instead, the oldest slot is overwritten, and lagging readers skip ahead.
+// Writers do not take a head lock.  They race to claim the head slot with a CAS on its index.
+// Operations do not lock the buffer.  The slots are published through an atomic pointer, which `resize` replaces.
+//   `resize` first seals each slot under its data lock, so operations in progress on the slot complete.
+//   Operations which find a sealed slot retry on the new slots, once they are published.
+//   The previous slots are kept until the buffer is dropped, as operations which loaded the pointer may still hold them.
+
+type Slots<T> = Box<[Slot<T>]>;
+
+// the index of a slot which has been sealed by a resize
+const SEALED: usize = usize::MAX;
 
 pub struct MpmcCircularBuffer<T> {
-    buffer: Box<[Slot<T>]>,
+    slots: AtomicPtr<Slots<T>>,
+    // every slot array the buffer has published.  arrays are not freed until the buffer is dropped
+    arrays: Mutex<Vec<Arc<Slots<T>>>>,
+    capacity: AtomicUsize,
     head: AtomicUsize,
+    // messages with lower ids were discarded by a resize, and readers behind it skip ahead
+    floor: AtomicUsize,
     maintenance: Mutex<()>,
     readers: AtomicUsize,
+    overwrite: bool,
+    listeners: Mutex<Vec<Weak<ReaderLog>>>,
+    hooks: Mutex<ReaderHooks>,
+    // the slots are shared between threads, so the buffer is only Send and Sync if the slots are
+    slot_marker: PhantomData<Slots<T>>,
+}
+
+/// Callbacks which run when the reader count changes between zero and one.
+#[derive(Default)]
+struct ReaderHooks {
+    first: Vec<Box<dyn Fn() + Send + Sync>>,
+    last: Vec<Box<dyn Fn() + Send + Sync>>,
 }
 
 impl<T> Debug for MpmcCircularBuffer<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("MpmcCircularBuffer")
-            .field("buffer", &self.buffer)
+            .field("buffer", &self.slots())
             .field("head", &self.head)
             .field("readers", &self.readers)
+            .field("overwrite", &self.overwrite)
             .finish()
     }
 }
@@ -32,7 +65,7 @@ impl<T> MpmcCircularBuffer<T>
 where
     T: Clone,
 {
-    pub fn new(capacity: usize) -> (Self, BufferReader) {
+    pub fn new(capacity: usize, overwrite: bool) -> (Self, BufferReader) {
         // we require two readers, so that unique slots can be acquired and released
         let capacity = max(2, capacity);
         let mut vec = Vec::with_capacity(capacity);
@@ -41,11 +74,20 @@ where
             vec.push(Slot::new(0));
         }
 
+        let slots = Arc::new(vec.into_boxed_slice());
+
         let this = Self {
-            buffer: vec.into_boxed_slice(),
+            slots: AtomicPtr::new(Arc::as_ptr(&slots) as *mut _),
+            arrays: Mutex::new(alloc::vec![slots]),
+            capacity: AtomicUsize::new(capacity),
             head: AtomicUsize::new(1),
+            floor: AtomicUsize::new(0),
             readers: AtomicUsize::new(1),
             maintenance: Mutex::new(()),
+            overwrite,
+            listeners: Mutex::new(Vec::new()),
+            hooks: Mutex::new(ReaderHooks::default()),
+            slot_marker: PhantomData,
         };
 
         let reader = BufferReader { index: 1 };
@@ -56,24 +98,34 @@ where
 
 pub enum TryWrite<T> {
     Pending(T),
-    Ready,
+    /// The value was written, with the given id
+    Ready(usize),
 }
 
 pub enum SlotTryWrite<T> {
     Pending(T),
     Ready,
     Written(T),
+    /// The slot was sealed by a resize, and the write retries on the new slots
+    Sealed(T),
 }
 
 impl<T> MpmcCircularBuffer<T> {
     pub fn len(&self) -> usize {
-        self.buffer.len()
+        self.capacity.load(Ordering::Acquire)
+    }
+
+    /// The slots most recently published by a resize.  During a resize, they may be sealed.
+    fn slots(&self) -> &[Slot<T>] {
+        // SAFETY: the pointer is always one of the arrays, which are not freed until the buffer is dropped
+        unsafe { &*self.slots.load(Ordering::Acquire) }
     }
 
     pub fn try_write(&self, mut value: T, cx: &Context<'_>) -> TryWrite<T> {
         loop {
+            let slots = self.slots();
             let head_id = self.head.load(Ordering::Acquire);
-            let head_slot = self.get_slot(head_id);
+            let head_slot = slot_at(slots, head_id);
 
             #[cfg(feature = "debug")]
             log::debug!(
@@ -86,24 +138,26 @@ impl<T> MpmcCircularBuffer<T> {
             );
 
             // try to write a value
-            // if the write is accepted, release the head lock in the closure
-            // this minimizes the time head is locked, and allows the move of value to occur after the lock is released
-            let try_write = head_slot.try_write(head_id, value, &self.readers, cx, || {
-                if let Err(_e) = self.head.compare_exchange(
-                    head_id,
-                    head_id + 1,
-                    Ordering::SeqCst,
-                    Ordering::Relaxed,
-                ) {
-                    #[cfg(feature = "debug")]
-                    log::warn!(
-                        "[{}] Expected {} head value, found {}",
+            // there is no head lock.  writers race to claim the slot by advancing its index with a CAS,
+            // and the winner advances head in the closure.  writers which lose the race observe
+            // SlotTryWrite::Written, and retry at the new head.
+            let try_write =
+                head_slot.try_write(head_id, value, &self.readers, self.overwrite, cx, || {
+                    if let Err(_e) = self.head.compare_exchange(
                         head_id,
                         head_id + 1,
-                        _e
-                    );
-                }
-            });
+                        Ordering::SeqCst,
+                        Ordering::Relaxed,
+                    ) {
+                        #[cfg(feature = "debug")]
+                        log::warn!(
+                            "[{}] Expected {} head value, found {}",
+                            head_id,
+                            head_id + 1,
+                            _e
+                        );
+                    }
+                });
 
             match try_write {
                 SlotTryWrite::Pending(v) => {
@@ -122,22 +176,192 @@ impl<T> MpmcCircularBuffer<T> {
                         head_id + 1
                     );
 
-                    return TryWrite::Ready;
+                    return TryWrite::Ready(head_id);
                 }
                 SlotTryWrite::Written(v) => {
+                    // another writer claimed the slot.  help advance head, in case this writer observed a stale value
+                    self.head
+                        .compare_exchange(head_id, head_id + 1, Ordering::AcqRel, Ordering::Relaxed)
+                        .ok();
+
+                    value = v;
+                    continue;
+                }
+                SlotTryWrite::Sealed(v) => {
                     value = v;
+                    spin_loop();
                     continue;
                 }
             }
         }
     }
 
+    /// Replaces the message with the given id, if no reader has read it.  Otherwise, returns the value.
+    pub fn try_replace(&self, id: usize, value: T) -> Result<(), T> {
+        loop {
+            let slot = slot_at(self.slots(), id);
+
+            // readers increment reads while holding the data lock, so they observe either the old or the new value
+            let mut data = slot.data.write();
+            let index = slot.index.load(Ordering::Acquire);
+            if index == SEALED {
+                drop(data);
+                spin_loop();
+                continue;
+            }
+
+            if index != id || slot.reads.load(Ordering::Acquire) > 0 {
+                return Err(value);
+            }
+
+            *data = Some(value);
+            return Ok(());
+        }
+    }
+
+    /// The number of slots which contain a message that has not been read by every reader.
+    pub fn occupied(&self) -> usize {
+        loop {
+            let readers = self.readers.load(Ordering::Acquire);
+            let mut occupied = 0;
+
+            // if a resize seals the slots, the messages are counted again in the new slots
+            let settled = self.slots().iter().all(|slot| {
+                let index = slot.index.load(Ordering::Acquire);
+                if index > 0 && index != SEALED && slot.reads.load(Ordering::Acquire) < readers {
+                    occupied += 1;
+                }
+
+                index != SEALED
+            });
+
+            if settled {
+                return occupied;
+            }
+
+            spin_loop();
+        }
+    }
+
+    /// Drops values which have been read by every reader, and returns the number of values which were dropped.
+    pub fn shrink_slots(&self) -> usize {
+        let mut released = 0;
+
+        loop {
+            let readers = self.readers.load(Ordering::Acquire);
+
+            // if a resize seals the slots, the remaining values are released in the new slots
+            let settled = self.slots().iter().all(|slot| {
+                let index = slot.index.load(Ordering::Acquire);
+                if index != SEALED && slot.release(index, readers) {
+                    released += 1;
+                }
+
+                index != SEALED
+            });
+
+            if settled {
+                return released;
+            }
+
+            spin_loop();
+        }
+    }
+
+    /// The id of the most recently written slot, or zero if no messages have been written.
+    pub fn last_written(&self) -> usize {
+        self.head.load(Ordering::Acquire) - 1
+    }
+
+    /// Returns `Poll::Ready` once every reader has read the message with the given id, or it has been overwritten.
+    /// Readers consume messages in order, so all earlier messages have also been read.
+    pub fn poll_read_by_all(&self, id: usize, cx: &Context<'_>) -> Poll<()> {
+        if id == 0 {
+            return Poll::Ready(());
+        }
+
+        loop {
+            let slot = slot_at(self.slots(), id);
+            let guard = slot.on_release.guard();
+
+            // writers hold the data lock while the index and reads are updated
+            let (index, released) = {
+                let _read = slot.data.read();
+                let index = slot.index.load(Ordering::Acquire);
+                let released = index != id
+                    || slot.reads.load(Ordering::Acquire) >= self.readers.load(Ordering::Acquire);
+
+                (index, released)
+            };
+
+            if index == SEALED {
+                spin_loop();
+                continue;
+            }
+
+            if released {
+                return Poll::Ready(());
+            }
+
+            slot.on_release.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Counts the tasks waiting on slots.  Writers wait for slots to be released, and readers wait for slots to be written.
+    #[cfg(feature = "debug")]
+    pub fn waiters(&self) -> crate::sync::Waiters {
+        self.slots()
+            .iter()
+            .fold(crate::sync::Waiters::default(), |waiters, slot| {
+                crate::sync::Waiters {
+                    senders: waiters.senders + slot.on_release.waiting(),
+                    receivers: waiters.receivers + slot.on_write.waiting(),
+                }
+            })
+    }
+
+    /// Counts the tasks waiting on slots, and finds the slot which blocks the next write.
+    #[cfg(feature = "diagnostics")]
+    pub fn diagnostics(&self) -> crate::sync::ChannelDiagnostics {
+        let slots = self.slots();
+        let (waiting_senders, waiting_receivers) =
+            slots.iter().fold((0, 0), |(senders, receivers), slot| {
+                (
+                    senders + slot.on_release.waiting(),
+                    receivers + slot.on_write.waiting(),
+                )
+            });
+
+        // the next write claims the slot at head, which holds a message until every reader has read it
+        let head = self.head.load(Ordering::Acquire);
+        let slot = slot_at(slots, head);
+        let index = slot.index.load(Ordering::Acquire);
+        let blocked = !self.overwrite
+            && index > 0
+            && index != SEALED
+            && slot.reads.load(Ordering::Acquire) < self.readers.load(Ordering::Acquire);
+
+        crate::sync::ChannelDiagnostics {
+            waiting_senders,
+            waiting_receivers,
+            blocking_slot: blocked.then(|| head % slots.len()),
+            since_last_write: None,
+        }
+    }
+
     pub fn new_reader(&self) -> BufferReader {
         let _maint = self.maintenance.lock();
         let index = self.head.load(Ordering::Acquire);
         self.readers.fetch_add(1, Ordering::AcqRel);
 
         self.mark_read_in_range(0, index);
+        self.record(ReaderChange::Joined(self.readers.load(Ordering::Acquire)));
 
         #[cfg(feature = "debug")]
         log::info!("[{}] New reader", index);
@@ -145,16 +369,190 @@ impl<T> MpmcCircularBuffer<T> {
         BufferReader { index }
     }
 
+    /// Creates a log which records each change in the reader count, until the buffer is dropped.
+    pub fn reader_log(&self) -> Arc<ReaderLog> {
+        let log = Arc::new(ReaderLog::new());
+        self.listeners.lock().push(Arc::downgrade(&log));
+        log
+    }
+
+    /// Registers a callback, which runs each time the reader count changes from zero to one.
+    pub fn on_first_reader(&self, callback: Box<dyn Fn() + Send + Sync>) {
+        self.hooks.lock().first.push(callback);
+    }
+
+    /// Registers a callback, which runs each time the reader count changes from one to zero.
+    pub fn on_last_reader(&self, callback: Box<dyn Fn() + Send + Sync>) {
+        self.hooks.lock().last.push(callback);
+    }
+
+    // called while the maintenance lock is held, so changes are recorded (and hooks run) in order
+    fn record(&self, change: ReaderChange) {
+        match change {
+            ReaderChange::Joined(1) => self.hooks.lock().first.iter().for_each(|hook| hook()),
+            ReaderChange::Left(0) => self.hooks.lock().last.iter().for_each(|hook| hook()),
+            _ => {}
+        }
+
+        self.listeners
+            .lock()
+            .retain(|listener| match listener.upgrade() {
+                Some(log) => {
+                    log.push(change);
+                    true
+                }
+                None => false,
+            });
+    }
+
     fn mark_read_in_range(&self, min: usize, max: usize) {
-        for slot in self.buffer.iter() {
+        for slot in self.slots() {
             let readers = self.readers.load(Ordering::Acquire);
             slot.mark_read_in_range(min, max, readers);
         }
     }
 
-    pub(in crate::sync::mpmc_circular_buffer) fn get_slot(&self, id: usize) -> &Slot<T> {
-        let index = id % self.len();
-        &self.buffer[index]
+    /// Resizes the buffer, preserving buffered messages and reader positions.  Tasks waiting on the buffer are woken.
+    ///
+    /// Growing always succeeds.  Unless the buffer overwrites, shrinking fails with the number of unread messages
+    /// if they would not fit in the new capacity.  With `overwrite`, messages which don't fit are discarded, and lagging readers skip ahead.
+    pub fn resize(&self, capacity: usize) -> Result<(), usize> {
+        let capacity = max(2, capacity);
+
+        // the maintenance lock prevents the reader count from changing during the resize
+        let _maint = self.maintenance.lock();
+        let previous = self.slots();
+        let readers = self.readers.load(Ordering::Acquire);
+
+        // sealing waits for the operations in progress on each slot.  once every slot is sealed,
+        // the messages and read counts can't change, and head has been advanced past every claimed slot
+        let indices: Vec<usize> = previous.iter().map(Slot::seal).collect();
+
+        if capacity < previous.len() && !self.overwrite {
+            // readers consume messages in order, so the unread messages are the most recent ids
+            let unread = previous
+                .iter()
+                .zip(&indices)
+                .filter(|(slot, &index)| index > 0 && slot.reads.load(Ordering::Acquire) < readers)
+                .count();
+
+            if unread > capacity {
+                for (slot, &index) in previous.iter().zip(&indices) {
+                    slot.unseal(index);
+                }
+
+                return Err(unread);
+            }
+        }
+
+        let mut resized = Vec::with_capacity(capacity);
+        for _ in 0..capacity {
+            resized.push(Slot::new(0));
+        }
+        let resized = Arc::new(resized.into_boxed_slice());
+
+        // every id in the window fits in a unique slot.  older messages have been read by every reader,
+        // or with `overwrite`, are discarded.  readers behind the oldest kept message skip to it
+        let head = self.head.load(Ordering::Acquire);
+        let oldest = head.saturating_sub(capacity);
+        let floor = head.saturating_sub(capacity.min(previous.len()));
+        self.floor.fetch_max(floor, Ordering::AcqRel);
+
+        for (slot, &index) in previous.iter().zip(&indices) {
+            if index > 0 && index >= oldest {
+                let resized = slot_at(&resized, index);
+                resized.take_from(slot, index);
+
+                // a read which completed while the slot was sealed could not release the message
+                resized.release(index, readers);
+            }
+        }
+
+        self.capacity.store(capacity, Ordering::Release);
+        self.slots
+            .store(Arc::as_ptr(&resized) as *mut _, Ordering::Release);
+        self.arrays.lock().push(resized);
+
+        // waiting tasks are subscribed to the previous slots, and re-subscribe when they poll again
+        for slot in previous.iter() {
+            slot.on_write.notify();
+            slot.on_release.notify();
+        }
+
+        Ok(())
+    }
+}
+
+fn slot_at<T>(slots: &[Slot<T>], id: usize) -> &Slot<T> {
+    &slots[id % slots.len()]
+}
+
+impl<T> Drop for MpmcCircularBuffer<T> {
+    fn drop(&mut self) {
+        for listener in self.listeners.get_mut().drain(..) {
+            if let Some(log) = listener.upgrade() {
+                log.close();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReaderChange {
+    /// A reader was created, and the buffer has the given number of readers
+    Joined(usize),
+    /// A reader was dropped, and the buffer has the given number of readers
+    Left(usize),
+}
+
+/// A queue of changes in the reader count of a buffer.
+#[derive(Debug)]
+pub struct ReaderLog {
+    changes: Mutex<VecDeque<ReaderChange>>,
+    closed: AtomicBool,
+    notify: Notifier,
+}
+
+impl ReaderLog {
+    fn new() -> Self {
+        Self {
+            changes: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+            notify: Notifier::new(),
+        }
+    }
+
+    fn push(&self, change: ReaderChange) {
+        self.changes.lock().push_back(change);
+        self.notify.notify();
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify();
+    }
+
+    /// Returns the next change, or `None` once the buffer has been dropped.
+    pub fn poll_next(&self, cx: &Context<'_>) -> Poll<Option<ReaderChange>> {
+        loop {
+            let guard = self.notify.guard();
+
+            if let Some(change) = self.changes.lock().pop_front() {
+                return Poll::Ready(Some(change));
+            }
+
+            if self.closed.load(Ordering::Acquire) {
+                return Poll::Ready(None);
+            }
+
+            self.notify.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
     }
 }
 
@@ -168,19 +566,90 @@ pub enum TryRead<T> {
     Ready(T),
     /// A value is pending in this slot
     Pending,
+    /// The reader fell behind, and skipped the given number of overwritten messages
+    Lagged(usize),
+    /// The message did not match the reader's filter.  It was marked as read, without being cloned
+    Filtered,
 }
 
 impl BufferReader {
-    pub fn try_read<T>(&mut self, buffer: &MpmcCircularBuffer<T>, cx: &Context<'_>) -> TryRead<T>
+    /// The number of messages which have been written, but not yet read by this reader.
+    pub fn pending<T>(&self, buffer: &MpmcCircularBuffer<T>) -> usize {
+        let head = buffer.head.load(Ordering::Acquire);
+        head.saturating_sub(self.index).min(buffer.len())
+    }
+
+    /// The number of messages which have been written since this reader's position, including any which were overwritten.
+    pub fn lag<T>(&self, buffer: &MpmcCircularBuffer<T>) -> usize {
+        let head = buffer.head.load(Ordering::Acquire);
+        head.saturating_sub(self.index)
+    }
+
+    /// Advances the reader to the head, marking the skipped messages as read.  Returns the number of messages which were skipped.
+    pub fn skip_to_head<T>(&mut self, buffer: &MpmcCircularBuffer<T>) -> usize {
+        let _maint = buffer.maintenance.lock();
+        let head = buffer.head.load(Ordering::Acquire);
+        if head <= self.index {
+            return 0;
+        }
+
+        // slots which were overwritten have newer indices, and are not marked
+        buffer.mark_read_in_range(self.index, head);
+
+        let skipped = head - self.index;
+        self.index = head;
+
+        #[cfg(feature = "debug")]
+        log::debug!("[{}] Reader skipped {} messages to head", head, skipped);
+
+        skipped
+    }
+
+    /// Reads the next message.  If a filter is given, messages which don't match are skipped, and are not cloned.
+    pub fn try_read<T>(
+        &mut self,
+        buffer: &MpmcCircularBuffer<T>,
+        cx: &Context<'_>,
+        filter: Option<&(dyn Fn(&T) -> bool + Send + Sync)>,
+    ) -> TryRead<T>
     where
         T: Clone,
     {
         let index = self.index;
-        let slot = buffer.get_slot(index);
 
-        let try_read = slot.try_read(index, &buffer.readers, cx);
+        let (_slot, try_read) = loop {
+            let slots = buffer.slots();
+
+            // the message at this position was discarded by a resize.  the slot may be empty, or hold a newer message
+            let floor = buffer.floor.load(Ordering::Acquire);
+            if index < floor {
+                self.index = floor;
+
+                #[cfg(feature = "debug")]
+                log::warn!(
+                    "[{}] Reader lagged during resize, skipping to {}",
+                    index,
+                    floor
+                );
+
+                return TryRead::Lagged(floor - index);
+            }
+
+            let slot = slot_at(slots, index);
+            match slot.try_read(index, &buffer.readers, cx, filter) {
+                Some(try_read) => break (slot, try_read),
+                // the slot was sealed by a resize.  the read retries once the new slots are published
+                None => spin_loop(),
+            }
+        };
 
         match &try_read {
+            TryRead::Filtered => {
+                self.index += 1;
+
+                #[cfg(feature = "debug")]
+                log::debug!("[{}] Read filtered in slot {}", index, index % buffer.len());
+            }
             TryRead::Ready(_) => {
                 self.index += 1;
 
@@ -189,13 +658,35 @@ impl BufferReader {
                     "[{}] Read complete in slot {} with {:?} reads of {:?} required",
                     index,
                     index % buffer.len(),
-                    slot.reads,
+                    _slot.reads,
                     &buffer.readers,
                 );
             }
             TryRead::Pending => {
                 #[cfg(feature = "debug")]
-                log::debug!("[{}] Read pending, slot: {:?}", index, slot);
+                log::debug!("[{}] Read pending, slot: {:?}", index, _slot);
+            }
+            TryRead::Lagged(_) => {
+                if !buffer.overwrite {
+                    #[cfg(feature = "debug")]
+                    log::error!(
+                        "[{}] Slot index has advanced past reader position, slot: {:?}",
+                        index,
+                        _slot
+                    );
+
+                    return TryRead::Pending;
+                }
+
+                // skip to the oldest message which may still be in the buffer
+                let head = buffer.head.load(Ordering::Acquire);
+                let next = max(index + 1, head.saturating_sub(buffer.len()));
+                self.index = next;
+
+                #[cfg(feature = "debug")]
+                log::warn!("[{}] Reader lagged, skipping to {}", index, next);
+
+                return TryRead::Lagged(next - index);
             }
         }
 
@@ -209,6 +700,7 @@ impl BufferReader {
 
         let index = self.index;
         buffer.mark_read_in_range(0, index);
+        buffer.record(ReaderChange::Joined(buffer.readers.load(Ordering::Acquire)));
 
         #[cfg(feature = "debug")]
         log::error!("[{}] Cloned reader", index);
@@ -220,16 +712,16 @@ impl BufferReader {
         let _maint = buffer.maintenance.lock();
 
         // first, cancel all reads that this reader has committed
-        buffer
-            .buffer
+        let slots = buffer.slots();
+        slots
             .iter()
             .for_each(|slot| slot.decrement_read_in_range(0, self.index));
 
         // then decrement the reader count
-        buffer.readers.fetch_sub(1, Ordering::AcqRel);
+        let readers = buffer.readers.fetch_sub(1, Ordering::AcqRel) - 1;
 
         // then go through the buffer, and release any slots that should be released
-        for (_id, slot) in buffer.buffer.iter().enumerate() {
+        for (_id, slot) in slots.iter().enumerate() {
             #[cfg(feature = "debug")]
             log::debug!(
                 "[{}] Dropping reader, notifying slot {} with reads {:?} of new reader count {:?}",
@@ -242,6 +734,8 @@ impl BufferReader {
             slot.notify_readers_decreased(&buffer.readers);
         }
 
+        buffer.record(ReaderChange::Left(readers));
+
         #[cfg(feature = "debug")]
         log::error!(
             "[{}] Dropped reader, readers reduced to {:?}",
@@ -257,6 +751,9 @@ pub struct Slot<T> {
     index: AtomicUsize,
     on_write: Notifier,
     on_release: Notifier,
+    // stamped while the data lock is held for writing, and validated while it is held for reading
+    #[cfg(feature = "checksum")]
+    checksum: AtomicUsize,
 }
 
 impl<T> Slot<T> {
@@ -267,6 +764,8 @@ impl<T> Slot<T> {
             index: AtomicUsize::new(index),
             on_write: Notifier::new(),
             on_release: Notifier::new(),
+            #[cfg(feature = "checksum")]
+            checksum: AtomicUsize::new(checksum::empty(index)),
         }
     }
 
@@ -275,6 +774,7 @@ impl<T> Slot<T> {
         index: usize,
         value: T,
         readers: &AtomicUsize,
+        overwrite: bool,
         cx: &Context<'_>,
         on_write: OnWrite,
     ) -> SlotTryWrite<T>
@@ -282,15 +782,23 @@ impl<T> Slot<T> {
         OnWrite: FnOnce(),
     {
         loop {
+            let guard = self.on_release.guard();
             let prev_index = self.index.load(Ordering::Acquire);
 
-            if prev_index >= index {
+            if prev_index == SEALED {
+                return SlotTryWrite::Sealed(value);
+            } else if prev_index >= index {
                 return SlotTryWrite::Written(value);
-            } else if prev_index != 0
+            } else if !overwrite
+                && prev_index != 0
                 && self.reads.load(Ordering::Acquire) < readers.load(Ordering::Acquire)
             {
                 self.on_release.subscribe(cx);
 
+                if guard.is_expired() {
+                    continue;
+                }
+
                 if prev_index < self.index.load(Ordering::Acquire) {
                     #[cfg(feature = "debug")]
                     log::warn!(
@@ -314,7 +822,8 @@ impl<T> Slot<T> {
 
             // lock the data, then update the index
             let mut data = self.data.write();
-            if prev_index != 0
+            if !overwrite
+                && prev_index != 0
                 && self.reads.load(Ordering::Acquire) < readers.load(Ordering::Acquire)
             {
                 #[cfg(feature = "debug")]
@@ -335,13 +844,46 @@ impl<T> Slot<T> {
             }
 
             on_write();
+
+            // the slot must hold the message that was observed before the index was claimed
+            #[cfg(feature = "checksum")]
+            checksum::validate_write(&self.checksum, prev_index, data.is_some());
+
             *data = Some(value);
+            #[cfg(feature = "checksum")]
+            self.checksum
+                .store(checksum::written(index), Ordering::Release);
+
             self.reads.store(0, Ordering::Release);
             self.on_write.notify();
             return SlotTryWrite::Ready;
         }
     }
 
+    /// Seals the slot for a resize, once operations in progress on the slot complete.  Returns the index of the slot.
+    fn seal(&self) -> usize {
+        let _data = self.data.write();
+        self.index.swap(SEALED, Ordering::AcqRel)
+    }
+
+    /// Restores the index of a sealed slot, if the resize fails.
+    fn unseal(&self, index: usize) {
+        let _data = self.data.write();
+        self.index.store(index, Ordering::Release);
+    }
+
+    /// Moves the message with the given id from a slot of the previous buffer, during a resize.
+    fn take_from(&self, previous: &Slot<T>, index: usize) {
+        *self.data.write() = previous.data.write().take();
+        self.index.store(index, Ordering::Release);
+        self.reads
+            .store(previous.reads.load(Ordering::Acquire), Ordering::Release);
+
+        #[cfg(feature = "checksum")]
+        self.checksum
+            .store(previous.checksum.load(Ordering::Acquire), Ordering::Release);
+    }
+
     fn mark_read_in_range(&self, min: usize, max: usize, readers: usize) {
         // prevent the index from changing while maintenance is performed
         let _read = self.data.read();
@@ -358,11 +900,33 @@ impl<T> Slot<T> {
             );
 
             if reads >= readers {
+                drop(_read);
+                self.release(index, readers);
                 self.on_release.notify();
             }
         }
     }
 
+    /// Drops the value once every reader has read it, so large messages are not kept alive until the slot is overwritten.
+    /// Returns true if a value was dropped.
+    fn release(&self, index: usize, readers: usize) -> bool {
+        let mut data = self.data.write();
+        if data.is_none()
+            || self.index.load(Ordering::Acquire) != index
+            || self.reads.load(Ordering::Acquire) < readers
+        {
+            return false;
+        }
+
+        *data = None;
+
+        #[cfg(feature = "checksum")]
+        self.checksum
+            .store(checksum::empty(index), Ordering::Release);
+
+        true
+    }
+
     fn decrement_read_in_range(&self, min: usize, max: usize) {
         // prevent the index from changing while maintenance is performed
         let _read = self.data.read();
@@ -393,9 +957,14 @@ impl<T> Slot<T> {
     }
 
     fn notify_readers_decreased(&self, readers: &AtomicUsize) {
-        if self.reads.load(Ordering::Acquire) >= readers.load(Ordering::Acquire) {
-            self.on_release.notify();
+        let readers = readers.load(Ordering::Acquire);
+        if self.reads.load(Ordering::Acquire) >= readers {
+            self.release(self.index.load(Ordering::Acquire), readers);
         }
+
+        // writers are always notified.  the loads above may observe a stale read count,
+        // and writers re-check the slot when they are woken
+        self.on_release.notify();
     }
 }
 
@@ -403,31 +972,44 @@ impl<T> Slot<T>
 where
     T: Clone,
 {
+    /// Reads the message with the given id.  Returns `None` if the slot was sealed by a resize.
     #[allow(clippy::comparison_chain)]
-    pub fn try_read(&self, index: usize, readers: &AtomicUsize, cx: &Context<'_>) -> TryRead<T> {
+    pub fn try_read(
+        &self,
+        index: usize,
+        readers: &AtomicUsize,
+        cx: &Context<'_>,
+        filter: Option<&(dyn Fn(&T) -> bool + Send + Sync)>,
+    ) -> Option<TryRead<T>> {
         loop {
+            let guard = self.on_write.guard();
             let slot_index = self.index.load(Ordering::Acquire);
-            if slot_index < index {
+            if slot_index == SEALED {
+                return None;
+            } else if slot_index < index {
                 self.on_write.subscribe(cx);
 
                 // if the index has advanced, continue and attempt to read again
-                if self.index.load(Ordering::Acquire) >= index {
+                if guard.is_expired() || self.index.load(Ordering::Acquire) >= index {
                     continue;
                 }
 
-                return TryRead::Pending;
+                return Some(TryRead::Pending);
             } else if slot_index > index {
-                #[cfg(feature = "debug")]
-                log::error!(
-                    "Slot index {} has advanced past reader position {}",
-                    slot_index,
-                    index
-                );
-                return TryRead::Pending;
+                return Some(TryRead::Lagged(slot_index - index));
             }
 
             let data_lock = self.data.read();
 
+            // in overwrite mode, writers do not wait for readers,
+            // so the slot may have been overwritten before the lock was acquired
+            if self.index.load(Ordering::Acquire) != index {
+                continue;
+            }
+
+            #[cfg(feature = "checksum")]
+            checksum::validate_read(&self.checksum, index, data_lock.is_some());
+
             let reads = 1 + self.reads.fetch_add(1, Ordering::AcqRel);
             #[cfg(feature = "debug")]
             log::debug!(
@@ -440,22 +1022,469 @@ where
             // but readers are initialized with index: 1
             // if the slot index was 0, then the above code would have returned TryRead::Pending
             let data_ref = data_lock.as_ref().unwrap();
-            let data_cloned = data_ref.clone();
+            let data_cloned = match filter {
+                Some(filter) if !filter(data_ref) => None,
+                _ => Some(data_ref.clone()),
+            };
+            drop(data_lock);
 
-            if reads >= readers.load(Ordering::Acquire) {
+            let readers = readers.load(Ordering::Acquire);
+            if reads >= readers {
+                self.release(index, readers);
                 self.on_release.notify();
             }
 
-            break TryRead::Ready(data_cloned);
+            break Some(match data_cloned {
+                Some(data) => TryRead::Ready(data),
+                None => TryRead::Filtered,
+            });
         }
     }
 }
 
 impl<T> Debug for Slot<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Slot")
-            .field("reads", &self.reads)
-            .field("index", &self.index)
-            .finish()
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut f = f.debug_struct("Slot");
+        f.field("reads", &self.reads).field("index", &self.index);
+
+        #[cfg(feature = "checksum")]
+        f.field("checksum", &self.checksum);
+
+        f.finish()
+    }
+}
+
+// Slot checksums, enabled by the `checksum` feature.
+// A checksum encodes the slot's message id, and whether the slot holds a message.  Writers stamp the checksum,
+// and release clears it.  A read which finds a released slot (use-after-release), or a write which finds that
+// another writer has replaced the message it claimed (double-write), panics with the slot's state.
+#[cfg(feature = "checksum")]
+mod checksum {
+    use super::{AtomicUsize, Ordering};
+
+    const WRITTEN: usize = 0x5A5A_5A5A;
+    const EMPTY: usize = 0xA5A5_A5A5;
+
+    fn mix(index: usize) -> usize {
+        index.wrapping_mul(0x9E37_79B9)
+    }
+
+    pub fn written(index: usize) -> usize {
+        mix(index) ^ WRITTEN
+    }
+
+    pub fn empty(index: usize) -> usize {
+        mix(index) ^ EMPTY
+    }
+
+    fn describe(checksum: usize, index: usize) -> &'static str {
+        if checksum == written(index) {
+            "written"
+        } else if checksum == empty(index) {
+            "released"
+        } else {
+            "corrupted"
+        }
+    }
+
+    /// Validates the slot holds message `index` when it is read.
+    pub fn validate_read(checksum: &AtomicUsize, index: usize, has_data: bool) {
+        let checksum = checksum.load(Ordering::Acquire);
+        if checksum != written(index) || !has_data {
+            panic!(
+                "slot checksum mismatch on read of message {}: the slot is {} (has data: {}).  This indicates a use-after-release, or a double-write",
+                index,
+                describe(checksum, index),
+                has_data
+            );
+        }
+    }
+
+    /// Validates the slot still holds message `prev_index` (or its release) when it is overwritten.
+    pub fn validate_write(checksum: &AtomicUsize, prev_index: usize, has_data: bool) {
+        let checksum = checksum.load(Ordering::Acquire);
+        let expected = if has_data {
+            written(prev_index)
+        } else {
+            empty(prev_index)
+        };
+
+        if checksum != expected {
+            panic!(
+                "slot checksum mismatch on overwrite of message {}: the slot is {} (has data: {}).  This indicates a double-write",
+                prev_index,
+                describe(checksum, prev_index),
+                has_data
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{MpmcCircularBuffer, Ordering, TryRead, TryWrite};
+    use crate::test::noop_context;
+
+    #[test]
+    fn shrink_slots_releases_retained() {
+        let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+        let mut other = reader.clone_with(&buffer);
+
+        let value = Arc::new(vec![0u8; 16]);
+        let id = match buffer.try_write(value.clone(), &noop_context()) {
+            TryWrite::Ready(id) => id,
+            TryWrite::Pending(_) => panic!("buffer is full"),
+        };
+
+        // the value is retained while a reader has not received it
+        assert!(matches!(
+            reader.try_read(&buffer, &noop_context(), None),
+            TryRead::Ready(_)
+        ));
+        assert_eq!(0, buffer.shrink_slots());
+        assert_eq!(2, Arc::strong_count(&value));
+
+        // the state left when the other reader is dropped concurrently with the read:
+        // the read observed two readers, and the drop observed a stale read count.  neither released the value
+        {
+            let _maint = buffer.maintenance.lock();
+            buffer.readers.fetch_sub(1, Ordering::AcqRel);
+        }
+        assert_eq!(
+            1,
+            super::slot_at(buffer.slots(), id)
+                .reads
+                .load(Ordering::Acquire)
+        );
+        assert_eq!(2, Arc::strong_count(&value));
+
+        assert_eq!(1, buffer.shrink_slots());
+        assert_eq!(1, Arc::strong_count(&value));
+        assert_eq!(0, buffer.shrink_slots());
+
+        // restore the reader count, so the buffer is consistent when the readers are dropped
+        buffer.readers.fetch_add(1, Ordering::AcqRel);
+        other.drop_with(&buffer);
+        reader.drop_with(&buffer);
+    }
+}
+
+#[cfg(all(test, feature = "checksum"))]
+mod checksum_tests {
+    use super::{MpmcCircularBuffer, Ordering, TryRead, TryWrite};
+    use crate::test::noop_context;
+
+    fn write(buffer: &MpmcCircularBuffer<usize>, value: usize) -> usize {
+        match buffer.try_write(value, &noop_context()) {
+            TryWrite::Ready(id) => id,
+            TryWrite::Pending(_) => panic!("buffer is full"),
+        }
+    }
+
+    #[test]
+    fn valid_reads_and_writes() {
+        let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+
+        for i in 0..10 {
+            write(&buffer, i);
+            match reader.try_read(&buffer, &noop_context(), None) {
+                TryRead::Ready(value) => assert_eq!(i, value),
+                _ => panic!("expected a value"),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "on read of message 1")]
+    fn read_after_release() {
+        let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+        let id = write(&buffer, 1);
+
+        // release the message while the reader still needs it
+        {
+            let slot = super::slot_at(buffer.slots(), id);
+            slot.reads.store(1, Ordering::Release);
+            slot.release(id, 1);
+            slot.reads.store(0, Ordering::Release);
+        }
+
+        let _ = reader.try_read(&buffer, &noop_context(), None);
+    }
+
+    #[test]
+    fn shrink_slots_then_overwrite() {
+        let (buffer, _reader) = MpmcCircularBuffer::new(2, false);
+        let id = write(&buffer, 1);
+
+        // the state left by a read which raced with a dropped reader: the message was read by every reader, but retained
+        super::slot_at(buffer.slots(), id)
+            .reads
+            .store(1, Ordering::Release);
+        assert_eq!(1, buffer.shrink_slots());
+
+        // the released slot is stamped empty, so it can be overwritten
+        for i in 2..6 {
+            let id = write(&buffer, i);
+            super::slot_at(buffer.slots(), id)
+                .reads
+                .store(1, Ordering::Release);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "on overwrite of message 1")]
+    fn double_write() {
+        let (buffer, _reader) = MpmcCircularBuffer::new(2, true);
+        let id = write(&buffer, 1);
+
+        // stamp the slot as if another writer had replaced the message without claiming the index
+        super::slot_at(buffer.slots(), id)
+            .checksum
+            .store(super::checksum::written(id + 2), Ordering::Release);
+
+        write(&buffer, 2);
+        write(&buffer, 3);
+    }
+}
+
+#[cfg(all(test, feature = "loom"))]
+mod loom_tests {
+    use core::task::Poll;
+
+    use loom::{sync::Arc, thread};
+
+    use super::{BufferReader, MpmcCircularBuffer, TryRead, TryWrite};
+
+    // bounding preemptions keeps the models tractable.  most concurrency bugs require very few preemptions.
+    // a deeper search can be run with LOOM_MAX_PREEMPTIONS
+    fn model<F>(f: F)
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        let mut builder = loom::model::Builder::new();
+        if builder.preemption_bound.is_none() {
+            builder.preemption_bound = Some(2);
+        }
+
+        builder.check(f);
+    }
+
+    // loom explores every value a load may observe, so the helpers park on the notifiers instead of spinning
+    fn write<T>(buffer: &MpmcCircularBuffer<T>, value: T) {
+        let mut value = Some(value);
+        loom::future::block_on(core::future::poll_fn(|cx| {
+            match buffer.try_write(value.take().unwrap(), &cx.into()) {
+                TryWrite::Ready(_) => Poll::Ready(()),
+                TryWrite::Pending(v) => {
+                    value = Some(v);
+                    Poll::Pending
+                }
+            }
+        }))
+    }
+
+    fn read(reader: &mut BufferReader, buffer: &MpmcCircularBuffer<usize>) -> usize {
+        loom::future::block_on(core::future::poll_fn(|cx| {
+            match reader.try_read(buffer, &cx.into(), None) {
+                TryRead::Ready(value) => Poll::Ready(value),
+                TryRead::Pending => Poll::Pending,
+                TryRead::Lagged(_) => panic!("reader lagged without overwrite"),
+                TryRead::Filtered => unreachable!("loom readers are unfiltered"),
+            }
+        }))
+    }
+
+    #[test]
+    fn write_read() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+
+            let writer = {
+                let buffer = buffer.clone();
+                thread::spawn(move || {
+                    for i in 1..=3 {
+                        write(&buffer, i);
+                    }
+                })
+            };
+
+            for i in 1..=3 {
+                assert_eq!(i, read(&mut reader, &buffer));
+            }
+
+            writer.join().unwrap();
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn filtered_read_releases() {
+        // a message which panics if it is cloned.  filtered messages must be released without a clone
+        struct Rejected;
+
+        impl Clone for Rejected {
+            fn clone(&self) -> Self {
+                panic!("filtered messages are not cloned");
+            }
+        }
+
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(1, false);
+            let buffer = Arc::new(buffer);
+
+            // with a capacity of 1, the second write hangs unless the filtered read releases the slot
+            let writer = {
+                let buffer = buffer.clone();
+                thread::spawn(move || {
+                    write(&buffer, Rejected);
+                    write(&buffer, Rejected);
+                })
+            };
+
+            let reject = |_: &Rejected| false;
+            for _ in 0..2 {
+                loom::future::block_on(core::future::poll_fn(|cx| {
+                    match reader.try_read(&buffer, &cx.into(), Some(&reject)) {
+                        TryRead::Filtered => Poll::Ready(()),
+                        TryRead::Pending => Poll::Pending,
+                        TryRead::Ready(_) => panic!("the filter rejects every message"),
+                        TryRead::Lagged(_) => panic!("reader lagged without overwrite"),
+                    }
+                }));
+            }
+
+            writer.join().unwrap();
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn concurrent_writers() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+
+            let writers: Vec<_> = (1..=2)
+                .map(|i| {
+                    let buffer = buffer.clone();
+                    thread::spawn(move || write(&buffer, i))
+                })
+                .collect();
+
+            let mut values = [read(&mut reader, &buffer), read(&mut reader, &buffer)];
+            values.sort_unstable();
+            assert_eq!([1, 2], values);
+
+            for writer in writers {
+                writer.join().unwrap();
+            }
+
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn clone_reader_during_write() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+
+            let writer = {
+                let buffer = buffer.clone();
+                thread::spawn(move || {
+                    for i in 1..=2 {
+                        write(&buffer, i);
+                    }
+                })
+            };
+
+            // the clone observes the same series of messages as the original
+            let mut clone = reader.clone_with(&buffer);
+            for i in 1..=2 {
+                assert_eq!(i, read(&mut reader, &buffer));
+                assert_eq!(i, read(&mut clone, &buffer));
+            }
+
+            writer.join().unwrap();
+            clone.drop_with(&buffer);
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn drop_reader_during_write() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+            let mut clone = reader.clone_with(&buffer);
+
+            let dropper = {
+                let buffer = buffer.clone();
+                thread::spawn(move || clone.drop_with(&buffer))
+            };
+
+            // the writer would hang if the dropped reader held a slot
+            for i in 1..=3 {
+                write(&buffer, i);
+                assert_eq!(i, read(&mut reader, &buffer));
+            }
+
+            dropper.join().unwrap();
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn resize_during_write() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+
+            let resizer = {
+                let buffer = buffer.clone();
+                thread::spawn(move || assert_eq!(Ok(()), buffer.resize(3)))
+            };
+
+            // each message is written to the previous or the resized slots, and none are lost
+            for i in 1..=2 {
+                write(&buffer, i);
+            }
+
+            resizer.join().unwrap();
+
+            for i in 1..=2 {
+                assert_eq!(i, read(&mut reader, &buffer));
+            }
+
+            reader.drop_with(&buffer);
+        });
+    }
+
+    #[test]
+    fn resize_during_read() {
+        model(|| {
+            let (buffer, mut reader) = MpmcCircularBuffer::new(2, false);
+            let buffer = Arc::new(buffer);
+            write(&buffer, 1);
+            write(&buffer, 2);
+
+            let resizer = {
+                let buffer = buffer.clone();
+                thread::spawn(move || assert_eq!(Ok(()), buffer.resize(3)))
+            };
+
+            // each message is read once, from the previous or the resized slots
+            for i in 1..=2 {
+                assert_eq!(i, read(&mut reader, &buffer));
+            }
+
+            resizer.join().unwrap();
+            write(&buffer, 3);
+            assert_eq!(3, read(&mut reader, &buffer));
+            reader.drop_with(&buffer);
+        });
     }
 }
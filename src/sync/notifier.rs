@@ -1,6 +1,9 @@
+use alloc::vec::Vec;
 use atomic::Ordering;
+use core::task::Waker;
 use crossbeam_queue::SegQueue;
-use std::{sync::atomic::AtomicUsize, task::Waker};
+
+use super::primitive::AtomicUsize;
 
 #[derive(Debug)]
 pub struct Notifier {
@@ -9,6 +12,16 @@ pub struct Notifier {
 }
 
 impl Notifier {
+    #[cfg(not(feature = "loom"))]
+    pub const fn new() -> Self {
+        Self {
+            generation: AtomicUsize::new(0),
+            wakers: SegQueue::new(),
+        }
+    }
+
+    // loom atomics can't be constructed in a const context
+    #[cfg(feature = "loom")]
     pub fn new() -> Self {
         Self {
             generation: AtomicUsize::new(0),
@@ -17,7 +30,8 @@ impl Notifier {
     }
 
     pub fn guard(&self) -> NotificationGuard {
-        let generation = self.generation.load(Ordering::Relaxed);
+        // acquire pairs with the release in `notify`, so the state observed after the guard is taken is at least as new as the generation
+        let generation = self.generation.load(Ordering::Acquire);
 
         NotificationGuard {
             generation,
@@ -46,11 +60,38 @@ impl Notifier {
         }
     }
 
+    /// The number of wakers which are registered, and have not yet been notified.
+    #[cfg(any(feature = "debug", feature = "diagnostics"))]
+    pub fn waiting(&self) -> usize {
+        self.wakers.len()
+    }
+
     pub fn subscribe(&self, cx: &crate::Context<'_>) {
         if let Some(waker) = cx.waker() {
             self.wakers.push(waker.clone());
         }
     }
+
+    /// Removes registered wakers which would wake the same task as `waker`.
+    pub fn unsubscribe(&self, waker: &Waker) {
+        let generation = self.generation.load(Ordering::Acquire);
+
+        let mut retained = Vec::new();
+        while let Some(registered) = self.wakers.pop() {
+            if !registered.will_wake(waker) {
+                retained.push(registered);
+            }
+        }
+
+        for registered in retained.iter() {
+            self.wakers.push(registered.clone());
+        }
+
+        // a notification may have run while the retained wakers were removed from the queue
+        if self.generation.load(Ordering::Acquire) != generation {
+            retained.into_iter().for_each(Waker::wake);
+        }
+    }
 }
 
 pub struct NotificationGuard<'a> {
@@ -60,6 +101,8 @@ pub struct NotificationGuard<'a> {
 
 impl<'a> NotificationGuard<'a> {
     pub fn is_expired(&self) -> bool {
-        self.stored_generation.load(Ordering::Relaxed) != self.generation
+        // a read-modify-write observes the latest generation.  a plain load may observe a stale generation,
+        // and miss a notification which drained the wakers before this task subscribed
+        self.stored_generation.fetch_add(0, Ordering::AcqRel) != self.generation
     }
 }
@@ -0,0 +1,67 @@
+//! Synchronization primitives used by the lock-free structures in this module.
+//!
+//! With the `loom` feature, the primitives are replaced with the `loom` model checker's versions,
+//! and the `loom_tests` modules explore every interleaving of the modeled operations:
+//!
+//! ```text
+//! cargo test --lib --release --features loom loom_tests
+//! ```
+//!
+//! Loom primitives panic outside of `loom::model`, so other tests should not be run with the feature enabled.
+
+#[cfg(not(feature = "loom"))]
+pub(crate) use super::lock::{Mutex, RwLock};
+#[cfg(not(feature = "loom"))]
+pub(crate) use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+
+#[cfg(feature = "loom")]
+pub(crate) use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+#[cfg(feature = "loom")]
+pub(crate) use loom_lock::{Mutex, RwLock};
+
+/// Waits briefly before an operation retries.  Under loom, the thread yields, so the model can run the thread it waits for.
+pub(crate) fn spin_loop() {
+    #[cfg(not(feature = "loom"))]
+    core::hint::spin_loop();
+
+    #[cfg(feature = "loom")]
+    loom::thread::yield_now();
+}
+
+// loom locks return a LockResult, and are wrapped to match the parking_lot API
+#[cfg(feature = "loom")]
+mod loom_lock {
+    #[derive(Debug)]
+    pub(crate) struct Mutex<T>(loom::sync::Mutex<T>);
+
+    impl<T> Mutex<T> {
+        pub fn new(value: T) -> Self {
+            Self(loom::sync::Mutex::new(value))
+        }
+
+        pub fn lock(&self) -> loom::sync::MutexGuard<'_, T> {
+            self.0.lock().unwrap()
+        }
+
+        pub fn get_mut(&mut self) -> loom::sync::MutexGuard<'_, T> {
+            self.lock()
+        }
+    }
+
+    #[derive(Debug)]
+    pub(crate) struct RwLock<T>(loom::sync::RwLock<T>);
+
+    impl<T> RwLock<T> {
+        pub fn new(value: T) -> Self {
+            Self(loom::sync::RwLock::new(value))
+        }
+
+        pub fn read(&self) -> loom::sync::RwLockReadGuard<'_, T> {
+            self.0.read().unwrap()
+        }
+
+        pub fn write(&self) -> loom::sync::RwLockWriteGuard<'_, T> {
+            self.0.write().unwrap()
+        }
+    }
+}
@@ -1,19 +1,53 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+//! An atomic reference count, which can be awaited until it reaches zero.
+//!
+//! The channels use this to track senders and receivers.  It is also useful for drain-tracking,
+//! such as waiting for all in-flight requests to complete before shutdown.
+//!
+//! ```rust
+//! use postage::sync::ref_count::RefCount;
+//! use std::sync::Arc;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let in_flight = Arc::new(RefCount::new(0));
+//!
+//!     in_flight.increment();
+//!     let task_count = in_flight.clone();
+//!     tokio::spawn(async move {
+//!         // ... process a request
+//!         task_count.decrement();
+//!     });
+//!
+//!     in_flight.wait_zero().await;
+//! }
+//! ```
+use core::{sync::atomic::Ordering, task::Poll};
 
+use super::notifier::Notifier;
+use super::primitive::AtomicUsize;
+use crate::Context;
+
+/// An atomic counter.  Tasks can wait for the count to reach zero with `wait_zero`.
 #[derive(Debug)]
 pub struct RefCount {
     count: AtomicUsize,
+    zero: Notifier,
 }
 
+/// The result of `RefCount::decrement`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum TryDecrement {
+    /// The count is still above zero, with the given value.
     Alive(usize),
+    /// The count has reached zero.
     Dead,
 }
 
 impl TryDecrement {
+    /// Panics with the message if the count is still alive.
     #[allow(dead_code)]
     #[track_caller]
-    pub fn expect_dead(&self, message: &str) {
+    pub(crate) fn expect_dead(&self, message: &str) {
         if let Self::Alive(_) = self {
             panic!("TryDecrement unwrapped on an Alive value: {}", message);
         }
@@ -21,20 +55,43 @@ impl TryDecrement {
 }
 
 impl RefCount {
+    /// Creates a counter with the given initial value.
     pub fn new(count: usize) -> Self {
         Self {
             count: AtomicUsize::new(count),
+            zero: Notifier::new(),
         }
     }
 
+    /// The current value of the counter.
+    pub fn get(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// True if the count is above zero.
     pub fn is_alive(&self) -> bool {
         self.count.load(Ordering::Acquire) > 0
     }
 
+    /// Increments the count.
     pub fn increment(&self) {
         self.count.fetch_add(1, Ordering::AcqRel);
     }
 
+    /// Increments the count, if it has not yet reached zero.  Returns false if the count is dead.
+    pub fn try_increment(&self) -> bool {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                if count == 0 {
+                    None
+                } else {
+                    Some(count + 1)
+                }
+            })
+            .is_ok()
+    }
+
+    /// Decrements the count, if it is above zero.  Tasks waiting in `wait_zero` are woken when the count reaches zero.
     pub fn decrement(&self) -> TryDecrement {
         loop {
             let state = self.count.load(Ordering::Acquire);
@@ -48,6 +105,7 @@ impl RefCount {
                     .compare_exchange(state, state - 1, Ordering::AcqRel, Ordering::Relaxed)
             {
                 if state == 1 {
+                    self.zero.notify();
                     return TryDecrement::Dead;
                 } else {
                     return TryDecrement::Alive(state - 1);
@@ -55,4 +113,134 @@ impl RefCount {
             }
         }
     }
+
+    /// Returns `Poll::Ready` if the count is zero.  Otherwise, registers the task to be woken when it reaches zero.
+    pub fn poll_zero(&self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.zero.guard();
+
+            if !self.is_alive() {
+                return Poll::Ready(());
+            }
+
+            self.zero.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
+    }
+
+    /// Waits until the count reaches zero.
+    pub async fn wait_zero(&self) {
+        core::future::poll_fn(|cx| self.poll_zero(&mut cx.into())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::{Context, Poll};
+
+    use futures_test::task::new_count_waker;
+
+    use super::{RefCount, TryDecrement};
+    use crate::test::noop_context;
+
+    #[test]
+    fn increment_decrement() {
+        let count = RefCount::new(1);
+
+        count.increment();
+        assert_eq!(2, count.get());
+        assert_eq!(TryDecrement::Alive(1), count.decrement());
+        assert_eq!(TryDecrement::Dead, count.decrement());
+        assert_eq!(TryDecrement::Dead, count.decrement());
+        assert_eq!(0, count.get());
+    }
+
+    #[test]
+    fn try_increment() {
+        let count = RefCount::new(1);
+
+        assert!(count.try_increment());
+        count.decrement();
+        count.decrement();
+        assert!(!count.try_increment());
+        assert!(!count.is_alive());
+    }
+
+    #[test]
+    fn poll_zero() {
+        let count = RefCount::new(0);
+        assert_eq!(Poll::Ready(()), count.poll_zero(&mut noop_context()));
+
+        count.increment();
+        count.increment();
+
+        let (w1, w1_count) = new_count_waker();
+        let w1_context = Context::from_waker(&w1);
+        assert_eq!(Poll::Pending, count.poll_zero(&mut w1_context.into()));
+
+        count.decrement();
+        assert_eq!(0, w1_count.get());
+
+        count.decrement();
+        assert_eq!(1, w1_count.get());
+        assert_eq!(Poll::Ready(()), count.poll_zero(&mut noop_context()));
+    }
+}
+
+#[cfg(all(test, feature = "loom"))]
+mod loom_tests {
+    use std::task::Poll;
+
+    use futures_test::task::new_count_waker;
+    use loom::{sync::Arc, thread};
+
+    use super::{RefCount, TryDecrement};
+
+    #[test]
+    fn concurrent_decrement() {
+        loom::model(|| {
+            let count = Arc::new(RefCount::new(2));
+
+            let count2 = count.clone();
+            let thread = thread::spawn(move || count2.decrement());
+
+            let local = count.decrement();
+            let remote = thread.join().unwrap();
+
+            // exactly one decrement observes the transition to zero
+            assert!(matches!(
+                (local, remote),
+                (TryDecrement::Alive(1), TryDecrement::Dead)
+                    | (TryDecrement::Dead, TryDecrement::Alive(1))
+            ));
+            assert_eq!(0, count.get());
+        });
+    }
+
+    #[test]
+    fn poll_zero_wakes() {
+        loom::model(|| {
+            let count = Arc::new(RefCount::new(1));
+
+            let count2 = count.clone();
+            let thread = thread::spawn(move || {
+                count2.decrement();
+            });
+
+            let (waker, waker_count) = new_count_waker();
+            let std_cx = std::task::Context::from_waker(&waker);
+            let mut cx: crate::Context<'_> = std_cx.into();
+            let poll = count.poll_zero(&mut cx);
+
+            thread.join().unwrap();
+
+            // either the count was observed at zero, or the decrement woke the task
+            assert!(poll == Poll::Ready(()) || waker_count.get() > 0);
+        });
+    }
 }
@@ -0,0 +1,101 @@
+use std::{
+    sync::{Condvar, Mutex, OnceLock},
+    task::Waker,
+    time::Instant,
+};
+
+// A single background thread which wakes tasks at a deadline.
+// The thread is started lazily, the first time a wakeup is registered.
+
+struct Timer {
+    entries: Mutex<Vec<(Instant, Waker)>>,
+    condvar: Condvar,
+}
+
+fn timer() -> &'static Timer {
+    static TIMER: OnceLock<&'static Timer> = OnceLock::new();
+
+    TIMER.get_or_init(|| {
+        let timer: &'static Timer = Box::leak(Box::new(Timer {
+            entries: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        }));
+
+        std::thread::Builder::new()
+            .name("postage-timer".to_string())
+            .spawn(move || timer.run())
+            .expect("failed to spawn the postage timer thread");
+
+        timer
+    })
+}
+
+/// The current instant.  With the `sim` feature, this is the virtual time of the thread's `sim::Clock`, if one is installed.
+pub fn now() -> Instant {
+    #[cfg(feature = "sim")]
+    if let Some(now) = crate::sim::now() {
+        return now;
+    }
+
+    Instant::now()
+}
+
+/// Wakes the waker at, or shortly after, the given deadline.
+pub fn wake_at(deadline: Instant, waker: Waker) {
+    #[cfg(feature = "sim")]
+    let waker = match crate::sim::wake_at(deadline, waker) {
+        Ok(()) => return,
+        Err(waker) => waker,
+    };
+
+    if deadline <= Instant::now() {
+        waker.wake();
+        return;
+    }
+
+    let timer = timer();
+    let mut entries = timer.entries.lock().unwrap();
+
+    if let Some(entry) = entries
+        .iter_mut()
+        .find(|(_, existing)| existing.will_wake(&waker))
+    {
+        if deadline < entry.0 {
+            entry.0 = deadline;
+        }
+    } else {
+        entries.push((deadline, waker));
+    }
+
+    timer.condvar.notify_one();
+}
+
+impl Timer {
+    fn run(&self) {
+        let mut entries = self.entries.lock().unwrap();
+
+        loop {
+            let now = Instant::now();
+
+            let mut i = 0;
+            while i < entries.len() {
+                if entries[i].0 <= now {
+                    let (_, waker) = entries.swap_remove(i);
+                    waker.wake();
+                } else {
+                    i += 1;
+                }
+            }
+
+            entries = match entries.iter().map(|(deadline, _)| *deadline).min() {
+                Some(deadline) => {
+                    self.condvar
+                        .wait_timeout(entries, deadline.saturating_duration_since(now))
+                        .unwrap()
+                        .0
+                }
+                None => self.condvar.wait(entries).unwrap(),
+            };
+        }
+    }
+}
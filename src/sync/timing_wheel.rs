@@ -0,0 +1,226 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// A hashed timing wheel.  Entries are stored in the slot for their deadline tick,
+// and are moved to the expired queue when the wheel advances past that tick.
+// Entries are released in deadline order, and entries which share a deadline in insertion order.
+
+const SLOTS: usize = 256;
+const TICK: Duration = Duration::from_millis(1);
+
+struct Entry<T> {
+    tick: u64,
+    deadline: Instant,
+    value: T,
+}
+
+pub struct TimingWheel<T> {
+    start: Instant,
+    current_tick: u64,
+    slots: Vec<VecDeque<Entry<T>>>,
+    expired: VecDeque<T>,
+}
+
+impl<T> TimingWheel<T> {
+    pub fn new() -> Self {
+        let mut slots = Vec::with_capacity(SLOTS);
+        for _ in 0..SLOTS {
+            slots.push(VecDeque::new());
+        }
+
+        Self {
+            start: crate::sync::timer::now(),
+            current_tick: 0,
+            slots,
+            expired: VecDeque::new(),
+        }
+    }
+
+    pub fn insert(&mut self, deadline: Instant, value: T) {
+        let tick = self.tick_ceil(deadline);
+
+        if tick <= self.current_tick {
+            self.expired.push_back(value);
+            return;
+        }
+
+        let slot = (tick % SLOTS as u64) as usize;
+        self.slots[slot].push_back(Entry {
+            tick,
+            deadline,
+            value,
+        });
+    }
+
+    /// Removes the next value whose deadline is at or before `now`.
+    pub fn pop_expired(&mut self, now: Instant) -> Option<T> {
+        self.advance(now);
+        self.expired.pop_front()
+    }
+
+    /// Removes the next value whose deadline is at or before `now`, and which matches the filter.
+    /// Expired values which do not match are retained, in order.
+    pub fn pop_expired_matching(&mut self, now: Instant, filter: impl Fn(&T) -> bool) -> Option<T> {
+        self.advance(now);
+        let index = self.expired.iter().position(filter)?;
+        self.expired.remove(index)
+    }
+
+    /// True if expired values are waiting to be popped.
+    pub fn has_expired(&self) -> bool {
+        !self.expired.is_empty()
+    }
+
+    /// The earliest deadline of the values which have not yet expired, or `None` if there are no such values.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.slots
+            .iter()
+            .flat_map(|slot| slot.iter())
+            .map(|entry| entry.deadline)
+            .min()
+    }
+
+    fn advance(&mut self, now: Instant) {
+        let target = self.tick_floor(now);
+        if target <= self.current_tick {
+            return;
+        }
+
+        // each slot only needs to be visited once, even if the wheel has turned several times
+        let ticks = (target - self.current_tick).min(SLOTS as u64);
+        let mut batch = Vec::new();
+        for offset in 1..=ticks {
+            let slot = ((self.current_tick + offset) % SLOTS as u64) as usize;
+            let entries = std::mem::take(&mut self.slots[slot]);
+
+            for entry in entries {
+                if entry.tick <= target {
+                    batch.push(entry);
+                } else {
+                    self.slots[slot].push_back(entry);
+                }
+            }
+        }
+
+        // if the wheel has turned, the slots are not visited in deadline order.
+        // the sort is stable, so entries which share a deadline keep their insertion order
+        batch.sort_by_key(|entry| entry.deadline);
+        self.expired
+            .extend(batch.into_iter().map(|entry| entry.value));
+
+        self.current_tick = target;
+    }
+
+    fn tick_floor(&self, instant: Instant) -> u64 {
+        let elapsed = instant.saturating_duration_since(self.start);
+        (elapsed.as_nanos() / TICK.as_nanos()) as u64
+    }
+
+    fn tick_ceil(&self, instant: Instant) -> u64 {
+        let elapsed = instant.saturating_duration_since(self.start);
+        elapsed.as_nanos().div_ceil(TICK.as_nanos()) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::TimingWheel;
+
+    #[test]
+    fn expires_in_deadline_order() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        wheel.insert(now + Duration::from_millis(20), 2);
+        wheel.insert(now + Duration::from_millis(10), 1);
+
+        assert_eq!(None, wheel.pop_expired(now));
+        assert_eq!(None, wheel.pop_expired(now + Duration::from_millis(5)));
+        assert_eq!(Some(1), wheel.pop_expired(now + Duration::from_millis(15)));
+        assert_eq!(None, wheel.pop_expired(now + Duration::from_millis(15)));
+        assert_eq!(Some(2), wheel.pop_expired(now + Duration::from_millis(25)));
+        assert_eq!(None, wheel.next_deadline());
+    }
+
+    #[test]
+    fn past_deadline_is_expired() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        wheel.pop_expired(now + Duration::from_millis(10));
+        wheel.insert(now, 1);
+
+        assert_eq!(Some(1), wheel.pop_expired(now + Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn wraps_around_the_wheel() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        wheel.insert(now + Duration::from_millis(1000), 2);
+        wheel.insert(now + Duration::from_millis(10), 1);
+
+        assert_eq!(Some(1), wheel.pop_expired(now + Duration::from_millis(500)));
+        assert_eq!(None, wheel.pop_expired(now + Duration::from_millis(500)));
+        assert_eq!(
+            Some(2),
+            wheel.pop_expired(now + Duration::from_millis(2000))
+        );
+    }
+
+    #[test]
+    fn turns_expire_in_deadline_order() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        // the slots are visited in the order 3, 2, 1, 4
+        wheel.insert(now + Duration::from_millis(100), 1);
+        wheel.insert(now + Duration::from_millis(300), 2);
+        wheel.insert(now + Duration::from_millis(520), 3);
+        wheel.insert(now + Duration::from_millis(700), 4);
+
+        let later = now + Duration::from_millis(1000);
+        assert_eq!(Some(1), wheel.pop_expired(later));
+        assert_eq!(Some(2), wheel.pop_expired(later));
+        assert_eq!(Some(3), wheel.pop_expired(later));
+        assert_eq!(Some(4), wheel.pop_expired(later));
+        assert_eq!(None, wheel.pop_expired(later));
+    }
+
+    #[test]
+    fn pop_expired_matching() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        wheel.insert(now + Duration::from_millis(10), 1);
+        wheel.insert(now + Duration::from_millis(10), 2);
+        wheel.insert(now + Duration::from_millis(10), 3);
+
+        let later = now + Duration::from_millis(20);
+        assert_eq!(Some(2), wheel.pop_expired_matching(later, |v| v % 2 == 0));
+        assert_eq!(None, wheel.pop_expired_matching(later, |v| v % 2 == 0));
+        assert!(wheel.has_expired());
+        assert_eq!(Some(1), wheel.pop_expired(later));
+        assert_eq!(Some(3), wheel.pop_expired(later));
+        assert!(!wheel.has_expired());
+    }
+
+    #[test]
+    fn next_deadline() {
+        let mut wheel = TimingWheel::new();
+        let now = Instant::now();
+
+        assert_eq!(None, wheel.next_deadline());
+
+        let deadline = now + Duration::from_millis(10);
+        wheel.insert(now + Duration::from_millis(20), 2);
+        wheel.insert(deadline, 1);
+
+        assert_eq!(Some(deadline), wheel.next_deadline());
+    }
+}
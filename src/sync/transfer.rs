@@ -1,6 +1,11 @@
+use core::task::Poll;
+
 use atomic::{Atomic, Ordering};
 
-use crate::{stream::PollRecv, Context};
+use crate::{
+    stream::{ClosedReason, PollRecv},
+    Context,
+};
 
 use super::{
     notifier::Notifier,
@@ -11,6 +16,7 @@ use super::{
 enum State {
     Alive,
     Dead,
+    Poisoned,
 }
 
 pub struct Transfer<T: Sized> {
@@ -18,6 +24,7 @@ pub struct Transfer<T: Sized> {
     receiver: Atomic<State>,
     value: OneshotCell<T>,
     notify_rx: Notifier,
+    notify_tx: Notifier,
 }
 
 impl<T> Transfer<T> {
@@ -27,6 +34,7 @@ impl<T> Transfer<T> {
             receiver: Atomic::new(State::Alive),
             value: OneshotCell::new(),
             notify_rx: Notifier::new(),
+            notify_tx: Notifier::new(),
         }
     }
 
@@ -47,11 +55,17 @@ impl<T> Transfer<T> {
             match self.value.try_recv() {
                 Ok(value) => return PollRecv::Ready(value),
                 Err(TryRecvError::Pending) => {
-                    if let State::Dead = self.sender.load(Ordering::Acquire) {
+                    let reason = match self.sender.load(Ordering::Acquire) {
+                        State::Alive => None,
+                        State::Dead => Some(ClosedReason::SendersDropped),
+                        State::Poisoned => Some(ClosedReason::Poisoned),
+                    };
+
+                    if let Some(reason) = reason {
                         return match self.value.try_recv() {
                             Ok(v) => PollRecv::Ready(v),
-                            Err(TryRecvError::Pending) => PollRecv::Closed,
-                            Err(TryRecvError::Closed) => PollRecv::Closed,
+                            Err(TryRecvError::Pending) => PollRecv::Closed(reason),
+                            Err(TryRecvError::Closed) => PollRecv::Closed(ClosedReason::Closed),
                         };
                     }
 
@@ -63,17 +77,47 @@ impl<T> Transfer<T> {
 
                     return PollRecv::Pending;
                 }
-                Err(TryRecvError::Closed) => return PollRecv::Closed,
+                Err(TryRecvError::Closed) => return PollRecv::Closed(ClosedReason::Closed),
             }
         }
     }
 
     pub fn sender_disconnect(&self) {
-        self.sender.store(State::Dead, Ordering::Release);
+        let state = if super::panicking() {
+            State::Poisoned
+        } else {
+            State::Dead
+        };
+
+        self.sender.store(state, Ordering::Release);
         self.notify_rx.notify();
     }
 
     pub fn receiver_disconnect(&self) {
         self.receiver.store(State::Dead, Ordering::Release);
+        self.notify_tx.notify();
+    }
+
+    pub fn is_receiver_closed(&self) -> bool {
+        matches!(self.receiver.load(Ordering::Acquire), State::Dead)
+    }
+
+    /// Returns `Poll::Ready` once the receiver has been dropped.  Otherwise, registers the sender to be woken.
+    pub fn poll_receiver_closed(&self, cx: &Context<'_>) -> Poll<()> {
+        loop {
+            let guard = self.notify_tx.guard();
+
+            if self.is_receiver_closed() {
+                return Poll::Ready(());
+            }
+
+            self.notify_tx.subscribe(cx);
+
+            if guard.is_expired() {
+                continue;
+            }
+
+            return Poll::Pending;
+        }
     }
 }
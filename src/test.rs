@@ -1,20 +1,74 @@
+//! Utilities for unit testing code which is driven by postage channels, without an async runtime.
+//!
+//! Sinks and streams can be polled directly with `noop_context()`, and the results checked with
+//! `assert_ready!`, `assert_pending!`, and `assert_closed!`.  `PollCounter` counts the polls of a wrapped sink or stream.
+//! Timers can be driven by a virtual clock with the `sim` module.
+//!
+//! Requires the `test-util` feature.
+//!
+//! ```rust
+//! use postage::{assert_pending, assert_ready, mpsc, prelude::*, test::noop_context};
+//! use std::pin::Pin;
+//!
+//! let (mut tx, mut rx) = mpsc::channel(4);
+//! let mut cx = noop_context();
+//!
+//! assert_pending!(Pin::new(&mut rx).poll_recv(&mut cx));
+//! assert_ready!(Pin::new(&mut tx).poll_send(&mut cx, 1usize));
+//! assert_eq!(1, assert_ready!(Pin::new(&mut rx).poll_recv(&mut cx)));
+//! ```
+
+mod assertions;
+mod poll_counter;
+
+#[doc(hidden)]
+pub use assertions::PollOutcome;
+pub use poll_counter::{PollCount, PollCounter};
+
+use crate::Context;
+
+/// Returns a context without a waker, which can be used to poll sinks and streams directly.
+pub fn noop_context() -> crate::Context<'static> {
+    Context::empty()
+}
+
+// the remaining utilities are used by the crate's own tests
+
+#[cfg(test)]
 pub mod sink;
+#[cfg(test)]
 pub mod stream;
+#[cfg(test)]
 mod test_messages;
+#[cfg(test)]
+pub mod timer;
+#[cfg(test)]
 pub use test_messages::*;
 
-use crate::Context;
+#[cfg(test)]
 use std::time::Duration;
 
+#[cfg(test)]
 pub const CHANNEL_TEST_ITERATIONS: usize = 2000;
+#[cfg(test)]
 pub const CHANNEL_TEST_SENDERS: usize = 10;
+#[cfg(test)]
 pub const CHANNEL_TEST_RECEIVERS: usize = 5;
+#[cfg(test)]
 pub const TEST_TIMEOUT: Duration = Duration::from_secs(100);
 
-pub fn noop_context() -> crate::Context<'static> {
-    Context::empty()
-}
-
+#[cfg(test)]
 pub fn panic_context() -> crate::Context<'static> {
     futures_test::task::panic_context().into()
 }
+
+/// Drops the value on a thread which is panicking.  If it is the last sender of a channel, the channel is poisoned.
+#[cfg(all(test, feature = "std"))]
+pub fn drop_panicking<T: Send + 'static>(value: T) {
+    let join = std::thread::spawn(move || {
+        let _value = value;
+        panic!("drop_panicking");
+    });
+
+    assert!(join.join().is_err());
+}
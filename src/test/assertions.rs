@@ -0,0 +1,133 @@
+use core::fmt::Debug;
+
+use crate::{sink::PollSend, stream::PollRecv};
+
+/// The result of polling a sink or stream.  Used by the `assert_ready!`, `assert_pending!`, and `assert_closed!` macros.
+#[doc(hidden)]
+pub trait PollOutcome: Debug + Sized {
+    type Ready;
+
+    fn into_ready(self) -> Result<Self::Ready, Self>;
+    fn is_pending(&self) -> bool;
+    fn is_closed(&self) -> bool;
+}
+
+impl<T> PollOutcome for PollRecv<T>
+where
+    T: Debug,
+{
+    type Ready = T;
+
+    fn into_ready(self) -> Result<T, Self> {
+        match self {
+            PollRecv::Ready(value) => Ok(value),
+            poll => Err(poll),
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        matches!(self, PollRecv::Pending)
+    }
+
+    fn is_closed(&self) -> bool {
+        matches!(self, PollRecv::Closed(_))
+    }
+}
+
+impl<T> PollOutcome for PollSend<T>
+where
+    T: Debug,
+{
+    type Ready = ();
+
+    fn into_ready(self) -> Result<(), Self> {
+        match self {
+            PollSend::Ready => Ok(()),
+            poll => Err(poll),
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        matches!(self, PollSend::Pending(_))
+    }
+
+    fn is_closed(&self) -> bool {
+        matches!(self, PollSend::Rejected(_))
+    }
+}
+
+/// Asserts that a `PollRecv` or `PollSend` is ready.  Evaluates to the received message, or `()` for a send.
+#[macro_export]
+macro_rules! assert_ready {
+    ($poll:expr) => {
+        match $crate::test::PollOutcome::into_ready($poll) {
+            Ok(value) => value,
+            Err(poll) => panic!("expected Ready, found {:?}", poll),
+        }
+    };
+}
+
+/// Asserts that a `PollRecv` or `PollSend` is pending.
+#[macro_export]
+macro_rules! assert_pending {
+    ($poll:expr) => {
+        let poll = $poll;
+        if !$crate::test::PollOutcome::is_pending(&poll) {
+            panic!("expected Pending, found {:?}", poll);
+        }
+    };
+}
+
+/// Asserts that a `PollRecv` is closed, or a `PollSend` was rejected.
+#[macro_export]
+macro_rules! assert_closed {
+    ($poll:expr) => {
+        let poll = $poll;
+        if !$crate::test::PollOutcome::is_closed(&poll) {
+            panic!("expected Closed, found {:?}", poll);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{sink::Sink, stream::Stream, test::noop_context};
+
+    #[test]
+    fn stream_assertions() {
+        let (mut tx, mut rx) = crate::mpsc::channel(4);
+
+        let mut cx = noop_context();
+        crate::assert_pending!(Pin::new(&mut rx).poll_recv(&mut cx));
+
+        crate::assert_ready!(Pin::new(&mut tx).poll_send(&mut cx, 1usize));
+        assert_eq!(
+            1,
+            crate::assert_ready!(Pin::new(&mut rx).poll_recv(&mut cx))
+        );
+
+        drop(tx);
+        crate::assert_closed!(Pin::new(&mut rx).poll_recv(&mut cx));
+    }
+
+    #[test]
+    fn sink_assertions() {
+        let (mut tx, rx) = crate::mpsc::channel(1);
+
+        let mut cx = noop_context();
+        crate::assert_ready!(Pin::new(&mut tx).poll_send(&mut cx, 1usize));
+        crate::assert_pending!(Pin::new(&mut tx).poll_send(&mut cx, 2));
+
+        drop(rx);
+        crate::assert_closed!(Pin::new(&mut tx).poll_send(&mut cx, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected Ready, found Pending")]
+    fn ready_panics() {
+        let (_tx, mut rx) = crate::mpsc::channel::<usize>(4);
+        crate::assert_ready!(Pin::new(&mut rx).poll_recv(&mut noop_context()));
+    }
+}
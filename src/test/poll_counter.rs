@@ -0,0 +1,143 @@
+use alloc::sync::Arc;
+use core::{
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use pin_project::pin_project;
+
+use crate::{
+    sink::{PollSend, Sink},
+    stream::{PollRecv, Stream},
+    Context,
+};
+
+/// Wraps a sink or stream, and counts the number of times it is polled.
+///
+/// Useful for asserting that a state machine does not busy-poll a channel.
+/// The count can be read with `polls`, or with a `PollCount` handle after the wrapper has been moved.
+#[pin_project]
+pub struct PollCounter<S> {
+    #[pin]
+    inner: S,
+    count: PollCount,
+}
+
+impl<S> PollCounter<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            count: PollCount::default(),
+        }
+    }
+
+    /// The number of times the sink or stream has been polled.
+    pub fn polls(&self) -> usize {
+        self.count.get()
+    }
+
+    /// Returns a handle to the counter, which can be read after the wrapper is moved into the code under test.
+    pub fn count(&self) -> PollCount {
+        self.count.clone()
+    }
+
+    /// Returns the wrapped sink or stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for PollCounter<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>) -> PollRecv<Self::Item> {
+        let this = self.project();
+        this.count.increment();
+        this.inner.poll_recv(cx)
+    }
+
+    fn cancel_recv(self: Pin<&mut Self>) {
+        self.project().inner.cancel_recv()
+    }
+}
+
+impl<S> Sink for PollCounter<S>
+where
+    S: Sink,
+{
+    type Item = S::Item;
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        value: Self::Item,
+    ) -> PollSend<Self::Item> {
+        let this = self.project();
+        this.count.increment();
+        this.inner.poll_send(cx, value)
+    }
+
+    fn cancel_send(self: Pin<&mut Self>) {
+        self.project().inner.cancel_send()
+    }
+}
+
+/// A handle to the count of a `PollCounter`.  Created by `PollCounter::count`.
+#[derive(Debug, Clone, Default)]
+pub struct PollCount {
+    polls: Arc<AtomicUsize>,
+}
+
+impl PollCount {
+    /// The number of times the sink or stream has been polled.
+    pub fn get(&self) -> usize {
+        self.polls.load(Ordering::Relaxed)
+    }
+
+    fn increment(&self) {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+
+    use crate::{
+        sink::{PollSend, Sink},
+        stream::{PollRecv, Stream},
+        test::noop_context,
+    };
+
+    use super::PollCounter;
+
+    #[test]
+    fn counts_stream_polls() {
+        let (_tx, rx) = crate::mpsc::channel::<usize>(4);
+        let mut rx = PollCounter::new(rx);
+        let count = rx.count();
+
+        let mut cx = noop_context();
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+        assert_eq!(PollRecv::Pending, Pin::new(&mut rx).poll_recv(&mut cx));
+
+        assert_eq!(2, rx.polls());
+        assert_eq!(2, count.get());
+    }
+
+    #[test]
+    fn counts_sink_polls() {
+        let (tx, _rx) = crate::mpsc::channel(4);
+        let mut tx = PollCounter::new(tx);
+
+        let mut cx = noop_context();
+        assert_eq!(
+            PollSend::Ready,
+            Pin::new(&mut tx).poll_send(&mut cx, 1usize)
+        );
+        assert_eq!(1, tx.polls());
+    }
+}
@@ -3,7 +3,7 @@
 use pin_project::pin_project;
 use std::marker::PhantomData;
 
-use crate::stream::{PollRecv, Stream};
+use crate::stream::{ClosedReason, PollRecv, Stream};
 
 pub fn ready<T>(value: T) -> impl Stream<Item = T>
 where
@@ -71,7 +71,7 @@ impl<T> Stream for ClosedStream<T> {
         self: std::pin::Pin<&mut Self>,
         _cx: &mut crate::Context<'_>,
     ) -> crate::stream::PollRecv<Self::Item> {
-        PollRecv::Closed
+        PollRecv::Closed(ClosedReason::Closed)
     }
 }
 #[pin_project]
@@ -101,7 +101,7 @@ where
         let this = self.project();
         match this.iter.next() {
             Some(value) => PollRecv::Ready(value),
-            None => PollRecv::Closed,
+            None => PollRecv::Closed(ClosedReason::Closed),
         }
     }
 }
@@ -137,7 +137,7 @@ where
         let this = self.project();
         match this.iter.next() {
             Some(poll) => poll,
-            None => PollRecv::Closed,
+            None => PollRecv::Closed(ClosedReason::Closed),
         }
     }
 }
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+use std::{
+    future::{pending, ready, Pending, Ready},
+    time::Duration,
+};
+
+use crate::time::Timer;
+
+/// A timer with deadlines which have already elapsed.
+pub struct ExpiredTimer;
+
+impl Timer for ExpiredTimer {
+    type Sleep = Ready<()>;
+
+    fn sleep(&self, _duration: Duration) -> Self::Sleep {
+        ready(())
+    }
+}
+
+/// A timer with deadlines which never elapse.
+pub struct NeverTimer;
+
+impl Timer for NeverTimer {
+    type Sleep = Pending<()>;
+
+    fn sleep(&self, _duration: Duration) -> Self::Sleep {
+        pending()
+    }
+}
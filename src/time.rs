@@ -0,0 +1,176 @@
+//! Runtime-independent timers, used by `Sink::send_timeout` and `Stream::recv_timeout`.
+//!
+//! Postage does not depend on an async runtime.  Operations which need a deadline accept a `Timer`,
+//! which constructs a sleep future:
+//! - `ThreadTimer` runs on a background thread, and works with any executor.  Requires the `timer` feature.
+//! - `TokioTimer` uses `tokio::time::sleep`.  Requires the `tokio-timer` feature.
+//! - `AsyncStdTimer` uses `async_std::task::sleep`.  Requires the `async-std-timer` feature.
+//!
+//! Other runtimes can be supported by implementing `Timer`.
+use core::{future::Future, pin::Pin, time::Duration};
+
+#[cfg(feature = "timer")]
+use core::task::{Context, Poll};
+#[cfg(feature = "timer")]
+use std::time::Instant;
+
+/// Constructs futures which complete after a duration has elapsed.
+pub trait Timer {
+    /// The future returned by `sleep`
+    type Sleep: Future<Output = ()>;
+
+    /// Returns a future which completes once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+/// Polls an optional sleep, which is in progress if it is `Some`.  Returns true, and clears the sleep, once it has elapsed.
+///
+/// Combinators poll sleeps with the postage context, which may not have a waker (for example, in `try_recv`).
+pub(crate) fn poll_sleep<Sleep>(mut sleep: Pin<&mut Option<Sleep>>, cx: &crate::Context<'_>) -> bool
+where
+    Sleep: Future<Output = ()>,
+{
+    let elapsed = match sleep.as_mut().as_pin_mut() {
+        Some(sleep) => {
+            // without a waker, the sleep must not store one
+            let noop = crate::context::noop_waker();
+            let waker = cx.waker().unwrap_or(&noop);
+            let mut sleep_cx = core::task::Context::from_waker(waker);
+            sleep.poll(&mut sleep_cx).is_ready()
+        }
+        None => return true,
+    };
+
+    if elapsed {
+        sleep.set(None);
+    }
+
+    elapsed
+}
+
+/// A timer driven by a postage background thread, which works with any executor.
+///
+/// Requires the `timer` feature.
+#[cfg(feature = "timer")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ThreadTimer;
+
+#[cfg(feature = "timer")]
+impl Timer for ThreadTimer {
+    type Sleep = ThreadSleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        ThreadSleep {
+            deadline: crate::sync::timer::now() + duration,
+        }
+    }
+}
+
+/// A future returned by `ThreadTimer::sleep`.
+#[cfg(feature = "timer")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ThreadSleep {
+    deadline: Instant,
+}
+
+#[cfg(feature = "timer")]
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.deadline <= crate::sync::timer::now() {
+            return Poll::Ready(());
+        }
+
+        crate::sync::timer::wake_at(self.deadline, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// A timer which uses `tokio::time::sleep`.  Must be used within a tokio runtime.
+///
+/// Requires the `tokio-timer` feature.
+#[cfg(feature = "tokio-timer")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio-timer")]
+impl Timer for TokioTimer {
+    type Sleep = tokio::time::Sleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// A timer which uses `async_std::task::sleep`.
+///
+/// Requires the `async-std-timer` feature.
+#[cfg(feature = "async-std-timer")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct AsyncStdTimer;
+
+#[cfg(feature = "async-std-timer")]
+impl Timer for AsyncStdTimer {
+    type Sleep = core::pin::Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "timer", feature = "blocking"))]
+    #[test]
+    fn thread_timer() {
+        use super::{ThreadTimer, Timer};
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        crate::blocking::block_on(ThreadTimer.sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[cfg(feature = "tokio-timer")]
+    #[tokio::test]
+    async fn tokio_timer() {
+        use super::TokioTimer;
+        use crate::{
+            mpsc, sink::SendTimeoutError, sink::Sink, stream::RecvTimeoutError, stream::Stream,
+        };
+        use std::time::Duration;
+
+        let (mut tx, mut rx) = mpsc::channel(1);
+        let timeout = Duration::from_millis(10);
+
+        assert_eq!(Ok(()), tx.send_timeout(1usize, timeout, TokioTimer).await);
+        assert_eq!(
+            Err(SendTimeoutError::Timeout(2usize)),
+            tx.send_timeout(2usize, timeout, TokioTimer).await
+        );
+
+        assert_eq!(Ok(1), rx.recv_timeout(timeout, TokioTimer).await);
+        assert_eq!(
+            Err(RecvTimeoutError::Timeout),
+            rx.recv_timeout(timeout, TokioTimer).await
+        );
+    }
+
+    #[cfg(feature = "async-std-timer")]
+    #[async_std::test]
+    async fn async_std_timer() {
+        use super::AsyncStdTimer;
+        use crate::{stream::RecvTimeoutError, stream::Stream};
+        use std::time::Duration;
+
+        let (_tx, mut rx) = crate::mpsc::channel::<usize>(1);
+
+        assert_eq!(
+            Err(RecvTimeoutError::Timeout),
+            rx.recv_timeout(Duration::from_millis(10), AsyncStdTimer)
+                .await
+        );
+    }
+}